@@ -51,6 +51,20 @@ fn benchmarks(c: &mut Criterion) {
             b.iter(|| bench.run_manual_flatten())
         });
 
+    c.benchmark_group("integrate")
+        .bench_function("iter", |b| {
+            let mut bench = integrate::Benchmark::new();
+            b.iter(|| bench.run())
+        })
+        .bench_function("for_each", |b| {
+            let mut bench = integrate::Benchmark::new();
+            b.iter(|| bench.run_for_each())
+        })
+        .bench_function("get", |b| {
+            let mut bench = integrate::Benchmark::new();
+            b.iter(|| bench.run_get())
+        });
+
     c.benchmark_group("heavy_compute")
         .bench_function("par", |b| {
             let mut bench = heavy_compute::Benchmark::new();
@@ -61,6 +75,16 @@ fn benchmarks(c: &mut Criterion) {
             b.iter(|| bench.run_seq())
         });
 
+    c.benchmark_group("many_archetypes")
+        .bench_function("prepare_seq", |b| {
+            let mut bench = many_archetypes::Benchmark::new();
+            b.iter(|| bench.run_seq())
+        })
+        .bench_function("prepare_par", |b| {
+            let mut bench = many_archetypes::Benchmark::new();
+            b.iter(|| bench.run_par())
+        });
+
     c.benchmark_group("schedule")
         .bench_function("inner_par", |b| {
             let mut bench = schedule_inner_par::Benchmark::new();
@@ -0,0 +1,63 @@
+use core::iter::repeat;
+
+use flax::*;
+use glam::*;
+
+component! {
+    position: Vec3,
+    velocity: Vec3,
+    acceleration: Vec3,
+}
+
+const DT: f32 = 0.02;
+
+pub struct Benchmark {
+    world: World,
+    ids: Vec<Entity>,
+}
+
+impl Benchmark {
+    pub fn new() -> Self {
+        let mut world = World::new();
+
+        let mut batch = BatchSpawn::new(10_000);
+        batch.set(position(), repeat(Vec3::ZERO)).unwrap();
+        batch.set(velocity(), repeat(Vec3::X)).unwrap();
+        batch.set(acceleration(), repeat(Vec3::NEG_Y)).unwrap();
+
+        let ids = batch.spawn(&mut world);
+        Self { world, ids }
+    }
+
+    /// Iterates chunks of contiguous slots
+    pub fn run(&mut self) {
+        for (pos, vel, &acc) in
+            &mut Query::new((position().as_mut(), velocity().as_mut(), acceleration()))
+                .borrow(&self.world)
+        {
+            *vel += acc * DT;
+            *pos += *vel * DT;
+        }
+    }
+
+    pub fn run_for_each(&mut self) {
+        Query::new((position().as_mut(), velocity().as_mut(), acceleration()))
+            .borrow(&self.world)
+            .for_each(|(pos, vel, &acc)| {
+                *vel += acc * DT;
+                *pos += *vel * DT;
+            })
+    }
+
+    /// Looks up each entity individually, for comparison with chunked iteration
+    pub fn run_get(&mut self) {
+        let mut query = Query::new((position().as_mut(), velocity().as_mut(), acceleration()));
+        let mut borrow = query.borrow(&self.world);
+
+        for &id in &self.ids {
+            let (pos, vel, &acc) = borrow.get(id).unwrap();
+            *vel += acc * DT;
+            *pos += *vel * DT;
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use flax::{components::child_of, *};
+
+component! {
+    data: f32,
+}
+
+pub struct Benchmark {
+    world: World,
+    query: Query<Mutable<f32>>,
+}
+
+impl Benchmark {
+    pub fn new() -> Self {
+        let mut world = World::default();
+
+        // Each distinct relation target creates a new archetype
+        for _ in 0..5000 {
+            let parent = world.spawn();
+            Entity::builder()
+                .set(data(), 0.0)
+                .set_default(child_of(parent))
+                .spawn(&mut world);
+        }
+
+        Self {
+            world,
+            query: Query::new(data().as_mut()),
+        }
+    }
+
+    pub fn run_seq(&mut self) {
+        let mut borrow = self.query.borrow(&self.world);
+        for data in borrow.iter() {
+            *data *= 2.0;
+        }
+    }
+
+    pub fn run_par(&mut self) {
+        let mut borrow = self.query.borrow(&self.world);
+        borrow.par_prepare();
+        for data in borrow.iter() {
+            *data *= 2.0;
+        }
+    }
+}
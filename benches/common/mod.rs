@@ -5,6 +5,8 @@ pub mod despawn_children;
 pub mod dfs;
 pub mod frag_iter;
 pub mod heavy_compute;
+pub mod integrate;
+pub mod many_archetypes;
 pub mod schedule;
 pub mod schedule_inner_par;
 #[cfg(feature = "serde")]
@@ -7,7 +7,7 @@ use quote::{format_ident, quote};
 use syn::{
     bracketed, parse::Parse, punctuated::Punctuated, spanned::Spanned, Attribute, DataStruct,
     DeriveInput, Error, Field, GenericParam, Generics, Ident, ImplGenerics, Index, Lifetime,
-    LifetimeParam, Result, Token, Type, TypeGenerics, TypeParam, Visibility,
+    LifetimeParam, Result, Token, Type, TypeGenerics, TypeParam, Visibility, WherePredicate,
 };
 
 /// ```rust,ignore
@@ -103,10 +103,12 @@ fn derive_fetch_struct(params: &Params) -> TokenStream {
         field_names,
         field_types,
         attrs,
+        predicates,
         ..
     } = params;
 
     let item_ty = params.q_ty();
+    let item_where = params.where_clause();
     let item_impl = params.q_impl();
     let item_msg = format!("The item returned by {fetch_name}");
 
@@ -137,7 +139,7 @@ fn derive_fetch_struct(params: &Params) -> TokenStream {
     quote! {
         #[doc = #item_msg]
         #extras
-        #vis struct #item_name #q_generics {
+        #vis struct #item_name #q_generics #item_where {
             #item_fields
         }
 
@@ -146,13 +148,15 @@ fn derive_fetch_struct(params: &Params) -> TokenStream {
         // }
 
         #[automatically_derived]
-        impl #item_impl #crate_name::fetch::FetchItem<'q> for #fetch_name #fetch_ty {
+        impl #item_impl #crate_name::fetch::FetchItem<'q> for #fetch_name #fetch_ty
+            where #(#predicates,)*
+        {
             type Item = #item_name #item_ty;
         }
 
         #[automatically_derived]
         impl #fetch_impl #crate_name::Fetch<'w> for #fetch_name #fetch_ty
-            where #(#field_types: 'static,)*
+            where #(#predicates,)* #(#field_types: 'static,)*
         {
             const MUTABLE: bool = #(<#field_types as #crate_name::Fetch <'w>>::MUTABLE)||*;
 
@@ -205,6 +209,7 @@ fn derive_union(params: &Params) -> TokenStream {
         crate_name,
         fields,
         prepared_name,
+        predicates,
         ..
     } = params;
 
@@ -218,7 +223,7 @@ fn derive_union(params: &Params) -> TokenStream {
 
     quote! {
         #[automatically_derived]
-        impl #impl_generics #crate_name::fetch::UnionFilter for #prepared_name #prep_ty where #prepared_name #prep_ty: #crate_name::fetch::PreparedFetch<'q> {
+        impl #impl_generics #crate_name::fetch::UnionFilter for #prepared_name #prep_ty where #prepared_name #prep_ty: #crate_name::fetch::PreparedFetch<'q>, #(#predicates,)* {
             const HAS_UNION_FILTER: bool = #(<<#filter_types as #crate_name::fetch::Fetch<'w>>::Prepared as #crate_name::fetch::PreparedFetch<'q>>::HAS_FILTER)&&*;
 
             unsafe fn filter_union(&mut self, slots: #crate_name::archetype::Slice) -> #crate_name::archetype::Slice {
@@ -236,6 +241,7 @@ fn derive_transform(params: &Params) -> Result<TokenStream> {
         fields,
         fetch_name,
         attrs,
+        predicates,
         ..
     } = params;
 
@@ -243,24 +249,22 @@ fn derive_transform(params: &Params) -> Result<TokenStream> {
         return Ok(quote! {});
     }
 
-    // Replace all the fields with generics to allow transforming into different types
+    // Replace all the fields with generics to allow transforming into different types.
+    //
+    // Ignored fields are also made generic, as their types may refer to the generics of the
+    // input struct.
     let ty_generics = ('A'..='Z')
         .zip(fields)
-        .filter(|(_, v)| !v.attrs.ignore)
         .map(|(c, _)| format_ident!("{}", c))
         .map(|v| GenericParam::Type(TypeParam::from(v)))
         .collect_vec();
 
     let transformed_name = format_ident!("{fetch_name}Transformed");
-    use quote::ToTokens;
+    let (impl_generics, fetch_ty, _) = params.generics.split_for_impl();
 
     let transformed_struct = {
         let fields = ('A'..='Z').zip(fields).map(|(c, field)| {
-            let ty = if field.attrs.ignore {
-                field.ty.to_token_stream()
-            } else {
-                format_ident!("{}", c).to_token_stream()
-            };
+            let ty = format_ident!("{}", c);
 
             let vis = field.vis;
             let ident = field.ident;
@@ -301,18 +305,24 @@ fn derive_transform(params: &Params) -> Result<TokenStream> {
 
             let types = fields
                 .iter()
-                .filter_map(|field| {
+                .map(|field| {
+                    let ty = field.ty;
                     if field.attrs.ignore {
-                        None
+                        quote! { #ty }
                     } else {
-                        let ty = field.ty;
-                        Some(quote! {
+                        quote! {
                             <#ty as #trait_name>::Output
-                        })
+                        }
                     }
                 })
                 .collect_vec();
 
+            let transformed_types = fields
+                .iter()
+                .filter(|field| !field.attrs.ignore)
+                .map(|field| field.ty)
+                .collect_vec();
+
             let initializers = fields
                 .iter()
                 .map(|field| {
@@ -332,7 +342,11 @@ fn derive_transform(params: &Params) -> Result<TokenStream> {
 
             quote! {
                 #[automatically_derived]
-                impl #trait_name for #fetch_name
+                impl #impl_generics #trait_name for #fetch_name #fetch_ty
+                    where
+                        #(#predicates,)*
+                        #(#transformed_types: #trait_name,)*
+                        #(<#transformed_types as #trait_name>::Output: for<'x> #crate_name::fetch::Fetch<'x>,)*
                 {
                     type Output = #crate_name::filter::Union<#transformed_name<#(#types,)*>>;
                     fn transform_fetch(self, method: #method) -> Self::Output {
@@ -369,10 +383,12 @@ fn derive_prepared_struct(params: &Params) -> TokenStream {
         field_names,
         field_types,
         w_generics,
+        predicates,
         ..
     } = params;
 
     let msg = format!("The prepared fetch for {fetch_name}");
+    let prep_where = params.where_clause();
 
     let prep_impl = params.wq_impl();
     let prep_ty = params.w_ty();
@@ -383,13 +399,13 @@ fn derive_prepared_struct(params: &Params) -> TokenStream {
 
     quote! {
         #[doc = #msg]
-        #vis struct #prepared_name #w_generics {
+        #vis struct #prepared_name #w_generics #prep_where {
             #(#field_names: <#field_types as #crate_name::Fetch <'w>>::Prepared,)*
         }
 
         #[automatically_derived]
         impl #prep_impl #crate_name::fetch::PreparedFetch<'q> for #prepared_name #prep_ty
-            where #(#field_types: 'static,)*
+            where #(#predicates,)* #(#field_types: 'static,)*
         {
             type Item = #item_name #item_ty;
             type Chunk = (#(<<#field_types as #crate_name::fetch::Fetch<'w>>::Prepared as #crate_name::fetch::PreparedFetch<'q>>::Chunk,)*);
@@ -561,6 +577,10 @@ struct Params<'a> {
     q_generics: Generics,
     wq_generics: Generics,
 
+    /// The where clause predicates of the input struct, which are propagated to the generated
+    /// structs and impls
+    predicates: Vec<&'a WherePredicate>,
+
     fields: Vec<ParsedField<'a>>,
     field_names: Vec<&'a Ident>,
     field_types: Vec<&'a Type>,
@@ -602,6 +622,12 @@ impl<'a> Params<'a> {
             crate_name,
             vis,
             generics: &input.generics,
+            predicates: input
+                .generics
+                .where_clause
+                .iter()
+                .flat_map(|v| &v.predicates)
+                .collect(),
             fields,
             field_names,
             field_types,
@@ -622,6 +648,16 @@ impl<'a> Params<'a> {
         })
     }
 
+    /// Returns the where clause for a generated struct
+    fn where_clause(&self) -> TokenStream {
+        let predicates = &self.predicates;
+        if predicates.is_empty() {
+            quote! {}
+        } else {
+            quote! { where #(#predicates),* }
+        }
+    }
+
     fn q_impl(&self) -> ImplGenerics {
         self.q_generics.split_for_impl().0
     }
@@ -63,6 +63,11 @@ impl BatchSpawn {
         Ok(self)
     }
 
+    /// Returns true if the batch contains the component
+    pub(crate) fn has_key(&self, key: ComponentKey) -> bool {
+        self.storage.contains_key(&key)
+    }
+
     /// Inserts a storage directly
     pub(crate) fn append(&mut self, storage: Storage) -> Result<()> {
         let desc = storage.desc();
@@ -4,6 +4,7 @@ use core::{
 };
 
 use alloc::vec::Vec;
+use itertools::Itertools;
 
 use super::{Remainder, Slice, Slot};
 
@@ -388,6 +389,22 @@ impl ChangeList {
     pub fn as_slice(&self) -> &[Change] {
         self.inner.as_slice()
     }
+
+    /// Returns the changes which overlap `slots`, in ascending order
+    pub fn overlapping(&self, slots: Slice) -> impl Iterator<Item = &Change> + '_ {
+        self.inner.iter().filter(move |v| v.slice.overlaps(slots))
+    }
+
+    /// Returns the slices which changed after `tick`, in ascending order.
+    ///
+    /// Adjacent slices are merged, regardless of the tick they changed at.
+    pub fn since(&self, tick: u32) -> impl Iterator<Item = Slice> + '_ {
+        self.inner
+            .iter()
+            .filter(move |v| v.tick > tick)
+            .map(|v| v.slice)
+            .coalesce(|a, b| a.union(&b).ok_or((a, b)))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
@@ -464,7 +481,9 @@ impl Change {
 ///
 ///
 /// The changes are always stored in a non-overlapping ascending order.
-pub(crate) struct Changes {
+///
+/// See: [`Archetype::changes`](crate::archetype::Archetype::changes)
+pub struct Changes {
     map: [ChangeList; 3],
     track_modified: AtomicBool,
 }
@@ -477,11 +496,27 @@ impl Changes {
         }
     }
 
+    /// Returns the changes of the specified kind
     #[inline]
-    pub(crate) fn get(&self, kind: ChangeKind) -> &ChangeList {
+    pub fn get(&self, kind: ChangeKind) -> &ChangeList {
         &self.map[kind as usize]
     }
 
+    /// Returns the merged slices of entities which were modified after `tick`.
+    ///
+    /// This includes the entities the component was added to. Modifications are only tracked once
+    /// they are observed, so this starts tracking modifications of the component if it was not
+    /// already, and only modifications made after the first call are included.
+    pub fn modified_since(&self, tick: u32) -> impl Iterator<Item = Slice> + '_ {
+        self.set_track_modified();
+        self.get(ChangeKind::Modified).since(tick)
+    }
+
+    /// Returns the merged slices of entities which the component was added to after `tick`
+    pub fn inserted_since(&self, tick: u32) -> impl Iterator<Item = Slice> + '_ {
+        self.get(ChangeKind::Added).since(tick)
+    }
+
     #[inline]
     pub(crate) fn set_added(&mut self, change: Change) -> &mut Self {
         self.map[ChangeKind::Added as usize].set(change);
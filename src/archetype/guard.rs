@@ -1,14 +1,15 @@
 use core::{
     fmt::Debug,
+    marker::PhantomData,
     ops::{Deref, DerefMut},
     ptr::NonNull,
 };
 
 use atomic_refcell::{AtomicRef, AtomicRefMut};
 
-use crate::{component::ComponentValue, Entity};
+use crate::{component::ComponentValue, Entity, World};
 
-use super::{CellData, Changes, Slice, Slot};
+use super::{Cell, CellData, Changes, Slice, Slot};
 
 /// Type safe abstraction over a borrowed cell data
 pub(crate) struct CellMutGuard<'a, T: ?Sized> {
@@ -54,6 +55,12 @@ impl<'a, T: ?Sized> CellMutGuard<'a, T> {
         self.storage
     }
 
+    /// Access the value without recording any changes
+    #[inline]
+    pub(crate) fn into_inner(mut self) -> AtomicRefMut<'a, T> {
+        AtomicRefMut::map(self.data, |_| unsafe { self.storage.as_mut() })
+    }
+
     pub(crate) fn get(&self) -> &T {
         unsafe { self.storage.as_ref() }
     }
@@ -166,3 +173,54 @@ impl<'a, T> DerefMut for RefMut<'a, T> {
         self.guard.get_mut()
     }
 }
+
+/// A mutable handle to an entity's component which only records a change when explicitly
+/// written to.
+///
+/// See: [`World::get_mut_lazy`]
+pub struct LazyRefMut<'a, T> {
+    world: &'a World,
+    cell: &'a Cell,
+    id: Entity,
+    slot: Slot,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T: ComponentValue> LazyRefMut<'a, T> {
+    /// **Note**: `cell` must be of type `T`
+    pub(crate) fn new(world: &'a World, cell: &'a Cell, id: Entity, slot: Slot) -> Self {
+        Self {
+            world,
+            cell,
+            id,
+            slot,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Acquire a shared reference to the current value without triggering a change
+    pub fn read(&self) -> AtomicRef<'a, T> {
+        // Type is guaranteed by the constructor
+        unsafe { self.cell.get(self.slot).unwrap() }
+    }
+
+    /// Acquire a mutable reference to the current value.
+    ///
+    /// Triggers a change when dereferenced mutably
+    pub fn write(&self) -> RefMut<'a, T> {
+        self.cell
+            .get_mut(self.id, self.slot, self.world.advance_change_tick())
+            .unwrap()
+    }
+
+    /// Returns the entity
+    pub fn id(&self) -> Entity {
+        self.id
+    }
+}
+
+impl<'a, T: ComponentValue + Debug> Debug for LazyRefMut<'a, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.read().fmt(f)
+    }
+}
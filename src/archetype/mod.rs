@@ -6,7 +6,7 @@ use alloc::{
 };
 use core::{fmt::Debug, mem};
 
-use atomic_refcell::{AtomicRef, AtomicRefCell, BorrowError, BorrowMutError};
+use atomic_refcell::{AtomicRef, AtomicRefCell, AtomicRefMut, BorrowError, BorrowMutError};
 use itertools::Itertools;
 
 use crate::{
@@ -88,10 +88,62 @@ impl ArchetypeInfo {
     }
 }
 
+/// Fragmentation metrics of the world after compaction.
+///
+/// See: [`World::compact`](crate::World::compact)
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FragmentationInfo {
+    pub(crate) pruned: usize,
+    pub(crate) archetypes: usize,
+    pub(crate) entities: usize,
+    pub(crate) smallest: usize,
+    pub(crate) released: usize,
+}
+
+impl FragmentationInfo {
+    /// Returns the number of empty archetypes which were removed
+    pub fn pruned(&self) -> usize {
+        self.pruned
+    }
+
+    /// Returns the number of archetypes which contain at least one entity
+    pub fn archetypes(&self) -> usize {
+        self.archetypes
+    }
+
+    /// Returns the total number of entities in the world
+    pub fn entities(&self) -> usize {
+        self.entities
+    }
+
+    /// Returns the number of entities in the least populated non-empty archetype
+    pub fn smallest(&self) -> usize {
+        self.smallest
+    }
+
+    /// Returns the number of bytes of unused storage capacity which were released
+    pub fn released(&self) -> usize {
+        self.released
+    }
+
+    /// Returns the average number of entities per non-empty archetype.
+    ///
+    /// A low value means that queries iterate many small chunks.
+    pub fn entities_per_archetype(&self) -> f32 {
+        if self.archetypes == 0 {
+            0.0
+        } else {
+            self.entities as f32 / self.archetypes as f32
+        }
+    }
+}
+
 pub(crate) struct CellData {
     pub(crate) storage: Storage,
     pub(crate) changes: Changes,
     subscribers: Vec<Arc<dyn EventSubscriber>>,
+    /// Modified slots which are yet to be notified to the subscribers
+    deferred: Option<ChangeList>,
     pub(crate) key: ComponentKey,
 }
 
@@ -103,6 +155,12 @@ impl CellData {
         self.changes
             .set_modified_if_tracking(Change::new(slots, change_tick));
 
+        if let Some(deferred) = &mut self.deferred {
+            // The tick is not used, and allows adjacent slots to be merged
+            deferred.set(Change::new(slots, 0));
+            return;
+        }
+
         let event = EventData {
             ids,
             slots,
@@ -114,6 +172,20 @@ impl CellData {
         }
     }
 
+    /// Invokes subscribers for the specified entities and slots having been written to without
+    /// changing the value. The change ticks are not updated.
+    pub(crate) fn set_touched(&self, ids: &[Entity], slots: Slice) {
+        let event = EventData {
+            ids,
+            slots,
+            key: self.key,
+        };
+
+        for handler in self.subscribers.iter() {
+            handler.on_touched(&event)
+        }
+    }
+
     /// Sets the specified entities and slots as modified and invokes subscribers
     /// **Note**: `ids` must be the slice of entities pointed to by `slice`
     pub(crate) fn set_added(&mut self, ids: &[Entity], slots: Slice, change_tick: u32) {
@@ -157,6 +229,7 @@ impl Cell {
                 storage: Storage::new(desc),
                 changes: Changes::new(),
                 subscribers: Vec::new(),
+                deferred: None,
                 key: desc.key,
             }),
             desc,
@@ -180,6 +253,14 @@ impl Cell {
             dst.changes.set_slot(kind, dst_slot, v.tick);
         });
 
+        if let Some(deferred) = &mut data.deferred {
+            deferred.swap_remove_with(slot, last, |v| {
+                if let Some(dst) = &mut dst.deferred {
+                    dst.set_slot(dst_slot, v.tick);
+                }
+            });
+        }
+
         // Do not notify of removal, since the component is still intact, but in another archetype
     }
 
@@ -200,6 +281,17 @@ impl Cell {
                 b.set(change);
             })
         });
+
+        if let Some(deferred) = &mut data.deferred {
+            deferred.inner.drain(..).for_each(|mut change| {
+                change.slice.start += dst_start;
+                change.slice.end += dst_start;
+
+                if let Some(dst) = &mut dst.deferred {
+                    dst.set(change);
+                }
+            })
+        }
     }
 
     /// Move a slot out of the cell by swapping with the last
@@ -210,6 +302,10 @@ impl Cell {
 
         data.storage.swap_remove(slot, |p| on_move(self.desc, p));
         data.changes.swap_remove(slot, last, |_, _| {});
+
+        if let Some(deferred) = &mut data.deferred {
+            deferred.swap_remove_with(slot, last, |_| {});
+        }
     }
 
     /// Silently clears (and drops) all components and changes.
@@ -218,6 +314,10 @@ impl Cell {
 
         data.storage.clear();
         data.changes.clear();
+
+        if let Some(deferred) = &mut data.deferred {
+            deferred.inner.clear();
+        }
     }
 
     /// Drain the values in the cell.
@@ -226,6 +326,10 @@ impl Cell {
         let storage = mem::replace(&mut data.storage, Storage::new(self.desc));
         data.changes.clear();
 
+        if let Some(deferred) = &mut data.deferred {
+            deferred.inner.clear();
+        }
+
         storage
     }
 
@@ -459,6 +563,18 @@ impl Archetype {
             .get_mut(self.entities[slot], slot, tick)
     }
 
+    /// Get a component from the entity at `slot` without recording a modification
+    pub(crate) fn get_mut_silent<T: ComponentValue>(
+        &self,
+        slot: Slot,
+        component: Component<T>,
+    ) -> Option<AtomicRefMut<'_, T>> {
+        let guard = self.cell(component.key())?.borrow_mut::<T>();
+        guard
+            .filter_map(|v| v.get_mut(slot))
+            .map(|v| v.into_inner())
+    }
+
     /// Get a component from the entity at `slot`
     pub(crate) fn try_get_mut<T: ComponentValue>(
         &self,
@@ -795,12 +911,27 @@ impl Archetype {
     /// Does nothing if the remaining capacity < additional.
     /// len remains unchanged, as does the internal order
     pub fn reserve(&mut self, additional: usize) {
+        self.entities.reserve(additional);
         for cell in &mut *self.cells {
             let data = cell.data.get_mut();
             data.storage.reserve(additional);
         }
     }
 
+    /// Releases the unused capacity of the entities and component storages, returning the number
+    /// of bytes released
+    pub(crate) fn shrink_to_fit(&mut self) -> usize {
+        let mut released =
+            (self.entities.capacity() - self.entities.len()) * mem::size_of::<Entity>();
+        self.entities.shrink_to_fit();
+
+        for cell in &mut *self.cells {
+            released += cell.data.get_mut().storage.shrink_to_fit();
+        }
+
+        released
+    }
+
     /// Returns the entity at `slot`
     pub fn entity(&self, slot: Slot) -> Option<Entity> {
         self.entities.get(slot).copied()
@@ -879,6 +1010,39 @@ impl Archetype {
         self.cell(key).map(|v| v.desc)
     }
 
+    /// Defers notifying subscribers of modifications until [`Self::flush_modified`], which
+    /// coalesces the modifications of each component
+    pub(crate) fn defer_modified(&mut self) {
+        for cell in &mut *self.cells {
+            cell.data
+                .get_mut()
+                .deferred
+                .get_or_insert_with(Default::default);
+        }
+    }
+
+    /// Notifies the subscribers of all deferred modifications, coalesced per component
+    pub(crate) fn flush_modified(&mut self) {
+        for cell in &mut *self.cells {
+            let data = cell.data.get_mut();
+            let Some(deferred) = data.deferred.take() else {
+                continue;
+            };
+
+            for change in deferred.iter() {
+                let event = EventData {
+                    ids: &self.entities[change.slice.as_range()],
+                    slots: change.slice,
+                    key: data.key,
+                };
+
+                for handler in data.subscribers.iter() {
+                    handler.on_modified(&event)
+                }
+            }
+        }
+    }
+
     /// Add a new subscriber. The subscriber must be interested in this archetype
     pub(crate) fn add_handler(&mut self, s: Arc<dyn EventSubscriber>) {
         // For component changes
@@ -922,6 +1086,17 @@ impl Archetype {
     pub fn components(&self) -> &BTreeMap<ComponentKey, usize> {
         &self.components
     }
+
+    /// Borrows the change ticks of a component in the archetype.
+    ///
+    /// Returns `None` if the archetype does not have the component.
+    ///
+    /// # Panics
+    /// If the component is borrowed mutably.
+    pub fn changes(&self, component: ComponentKey) -> Option<AtomicRef<'_, Changes>> {
+        let data = self.cell(component)?.data.borrow();
+        Some(AtomicRef::map(data, |v| &v.changes))
+    }
 }
 
 impl Drop for Archetype {
@@ -110,6 +110,43 @@ impl Storage {
         self.data = data
     }
 
+    /// Shrinks the capacity of the storage to its length, returning the number of bytes released
+    pub(crate) fn shrink_to_fit(&mut self) -> usize {
+        let old_cap = self.cap;
+        if self.len == old_cap {
+            return 0;
+        }
+
+        // Handle zst
+        if self.desc.size() == 0 {
+            self.cap = self.len;
+            return 0;
+        }
+
+        let old_layout =
+            Layout::from_size_align(self.desc.size() * old_cap, self.desc.align()).unwrap();
+
+        if self.len == 0 {
+            unsafe { dealloc(self.as_ptr(), old_layout) }
+            self.data = (self.desc.vtable.dangling)();
+            self.cap = 0;
+            return old_layout.size();
+        }
+
+        let new_layout =
+            Layout::from_size_align(self.desc.size() * self.len, self.desc.align()).unwrap();
+
+        let ptr = unsafe { realloc(self.as_ptr(), old_layout, new_layout.size()) };
+
+        self.data = match NonNull::new(ptr) {
+            Some(v) => v,
+            None => handle_alloc_error(new_layout),
+        };
+        self.cap = self.len;
+
+        old_layout.size() - new_layout.size()
+    }
+
     pub fn swap_remove(&mut self, slot: Slot, on_move: impl FnOnce(*mut u8)) {
         if slot >= self.len() {
             panic!("Index out of bounds")
@@ -134,6 +171,16 @@ impl Storage {
         self.data.as_ptr()
     }
 
+    #[inline(always)]
+    pub(crate) fn at(&self, slot: Slot) -> Option<*const u8> {
+        if slot >= self.len {
+            None
+        } else {
+            // Safety: `slot` is within the allocation
+            Some(unsafe { self.data.as_ptr().add(self.desc.size() * slot) })
+        }
+    }
+
     #[inline(always)]
     pub(crate) unsafe fn at_mut(&mut self, slot: Slot) -> Option<*mut u8> {
         if slot >= self.len {
@@ -18,6 +18,8 @@ pub(crate) struct Archetypes {
     // These trickle down to the archetypes
     subscribers: Vec<Arc<dyn EventSubscriber>>,
     pub(crate) index: ArchetypeIndex,
+    /// Modification events are deferred for new archetypes
+    pub(crate) defer_modified: bool,
 }
 
 impl Archetypes {
@@ -36,6 +38,7 @@ impl Archetypes {
             reserved,
             subscribers: Vec::new(),
             index: ArchetypeIndex::new(),
+            defer_modified: false,
         }
     }
 
@@ -49,6 +52,10 @@ impl Archetypes {
         }
     }
 
+    pub(crate) fn try_get(&self, arch_id: ArchetypeId) -> Option<&Archetype> {
+        self.inner.get(arch_id)
+    }
+
     #[track_caller]
     pub fn get_mut(&mut self, arch_id: ArchetypeId) -> &mut Archetype {
         let arch = self.inner.get_mut(arch_id).expect("Invalid archetype");
@@ -166,6 +173,10 @@ impl Archetypes {
                         Archetype::new(arch_components)
                     };
 
+                    if self.defer_modified {
+                        new.defer_modified();
+                    }
+
                     // Insert the appropriate subscribers
                     for s in &self.subscribers {
                         if s.matches_arch(&new) {
@@ -250,6 +261,10 @@ impl Archetypes {
         self.subscribers.push(subscriber)
     }
 
+    pub(crate) fn subscribers(&self) -> &[Arc<dyn EventSubscriber>] {
+        &self.subscribers
+    }
+
     pub(crate) fn gen(&self) -> u32 {
         self.gen
     }
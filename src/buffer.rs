@@ -253,6 +253,10 @@ impl ComponentBuffer {
         self.entries.contains_key(&component.key())
     }
 
+    pub(crate) fn has_key(&self, key: ComponentKey) -> bool {
+        self.entries.contains_key(&key)
+    }
+
     /// Returns the components in the buffer
     pub fn components(&self) -> impl Iterator<Item = &ComponentDesc> {
         self.entries.values().map(|v| &v.0)
@@ -356,6 +360,80 @@ impl ComponentBuffer {
     }
 }
 
+/// A statically known group of components which can be inserted together.
+///
+/// Writing all components to a buffer at once allows the destination archetype to be resolved
+/// once, rather than once per component.
+///
+/// Implemented for `(Component<T>, T)` pairs and tuples of bundles.
+///
+/// ```rust
+/// # use flax::{*, buffer::{Bundle, ComponentBuffer}};
+/// # use glam::*;
+/// component! {
+///     position: Vec3,
+///     rotation: Quat,
+///     scale: Vec3,
+/// }
+///
+/// struct TransformBundle {
+///     position: Vec3,
+///     rotation: Quat,
+///     scale: Vec3,
+/// }
+///
+/// impl Bundle for TransformBundle {
+///     fn write(self, buffer: &mut ComponentBuffer) {
+///         (
+///             (position(), self.position),
+///             (rotation(), self.rotation),
+///             (scale(), self.scale),
+///         )
+///             .write(buffer)
+///     }
+/// }
+///
+/// let mut world = World::new();
+/// let id = Entity::builder()
+///     .set_bundle(TransformBundle {
+///         position: Vec3::X,
+///         rotation: Quat::IDENTITY,
+///         scale: Vec3::ONE,
+///     })
+///     .spawn(&mut world);
+///
+/// assert_eq!(world.get(id, position()).as_deref(), Ok(&Vec3::X));
+/// ```
+pub trait Bundle {
+    /// Write the components of the bundle to the buffer
+    fn write(self, buffer: &mut ComponentBuffer);
+}
+
+impl<T: ComponentValue> Bundle for (Component<T>, T) {
+    fn write(self, buffer: &mut ComponentBuffer) {
+        buffer.set(self.0, self.1);
+    }
+}
+
+macro_rules! tuple_impl {
+    ($($idx: tt => $ty: ident),*) => {
+        impl<$($ty: Bundle,)*> Bundle for ($($ty,)*) {
+            fn write(self, buffer: &mut ComponentBuffer) {
+                $(self.$idx.write(buffer);)*
+            }
+        }
+    };
+}
+
+tuple_impl! { 0 => A }
+tuple_impl! { 0 => A, 1 => B }
+tuple_impl! { 0 => A, 1 => B, 2 => C }
+tuple_impl! { 0 => A, 1 => B, 2 => C, 3 => D }
+tuple_impl! { 0 => A, 1 => B, 2 => C, 3 => D, 4 => E }
+tuple_impl! { 0 => A, 1 => B, 2 => C, 3 => D, 4 => E, 5 => F }
+tuple_impl! { 0 => A, 1 => B, 2 => C, 3 => D, 4 => E, 5 => F, 6 => H }
+tuple_impl! { 0 => A, 1 => B, 2 => C, 3 => D, 4 => E, 5 => F, 6 => H, 7 => I }
+
 pub(crate) struct ComponentBufferIter<'a> {
     entries: &'a mut BTreeMap<ComponentKey, (ComponentDesc, Offset)>,
     storage: &'a mut BufferStorage,
@@ -7,7 +7,7 @@ use crate::{
     buffer::MultiComponentBuffer,
     component::{ComponentDesc, ComponentValue},
     writer::{MissingDyn, SingleComponentWriter, WriteDedupDyn},
-    BatchSpawn, Component, Entity, EntityBuilder, World,
+    BatchSpawn, Component, Entity, EntityBuilder, Error, World,
 };
 
 type DeferFn = Box<dyn Fn(&mut World) -> anyhow::Result<()> + Send + Sync>;
@@ -158,7 +158,8 @@ impl CommandBuffer {
 
     /// Set a component for `id`.
     ///
-    /// Does not trigger a modification event if the value is the same
+    /// Does not trigger a modification event if the value is the same, but rather a
+    /// [`EventKind::Touched`](crate::events::EventKind::Touched) event
     pub fn set_dedup<T: ComponentValue + PartialEq>(
         &mut self,
         id: Entity,
@@ -248,6 +249,9 @@ impl CommandBuffer {
     }
 
     /// Despawn an entity by id
+    ///
+    /// Despawning an entity which no longer exists, such as when the same entity is despawned
+    /// twice by deferred commands, does nothing.
     pub fn despawn(&mut self, id: Entity) -> &mut Self {
         self.commands.push(Command::Despawn(id));
         self
@@ -266,7 +270,12 @@ impl CommandBuffer {
 
     /// Applies all contents of the command buffer to the world.
     /// The commandbuffer is cleared and can be reused.
+    ///
+    /// Relations targeting an entity despawned by the buffer are removed once all commands are
+    /// applied, including relations added by commands following the despawn.
     pub fn apply(&mut self, world: &mut World) -> anyhow::Result<()> {
+        let mut despawned = Vec::new();
+
         for cmd in self.commands.drain(..) {
             match cmd {
                 Command::Spawn(mut entity) => {
@@ -322,10 +331,12 @@ impl CommandBuffer {
                         .map_err(|v| v.into_anyhow())
                         .with_context(|| format!("Failed to set component {}", desc.name()))?;
                 },
-                Command::Despawn(id) => world
-                    .despawn(id)
-                    .map_err(|v| v.into_anyhow())
-                    .context("Failed to despawn entity")?,
+                Command::Despawn(id) => match world.despawn(id) {
+                    Ok(()) => despawned.push(id),
+                    // Already despawned
+                    Err(Error::NoSuchEntity(_)) => {}
+                    Err(err) => return Err(err.into_anyhow()).context("Failed to despawn entity"),
+                },
                 Command::Remove { id, desc } => world
                     .remove_dyn(id, desc)
                     .map_err(|v| v.into_anyhow())
@@ -336,6 +347,13 @@ impl CommandBuffer {
             }
         }
 
+        // Sweep relations to despawned entities which were added after the despawn
+        for id in despawned {
+            if !world.is_alive(id) {
+                world.detach(id);
+            }
+        }
+
         self.inserts.clear();
 
         Ok(())
@@ -347,6 +365,26 @@ impl CommandBuffer {
         self.inserts.clear();
         self.commands.clear()
     }
+
+    /// Returns the number of recorded commands
+    pub(crate) fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Discards the commands recorded after the first `len` commands
+    pub(crate) fn truncate(&mut self, len: usize) {
+        for cmd in self.commands.drain(len.min(self.commands.len())..) {
+            if let Command::Set { desc, offset, .. }
+            | Command::SetDedup { desc, offset, .. }
+            | Command::SetMissing { desc, offset, .. } = cmd
+            {
+                unsafe {
+                    let value = self.inserts.take_dyn(offset);
+                    desc.drop(value);
+                }
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -29,4 +29,10 @@ component! {
 
     /// Added automatically to all STATIC entities
     pub is_static: () => [ Debuggable ],
+
+    /// Marks an entity which was released back into an [`EntityPool`](crate::EntityPool).
+    ///
+    /// Pooled entities have no other components, but are still yielded by queries which do not
+    /// require any component, such as `Query::new(entity_ids())`.
+    pub pooled: () => [ Debuggable ],
 }
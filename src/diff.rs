@@ -0,0 +1,193 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+
+use crate::{
+    archetype::{Archetype, Slot},
+    buffer::ComponentBuffer,
+    component::ComponentDesc,
+    error::Result,
+    metadata::{diffable, Diffable},
+    Entity, World,
+};
+
+/// The difference between two worlds, which transforms one into the other when applied.
+///
+/// Only components with the [`Diffable`] metadata are considered, and entities without any such
+/// components are left untouched. This allows runtime state to survive applying the difference of
+/// content loaded from disk, such as when reloading a scene in an editor.
+///
+/// Entities are matched by id rather than by e.g; name, which requires the ids to be preserved
+/// between the worlds, such as when the worlds are deserialized from the same source.
+///
+/// See: [`World::diff`]
+#[derive(Debug, Default)]
+pub struct WorldDiff {
+    pub(crate) despawned: Vec<Entity>,
+    pub(crate) spawned: Vec<(Entity, ComponentBuffer)>,
+    pub(crate) changed: Vec<(Entity, ComponentBuffer)>,
+    pub(crate) removed: Vec<(Entity, Vec<ComponentDesc>)>,
+}
+
+impl WorldDiff {
+    /// Returns the entities which are despawned
+    pub fn despawned(&self) -> &[Entity] {
+        &self.despawned
+    }
+
+    /// Returns the entities which are spawned, along with their components
+    pub fn spawned(&self) -> impl Iterator<Item = (Entity, &ComponentBuffer)> {
+        self.spawned.iter().map(|(id, v)| (*id, v))
+    }
+
+    /// Returns the components which are added or changed for each existing entity
+    pub fn changed(&self) -> impl Iterator<Item = (Entity, &ComponentBuffer)> {
+        self.changed.iter().map(|(id, v)| (*id, v))
+    }
+
+    /// Returns the components which are removed from each existing entity
+    pub fn removed(&self) -> impl Iterator<Item = (Entity, &[ComponentDesc])> {
+        self.removed.iter().map(|(id, v)| (*id, &v[..]))
+    }
+
+    /// Returns true if applying the difference does nothing
+    pub fn is_empty(&self) -> bool {
+        self.despawned.is_empty()
+            && self.spawned.is_empty()
+            && self.changed.is_empty()
+            && self.removed.is_empty()
+    }
+
+    pub(crate) fn new(src: &World, dst: &World) -> Self {
+        let src_entities = content_entities(src);
+        let dst_entities = content_entities(dst);
+
+        let mut diff = Self::default();
+
+        for (&id, &(arch, _)) in &src_entities {
+            if dst_entities.contains_key(&id) {
+                continue;
+            }
+
+            if dst.is_alive(id) {
+                let removed = diffable_components(arch).map(|(desc, _)| desc).collect();
+                diff.removed.push((id, removed));
+            } else {
+                diff.despawned.push(id);
+            }
+        }
+
+        for (&id, &(dst_arch, dst_slot)) in &dst_entities {
+            let Some(&(src_arch, src_slot)) = src_entities.get(&id) else {
+                let mut buffer = ComponentBuffer::new();
+                for (desc, diffable) in diffable_components(dst_arch) {
+                    unsafe { clone_value(dst_arch, dst_slot, desc, &diffable, &mut buffer) }
+                }
+
+                if src.is_alive(id) {
+                    diff.changed.push((id, buffer));
+                } else {
+                    diff.spawned.push((id, buffer));
+                }
+
+                continue;
+            };
+
+            let mut changed = ComponentBuffer::new();
+            for (desc, diffable) in diffable_components(dst_arch) {
+                let equal = src_arch.has(desc.key())
+                    && with_value(src_arch, src_slot, desc, |src_value| {
+                        with_value(dst_arch, dst_slot, desc, |dst_value| unsafe {
+                            (diffable.eq)(src_value, dst_value)
+                        })
+                    });
+
+                if !equal {
+                    unsafe { clone_value(dst_arch, dst_slot, desc, &diffable, &mut changed) }
+                }
+            }
+
+            let removed = diffable_components(src_arch)
+                .map(|(desc, _)| desc)
+                .filter(|desc| !dst_arch.has(desc.key()))
+                .collect::<Vec<_>>();
+
+            if !changed.is_empty() {
+                diff.changed.push((id, changed));
+            }
+
+            if !removed.is_empty() {
+                diff.removed.push((id, removed));
+            }
+        }
+
+        diff
+    }
+
+    pub(crate) fn apply(self, world: &mut World) -> Result<()> {
+        for id in self.despawned {
+            world.despawn(id)?;
+        }
+
+        for (id, mut buffer) in self.spawned {
+            world.spawn_at(id)?;
+            world.set_with(id, &mut buffer)?;
+        }
+
+        for (id, mut buffer) in self.changed {
+            world.set_with(id, &mut buffer)?;
+        }
+
+        for (id, removed) in self.removed {
+            for desc in removed {
+                world.remove_dyn(id, desc)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the regular entities which have at least one diffable component
+fn content_entities(world: &World) -> BTreeMap<Entity, (&Archetype, Slot)> {
+    world
+        .archetypes
+        .iter()
+        .filter(|(_, arch)| diffable_components(arch).next().is_some())
+        .flat_map(|(_, arch)| {
+            arch.entities()
+                .iter()
+                .enumerate()
+                .filter(|(_, id)| id.kind().is_empty())
+                .map(move |(slot, &id)| (id, (arch, slot)))
+        })
+        .collect()
+}
+
+fn diffable_components(arch: &Archetype) -> impl Iterator<Item = (ComponentDesc, Diffable)> + '_ {
+    arch.components_desc()
+        .filter_map(|desc| Some((desc, desc.meta_ref().get(diffable())?.clone())))
+}
+
+/// Invokes `f` with a pointer to the value of a component, which is borrowed for the duration
+fn with_value<R>(
+    arch: &Archetype,
+    slot: Slot,
+    desc: ComponentDesc,
+    f: impl FnOnce(*const u8) -> R,
+) -> R {
+    let data = arch.cell(desc.key()).unwrap().data.borrow();
+    f(data.storage.at(slot).unwrap())
+}
+
+/// # Safety
+/// `diffable` must belong to `desc`
+unsafe fn clone_value(
+    arch: &Archetype,
+    slot: Slot,
+    desc: ComponentDesc,
+    diffable: &Diffable,
+    buffer: &mut ComponentBuffer,
+) {
+    with_value(arch, slot, desc, |value| {
+        (diffable.clone)(value, desc, buffer)
+    })
+}
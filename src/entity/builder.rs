@@ -1,5 +1,5 @@
 use crate::{
-    buffer::ComponentBuffer,
+    buffer::{Bundle, ComponentBuffer},
     component::{ComponentDesc, ComponentValue},
     error::Result,
     relation::RelationExt,
@@ -73,6 +73,12 @@ impl EntityBuilder {
         self
     }
 
+    /// Sets all components of the bundle.
+    pub fn set_bundle(&mut self, bundle: impl Bundle) -> &mut Self {
+        bundle.write(&mut self.buffer);
+        self
+    }
+
     /// Shorthand for setting a unit type component
     pub fn tag<T: From<()> + ComponentValue>(&mut self, component: Component<T>) -> &mut Self {
         self.set(component, ().into())
@@ -136,6 +142,32 @@ impl EntityBuilder {
     }
 
     /// Attach a child with the default value
+    ///
+    /// Children are spawned after the parent, once the parent id is known. Children may in turn
+    /// have attached children, which allows describing a whole hierarchy at once.
+    ///
+    /// ```rust
+    /// # use flax::{*, components::*};
+    /// let mut world = World::new();
+    /// let root = Entity::builder()
+    ///     .set(name(), "root".into())
+    ///     .attach(
+    ///         child_of,
+    ///         Entity::builder()
+    ///             .set(name(), "child".into())
+    ///             .attach(child_of, Entity::builder().set(name(), "grandchild".into())),
+    ///     )
+    ///     .spawn(&mut world);
+    ///
+    /// let children = Query::new(entity_ids())
+    ///     .with_relation(child_of)
+    ///     .borrow(&world)
+    ///     .iter()
+    ///     .count();
+    ///
+    /// assert_eq!(children, 2);
+    /// # assert!(world.is_alive(root));
+    /// ```
     pub fn attach<T: ComponentValue + Default>(
         &mut self,
         relation: impl RelationExt<T> + ComponentValue,
@@ -188,6 +220,26 @@ impl EntityBuilder {
         Ok(id)
     }
 
+    /// Returns the components staged in the builder.
+    ///
+    /// This allows inspecting an entity before it is spawned, e.g; to display a prefab in an
+    /// editor.
+    pub fn buffer(&self) -> &ComponentBuffer {
+        &self.buffer
+    }
+
+    /// Returns the components staged in the builder mutably
+    pub fn buffer_mut(&mut self) -> &mut ComponentBuffer {
+        &mut self.buffer
+    }
+
+    /// Spawns the children of the builder, attached to `id`
+    pub(crate) fn spawn_children(&mut self, world: &mut World, id: Entity) {
+        self.children.drain(..).for_each(|child| {
+            child.spawn(world, id);
+        });
+    }
+
     /// Spawns the entity into the world through a commandbuffer
     pub fn spawn_into(&mut self, cmd: &mut CommandBuffer) {
         cmd.spawn(core::mem::take(self));
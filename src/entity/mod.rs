@@ -1,4 +1,5 @@
 mod builder;
+mod pool;
 mod store;
 
 use core::fmt;
@@ -6,7 +7,9 @@ use core::num::NonZeroU16;
 use core::sync::atomic::{AtomicU32, Ordering};
 
 pub use builder::*;
+pub use pool::EntityPool;
 pub(crate) use store::*;
+pub use store::{EntityLocation, RecyclePolicy};
 
 use crate::EntityIds;
 
@@ -0,0 +1,114 @@
+use alloc::vec::Vec;
+
+use crate::{
+    buffer::ComponentBuffer, components::pooled, error::Result, Entity, EntityBuilder, World,
+};
+
+/// A pool of entities which are reused rather than despawned.
+///
+/// This is useful for high frequency transient entities, such as particles, as it avoids
+/// allocating a new entity and spawning it for each use.
+///
+/// Released entities are kept alive in the world, but all their components are removed and they
+/// are marked with [`pooled`]. As such, they are no longer matched by queries for their previous
+/// components, and are still accessible by id.
+///
+/// Releasing and acquiring an entity each move it between archetypes only once, as the pooled
+/// entities are parked together in the archetype of only [`pooled`].
+///
+/// Queries which do not require any component, such as `Query::new(entity_ids())`, still yield
+/// pooled entities. Use `.without(pooled())` to exclude them.
+///
+/// ```rust
+/// # use flax::{*, components::name};
+/// component! {
+///     lifetime: f32,
+/// }
+///
+/// let mut world = World::new();
+/// let mut pool = EntityPool::new(|builder: &mut EntityBuilder| {
+///     builder.set(name(), "particle".into()).set(lifetime(), 1.0);
+/// });
+///
+/// let particle = pool.acquire(&mut world);
+/// *world.get_mut(particle, lifetime()).unwrap() = 0.0;
+///
+/// pool.release(&mut world, particle).unwrap();
+/// assert_eq!(Query::new(lifetime()).borrow(&world).iter().count(), 0);
+///
+/// // The same entity is reused, and initialized again
+/// assert_eq!(pool.acquire(&mut world), particle);
+/// assert_eq!(world.get_copy(particle, lifetime()), Ok(1.0));
+/// ```
+pub struct EntityPool<F> {
+    init: F,
+    free: Vec<Entity>,
+    builder: EntityBuilder,
+    marker: ComponentBuffer,
+}
+
+impl<F> EntityPool<F>
+where
+    F: FnMut(&mut EntityBuilder),
+{
+    /// Creates a new pool where `init` sets the components of each acquired entity.
+    pub fn new(init: F) -> Self {
+        Self {
+            init,
+            free: Vec::new(),
+            builder: EntityBuilder::new(),
+            marker: ComponentBuffer::new(),
+        }
+    }
+
+    /// Acquire an entity from the pool, or spawn a new one if the pool is empty.
+    ///
+    /// The components of a reused entity are set by the initializer of the pool, as all previous
+    /// components are removed when the entity is released.
+    pub fn acquire(&mut self, world: &mut World) -> Entity {
+        (self.init)(&mut self.builder);
+
+        while let Some(id) = self.free.pop() {
+            // The entity may have been despawned while pooled
+            if world.has(id, pooled()) {
+                world
+                    .replace_with(id, self.builder.buffer_mut())
+                    .expect("Entity is alive");
+                self.builder.spawn_children(world, id);
+                return id;
+            }
+        }
+
+        self.builder.spawn(world)
+    }
+
+    /// Release an entity back into the pool, removing all its components.
+    ///
+    /// Fails if the entity does not exist.
+    pub fn release(&mut self, world: &mut World, id: Entity) -> Result<()> {
+        self.marker.set(pooled(), ());
+        world.replace_with(id, &mut self.marker)?;
+        self.free.push(id);
+        Ok(())
+    }
+
+    /// Returns the number of entities available for reuse
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.free.len()
+    }
+
+    /// Returns true if there are no entities available for reuse
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.free.is_empty()
+    }
+}
+
+impl<F> core::fmt::Debug for EntityPool<F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("EntityPool")
+            .field("free", &self.free)
+            .finish_non_exhaustive()
+    }
+}
@@ -43,14 +43,20 @@ impl<T> Slot<T> {
         (from_slot_gen(self.gen), unsafe { &mut self.value.occupied })
     }
 
-    fn make_dead(&mut self) -> T {
+    /// Kills the slot. If `bump` is false the generation is kept when the slot is revived.
+    fn make_dead(&mut self, bump: bool) -> T {
         debug_assert!(self.is_alive());
 
         let val = mem::replace(&mut self.value, SlotValue { vacant: Vacant });
         let val = unsafe { ManuallyDrop::<T>::into_inner(val.occupied) };
 
-        // Since the slot is alive, the gen is odd, adding one makes it even
-        self.gen = self.gen.wrapping_add(1);
+        if bump {
+            // Since the slot is alive, the gen is odd, adding one makes it even
+            self.gen = self.gen.wrapping_add(1);
+        } else {
+            self.gen &= !1;
+        }
+
         val
     }
 }
@@ -64,16 +70,67 @@ fn from_slot_gen(gen: u32) -> EntityGen {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-/// An entity's location within an archetype
+/// An entity's location within an archetype.
+///
+/// Retrieved through [`World::location`](crate::World::location) or the
+/// [`entity_location`](crate::fetch::entity_location) fetch, and used with
+/// [`World::get_by_location`](crate::World::get_by_location) to access components without
+/// resolving the entity id.
+///
+/// # Stability
+///
+/// A location remains valid as long as no structural change happens to its archetype. It is
+/// invalidated when:
+/// - the entity is despawned, or moves to another archetype by adding or removing a component
+/// - another entity is removed from the same archetype, as the last entity is swapped into the
+///   freed slot
+/// - the archetype is pruned after becoming empty
+///
+/// Modifying component values, and spawning entities into the archetype, do not invalidate
+/// existing locations.
 pub struct EntityLocation {
     pub(crate) slot: usize,
     pub(crate) arch_id: ArchetypeId,
 }
 
+impl EntityLocation {
+    /// Returns the archetype the entity resides in
+    pub fn arch_id(&self) -> ArchetypeId {
+        self.arch_id
+    }
+
+    /// Returns the slot of the entity within the archetype
+    pub fn slot(&self) -> usize {
+        self.slot
+    }
+}
+
+/// Decides how the index of a despawned entity is reused.
+///
+/// See: [`World::set_recycle_policy`](crate::World::set_recycle_policy)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RecyclePolicy {
+    /// Reuse the index with an incremented generation, so that ids of despawned entities are
+    /// never valid again.
+    #[default]
+    BumpGeneration,
+    /// Never reuse an index, so that indices increase monotonically.
+    ///
+    /// This trades memory, as the slots of despawned entities are kept, for indices which can
+    /// safely be used as keys of external data.
+    NeverReuse,
+    /// Reuse the index with the same generation.
+    ///
+    /// **Note**: ids of despawned entities become valid again, and refer to the entity which
+    /// reuses the index.
+    Fixed,
+}
+
 pub(crate) struct EntityStore<V = EntityLocation> {
     slots: Vec<Slot<V>>,
     free: Vec<EntityIndex>,
     pub(crate) kind: EntityKind,
+    policy: RecyclePolicy,
     /// Increases for each freed item
     /// Decreases for each reserved id
     ///
@@ -204,11 +261,17 @@ impl<V> EntityStore<V> {
             slots: Vec::with_capacity(cap),
             free: Vec::new(),
             kind,
+            policy: RecyclePolicy::default(),
             len: 0,
             cursor: AtomicI64::new(0),
         }
     }
 
+    /// Sets the policy for indices freed from now on
+    pub(crate) fn set_policy(&mut self, policy: RecyclePolicy) {
+        self.policy = policy;
+    }
+
     #[inline]
     fn assert_reserved(&self) {
         #[cfg(debug_assertions)]
@@ -258,6 +321,7 @@ impl<V> EntityStore<V> {
         let index = id.index();
 
         let kind = self.kind;
+        let policy = self.policy;
         let slot = self.slot_mut(index).unwrap();
 
         // Make sure static ids never get a generation
@@ -265,9 +329,12 @@ impl<V> EntityStore<V> {
             panic!("Attempt to despawn static entity");
         }
 
-        let val = slot.make_dead();
-        self.free.push(index);
-        self.cursor.fetch_add(1, Relaxed);
+        let val = slot.make_dead(policy != RecyclePolicy::Fixed);
+
+        if policy != RecyclePolicy::NeverReuse {
+            self.free.push(index);
+            self.cursor.fetch_add(1, Relaxed);
+        }
 
         self.len -= 1;
 
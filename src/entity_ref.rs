@@ -4,7 +4,7 @@ use core::{
 };
 
 use alloc::string::String;
-use atomic_refcell::{AtomicRef, BorrowError, BorrowMutError};
+use atomic_refcell::{AtomicRef, AtomicRefMut, BorrowError, BorrowMutError};
 use once_cell::unsync::OnceCell;
 
 use crate::{
@@ -17,7 +17,7 @@ use crate::{
     format::EntityFormatter,
     query::QueryOne,
     relation::{RelationExt, RelationIter, RelationIterMut},
-    writer::{EntityWriter, FnWriter, Missing, Replace, SingleComponentWriter, WriteDedup},
+    writer::{EntityWriter, FnWriter, Missing, SingleComponentWriter, WriteDedup},
     Component, Entity, Fetch, World,
 };
 
@@ -58,6 +58,23 @@ impl<'a> EntityRefMut<'a> {
             })
     }
 
+    /// Access a component mutably without generating a modification event.
+    ///
+    /// See [`EntityRef::get_mut_silent`].
+    pub fn get_mut_silent<T: ComponentValue>(
+        &self,
+        component: Component<T>,
+    ) -> Result<AtomicRefMut<'_, T>, MissingComponent> {
+        self.world
+            .archetypes
+            .get(self.loc().arch_id)
+            .get_mut_silent(self.loc().slot, component)
+            .ok_or_else(|| MissingComponent {
+                id: self.id,
+                desc: component.desc(),
+            })
+    }
+
     /// Shorthand to copy and not use a borrowing references
     pub fn get_copy<T: ComponentValue + Copy>(
         &self,
@@ -92,7 +109,10 @@ impl<'a> EntityRefMut<'a> {
             })
     }
 
-    /// Updates a component in place
+    /// Updates a component in place.
+    ///
+    /// Does not trigger a modification event if the value is the same, but rather a
+    /// [`EventKind::Touched`](crate::events::EventKind::Touched) event
     pub fn update_dedup<T: ComponentValue + PartialEq>(
         &self,
         component: Component<T>,
@@ -156,11 +176,10 @@ impl<'a> EntityRefMut<'a> {
 
     /// Set a component for the entity
     pub fn set<T: ComponentValue>(&mut self, component: Component<T>, value: T) -> Option<T> {
-        self.set_with_writer(SingleComponentWriter::new(
-            component.desc(),
-            Replace::new(value),
-        ))
-        .left()
+        // Goes through the world to resolve required components and record history
+        let old = self.world.set(self.id, component, value).unwrap();
+        self.loc = OnceCell::new();
+        old
     }
 
     /// Set a component for the entity only if it is missing.
@@ -176,7 +195,8 @@ impl<'a> EntityRefMut<'a> {
 
     /// Set a component for the entity.
     ///
-    /// Does not trigger a modification event if the value is the same
+    /// Does not trigger a modification event if the value is the same, but rather a
+    /// [`EventKind::Touched`](crate::events::EventKind::Touched) event
     pub fn set_dedup<T: ComponentValue + PartialEq>(&mut self, component: Component<T>, value: T) {
         self.set_with_writer(SingleComponentWriter::new(
             component.desc(),
@@ -243,7 +263,10 @@ impl<'a> EntityRefMut<'a> {
         if self.has(component) {
             let loc = self.loc();
             Entry::Occupied(OccupiedEntry {
-                borrow: self.world.get_mut_at(loc, component).unwrap(),
+                world: self.world,
+                id: self.id,
+                loc,
+                component,
             })
         } else {
             Entry::Vacant(VacantEntry {
@@ -259,7 +282,10 @@ impl<'a> EntityRefMut<'a> {
         if self.has(component) {
             let loc = self.loc();
             Entry::Occupied(OccupiedEntry {
-                borrow: self.world.get_mut_at(loc, component).unwrap(),
+                world: self.world,
+                id: self.id,
+                loc,
+                component,
             })
         } else {
             self.loc.take();
@@ -357,6 +383,24 @@ impl<'a> EntityRef<'a> {
             })
     }
 
+    /// Access a component mutably without generating a modification event.
+    ///
+    /// Changes made through the returned reference are not visible to change detection, such
+    /// as [`FetchExt::modified`](crate::FetchExt::modified). This is useful when initializing
+    /// data where a modification would be semantically wrong, such as freshly deserialized
+    /// components.
+    pub fn get_mut_silent<T: ComponentValue>(
+        &self,
+        component: Component<T>,
+    ) -> Result<AtomicRefMut<'a, T>, MissingComponent> {
+        self.arch
+            .get_mut_silent(self.loc.slot, component)
+            .ok_or_else(|| MissingComponent {
+                id: self.id,
+                desc: component.desc(),
+            })
+    }
+
     /// Shorthand to copy and not use a borrowing references
     pub fn get_copy<T: ComponentValue + Copy>(
         &self,
@@ -383,7 +427,10 @@ impl<'a> EntityRef<'a> {
             .update(self.loc.slot, component, FnWriter::new(f), change_tick)
     }
 
-    /// Updates a component in place
+    /// Updates a component in place.
+    ///
+    /// Does not trigger a modification event if the value is the same, but rather a
+    /// [`EventKind::Touched`](crate::events::EventKind::Touched) event
     pub fn update_dedup<T: ComponentValue + PartialEq>(
         &self,
         component: Component<T>,
@@ -460,6 +507,72 @@ impl<'a> EntityRef<'a> {
     }
 }
 
+/// A read-only view of the components of an entity.
+///
+/// Contrary to [`EntityRef`], this does not allow modifying components or accessing the world,
+/// which allows it to be yielded alongside mutable fetches, as for
+/// [`FetchExt::with_entity_ref`](crate::FetchExt::with_entity_ref).
+#[derive(Copy, Clone)]
+pub struct ReadOnlyEntityRef<'a> {
+    inner: EntityRef<'a>,
+}
+
+impl<'a> ReadOnlyEntityRef<'a> {
+    pub(crate) fn new(inner: EntityRef<'a>) -> Self {
+        Self { inner }
+    }
+
+    /// Access a component
+    pub fn get<T: ComponentValue>(
+        &self,
+        component: Component<T>,
+    ) -> Result<AtomicRef<'a, T>, MissingComponent> {
+        self.inner.get(component)
+    }
+
+    /// Shorthand to copy and not use a borrowing references
+    pub fn get_copy<T: ComponentValue + Copy>(
+        &self,
+        component: Component<T>,
+    ) -> Result<T, MissingComponent> {
+        self.inner.get_copy(component)
+    }
+
+    /// Check if the entity currently has the specified component without
+    /// borrowing.
+    pub fn has<T: ComponentValue>(&self, component: Component<T>) -> bool {
+        self.inner.has(component)
+    }
+
+    /// Attempt concurrently access a component and fail if the component is already borrowed
+    pub fn try_get<T: ComponentValue>(
+        &self,
+        component: Component<T>,
+    ) -> core::result::Result<Option<AtomicRef<'a, T>>, BorrowError> {
+        self.inner.arch.try_get(self.inner.loc.slot, component)
+    }
+
+    /// Returns all relations to other entities of the specified kind
+    #[inline]
+    pub fn relations<T: ComponentValue>(
+        &self,
+        relation: impl RelationExt<T>,
+    ) -> RelationIter<'a, T> {
+        self.inner.relations(relation)
+    }
+
+    /// Returns the entity id
+    pub fn id(&self) -> Entity {
+        self.inner.id
+    }
+
+    /// Shorthand for retrieving the [`name`](crate::components::name) component
+    #[inline]
+    pub fn name(&self) -> Option<AtomicRef<'a, String>> {
+        self.get(name()).ok()
+    }
+}
+
 impl<'a> Debug for EntityRef<'a> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         EntityFormatter {
@@ -499,6 +612,18 @@ impl Display for EntityRef<'_> {
     }
 }
 
+impl<'a> Debug for ReadOnlyEntityRef<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Debug::fmt(&self.inner, f)
+    }
+}
+
+impl Display for ReadOnlyEntityRef<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&self.inner, f)
+    }
+}
+
 impl Display for EntityRefMut<'_> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let id = self.id();
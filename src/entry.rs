@@ -1,8 +1,7 @@
-use core::mem;
-
 use crate::{
     archetype::RefMut,
     component::ComponentValue,
+    entity::EntityLocation,
     writer::{Replace, SingleComponentWriter},
     Component, Entity, World,
 };
@@ -39,13 +38,16 @@ impl<'a, T: ComponentValue> VacantEntry<'a, T> {
 
 /// A view into an occupied component entry
 pub struct OccupiedEntry<'a, T: ComponentValue> {
-    pub(crate) borrow: RefMut<'a, T>,
+    pub(crate) world: &'a mut World,
+    pub(crate) id: Entity,
+    pub(crate) loc: EntityLocation,
+    pub(crate) component: Component<T>,
 }
 
 impl<'a, T: ComponentValue> OccupiedEntry<'a, T> {
     /// Convert the entry into a mutable reference
     pub fn into_mut(self) -> RefMut<'a, T> {
-        self.borrow
+        self.world.get_mut_at(self.loc, self.component).unwrap()
     }
 }
 
@@ -56,7 +58,7 @@ where
     /// Mutate the value in place
     pub fn and_modify(mut self, mut func: impl FnMut(&mut T)) -> Self {
         if let Self::Occupied(v) = &mut self {
-            (func)(&mut *v.borrow)
+            (func)(&mut *v.world.get_mut_at(v.loc, v.component).unwrap())
         }
 
         self
@@ -97,7 +99,16 @@ where
                 slot.insert(value);
                 None
             }
-            Entry::Occupied(mut slot) => Some(mem::replace(&mut slot.borrow, value)),
+            // Goes through the writer to record the history of the component
+            Entry::Occupied(slot) => slot
+                .world
+                .set_with_writer(
+                    slot.id,
+                    SingleComponentWriter::new(slot.component.desc(), Replace { value }),
+                )
+                .expect("Entry is valid")
+                .1
+                .left(),
         }
     }
 }
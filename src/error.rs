@@ -18,6 +18,8 @@ pub enum Error {
     IncompleteBatch,
     /// Attempt to spawn entity with occupied entity id
     EntityOccupied(Entity),
+    /// The same component of an entity was borrowed mutably while also being borrowed elsewhere
+    AliasedBorrow(Entity, ComponentDesc),
 }
 
 impl Error {
@@ -78,6 +80,12 @@ impl Display for Error {
             Error::EntityOccupied(current) => {
                 write!(f, "Attempt to spawn new entity occupied id {current}")
             }
+            Error::AliasedBorrow(id, desc) => {
+                write!(
+                    f,
+                    "Attempt to borrow the component {desc:?} of entity {id} mutably more than once"
+                )
+            }
         }
     }
 }
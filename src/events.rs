@@ -1,4 +1,7 @@
-use alloc::vec::Vec;
+use core::mem;
+
+use alloc::{collections::BTreeSet, vec::Vec};
+use atomic_refcell::AtomicRefCell;
 use itertools::Itertools;
 
 use crate::{
@@ -46,6 +49,12 @@ pub enum EventKind {
     Removed,
     /// The component was modified
     Modified,
+    /// The component was set to a value equal to the current one.
+    ///
+    /// Only sent to subscribers which opt in through [`EventSubscriber::with_touched`].
+    ///
+    /// See: [`EventSubscriber::on_touched`]
+    Touched,
 }
 
 /// Represents the raw form of an event, where the archetype is available
@@ -77,9 +86,29 @@ pub trait EventSubscriber: ComponentValue {
     /// Handle an incoming event
     fn on_removed(&self, storage: &Storage, event: &EventData);
 
+    /// Handle a component being set to a value equal to the current one.
+    ///
+    /// This is emitted instead of [`Self::on_modified`] by the deduplicating setters, such as
+    /// [`EntityRefMut::set_dedup`](crate::EntityRefMut::set_dedup), which compare the values
+    /// and leave the change ticks untouched. This allows each subscriber to decide whether no-op
+    /// writes are of interest. [`Sink`] subscribers ignore them unless wrapped with
+    /// [`Self::with_touched`].
+    ///
+    /// The same restrictions as for [`Self::on_modified`] apply.
+    #[inline]
+    fn on_touched(&self, _event: &EventData) {}
+
     /// Returns true if the subscriber is still connected
     fn is_connected(&self) -> bool;
 
+    /// Called when the world change tick is explicitly advanced through [`World::tick`].
+    ///
+    /// This allows reacting to frame boundaries.
+    ///
+    /// [`World::tick`]: crate::World::tick
+    #[inline]
+    fn on_tick(&self, _tick: u32) {}
+
     /// Returns true if the subscriber is interested in this archetype
     #[inline]
     fn matches_arch(&self, _: &Archetype) -> bool {
@@ -139,6 +168,16 @@ pub trait EventSubscriber: ComponentValue {
             subscriber: self,
         }
     }
+
+    /// Send [`EventKind::Touched`] events to the sink, in addition to the other events.
+    ///
+    /// Must be applied to the sink before any filters.
+    fn with_touched(self) -> WithTouched<Self>
+    where
+        Self: Sized + Sink<Event>,
+    {
+        WithTouched { sink: self }
+    }
 }
 
 impl<S> EventSubscriber for S
@@ -180,6 +219,44 @@ where
     }
 }
 
+/// Sends [`EventKind::Touched`] events to the sink, which are otherwise ignored.
+///
+/// See: [`EventSubscriber::with_touched`]
+pub struct WithTouched<S> {
+    sink: S,
+}
+
+impl<S> EventSubscriber for WithTouched<S>
+where
+    S: 'static + Send + Sync + Sink<Event>,
+{
+    fn on_added(&self, storage: &Storage, event: &EventData) {
+        EventSubscriber::on_added(&self.sink, storage, event)
+    }
+
+    fn on_modified(&self, event: &EventData) {
+        EventSubscriber::on_modified(&self.sink, event)
+    }
+
+    fn on_removed(&self, storage: &Storage, event: &EventData) {
+        EventSubscriber::on_removed(&self.sink, storage, event)
+    }
+
+    fn on_touched(&self, event: &EventData) {
+        for &id in event.ids {
+            self.sink.send(Event {
+                id,
+                key: event.key,
+                kind: EventKind::Touched,
+            });
+        }
+    }
+
+    fn is_connected(&self) -> bool {
+        self.sink.is_connected()
+    }
+}
+
 /// Receive the component value of an event
 ///
 /// This is a convenience wrapper around [`EventSubscriber`] that sends the component value along
@@ -272,11 +349,19 @@ where
         self.subscriber.on_removed(storage, event)
     }
 
+    fn on_touched(&self, event: &EventData) {
+        self.subscriber.on_touched(event)
+    }
+
     #[inline]
     fn is_connected(&self) -> bool {
         self.subscriber.is_connected()
     }
 
+    fn on_tick(&self, tick: u32) {
+        self.subscriber.on_tick(tick)
+    }
+
     #[inline]
     fn matches_arch(&self, arch: &Archetype) -> bool {
         self.filter.filter_static(arch) && self.subscriber.matches_arch(arch)
@@ -317,6 +402,12 @@ where
         }
     }
 
+    fn on_touched(&self, event: &EventData) {
+        if (self.filter)(EventKind::Touched, event) {
+            self.subscriber.on_touched(event)
+        }
+    }
+
     #[inline]
     fn matches_arch(&self, arch: &Archetype) -> bool {
         self.subscriber.matches_arch(arch)
@@ -331,6 +422,10 @@ where
     fn is_connected(&self) -> bool {
         self.subscriber.is_connected()
     }
+
+    fn on_tick(&self, tick: u32) {
+        self.subscriber.on_tick(tick)
+    }
 }
 
 /// Filter a subscriber to only receive events for a specific set of components
@@ -355,6 +450,10 @@ where
         self.subscriber.on_removed(storage, event)
     }
 
+    fn on_touched(&self, event: &EventData) {
+        self.subscriber.on_touched(event)
+    }
+
     #[inline]
     fn matches_arch(&self, arch: &Archetype) -> bool {
         self.components.iter().any(|&key| arch.has(key)) && self.subscriber.matches_arch(arch)
@@ -369,6 +468,10 @@ where
     fn is_connected(&self) -> bool {
         self.subscriber.is_connected()
     }
+
+    fn on_tick(&self, tick: u32) {
+        self.subscriber.on_tick(tick)
+    }
 }
 
 /// Filter a subscriber to only receive events of a specific kind
@@ -399,9 +502,19 @@ where
         }
     }
 
+    fn on_touched(&self, event: &EventData) {
+        if self.event_kind == EventKind::Touched {
+            self.subscriber.on_touched(event)
+        }
+    }
+
     fn is_connected(&self) -> bool {
         self.subscriber.is_connected()
     }
+
+    fn on_tick(&self, tick: u32) {
+        self.subscriber.on_tick(tick)
+    }
 }
 
 /// Maps an event to the associated entity id.
@@ -425,3 +538,112 @@ impl<S: Sink<Entity>> Sink<Event> for WithIds<S> {
         self.sink.is_connected()
     }
 }
+
+/// Collects the entities for which a component was added or modified since it was last taken.
+///
+/// See [`World::track_changes`](crate::World::track_changes)
+pub(crate) struct ChangeTracker {
+    component: ComponentKey,
+    changed: AtomicRefCell<BTreeSet<Entity>>,
+}
+
+impl ChangeTracker {
+    pub(crate) fn new(component: ComponentKey) -> Self {
+        Self {
+            component,
+            changed: Default::default(),
+        }
+    }
+
+    /// Returns the changed entities, and starts collecting anew
+    pub(crate) fn take(&self) -> BTreeSet<Entity> {
+        mem::take(&mut *self.changed.borrow_mut())
+    }
+}
+
+impl EventSubscriber for ChangeTracker {
+    fn on_added(&self, _: &Storage, event: &EventData) {
+        self.changed.borrow_mut().extend(event.ids);
+    }
+
+    fn on_modified(&self, event: &EventData) {
+        self.changed.borrow_mut().extend(event.ids);
+    }
+
+    fn on_removed(&self, _: &Storage, _: &EventData) {}
+
+    fn is_connected(&self) -> bool {
+        true
+    }
+
+    fn matches_arch(&self, arch: &Archetype) -> bool {
+        arch.has(self.component)
+    }
+
+    fn matches_component(&self, desc: ComponentDesc) -> bool {
+        desc.key() == self.component
+    }
+}
+
+/// Invokes a function with the value of a component each time it is added to or removed from an
+/// entity.
+///
+/// See [`World::observe_added`](crate::World::observe_added)
+pub(crate) struct Observer<T, F> {
+    component: Component<T>,
+    kind: EventKind,
+    func: F,
+}
+
+impl<T, F> Observer<T, F> {
+    pub(crate) fn new(component: Component<T>, kind: EventKind, func: F) -> Self {
+        Self {
+            component,
+            kind,
+            func,
+        }
+    }
+
+    fn notify(&self, storage: &Storage, event: &EventData)
+    where
+        T: ComponentValue,
+        F: Fn(Entity, &T),
+    {
+        let values = storage.downcast_ref::<T>();
+        for (&id, slot) in event.ids.iter().zip_eq(event.slots.as_range()) {
+            (self.func)(id, &values[slot]);
+        }
+    }
+}
+
+impl<T, F> EventSubscriber for Observer<T, F>
+where
+    T: ComponentValue,
+    F: 'static + Send + Sync + Fn(Entity, &T),
+{
+    fn on_added(&self, storage: &Storage, event: &EventData) {
+        if self.kind == EventKind::Added {
+            self.notify(storage, event)
+        }
+    }
+
+    fn on_modified(&self, _: &EventData) {}
+
+    fn on_removed(&self, storage: &Storage, event: &EventData) {
+        if self.kind == EventKind::Removed {
+            self.notify(storage, event)
+        }
+    }
+
+    fn is_connected(&self) -> bool {
+        true
+    }
+
+    fn matches_arch(&self, arch: &Archetype) -> bool {
+        arch.has(self.component.key())
+    }
+
+    fn matches_component(&self, desc: ComponentDesc) -> bool {
+        desc.key() == self.component.key()
+    }
+}
@@ -0,0 +1,205 @@
+use core::{
+    convert::Infallible,
+    fmt::{self, Formatter},
+};
+
+use alloc::vec::Vec;
+
+use crate::{
+    archetype::{Slice, Slot},
+    system::Access,
+    Fetch, FetchItem,
+};
+
+use super::{FetchAccessData, FetchPrepareData, PreparedFetch, RandomFetch};
+
+/// Yields the item of whichever of several alternative fetches matches the entity.
+///
+/// See [`any`].
+#[derive(Debug, Clone)]
+pub struct Any<T>(pub(crate) T);
+
+/// Matches if *any* of the provided fetches match, and yields the item of the first one which
+/// matches as an [`AnyItem`].
+///
+/// This is useful for data where usually exactly one of several alternatives is present, such as
+/// an entity being renderable through one of several component types.
+///
+/// If more than one alternative matches, the first one which can be prepared for the archetype is
+/// used.
+///
+/// ```rust
+/// # use flax::{*, fetch::{any, AnyItem}};
+/// component! {
+///     sprite: String,
+///     text: String,
+///     mesh: u32,
+/// }
+///
+/// let mut world = World::new();
+///
+/// Entity::builder().set(sprite(), "player.png".into()).spawn(&mut world);
+/// Entity::builder().set(mesh(), 5).spawn(&mut world);
+///
+/// let mut query = Query::new(any((sprite(), text(), mesh())));
+///
+/// for item in &mut query.borrow(&world) {
+///     match item {
+///         AnyItem::A(sprite) => assert_eq!(sprite, "player.png"),
+///         AnyItem::B(_text) => unreachable!(),
+///         AnyItem::C(mesh) => assert_eq!(*mesh, 5),
+///     }
+/// }
+/// ```
+pub fn any<T>(fetches: T) -> Any<T> {
+    Any(fetches)
+}
+
+/// Represents which of the alternatives of [`any`] matched.
+///
+/// Also used for the prepared state of the fetch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AnyItem<A, B, C = Infallible, D = Infallible, E = Infallible, F = Infallible> {
+    /// The first alternative matched
+    A(A),
+    /// The second alternative matched
+    B(B),
+    /// The third alternative matched
+    C(C),
+    /// The fourth alternative matched
+    D(D),
+    /// The fifth alternative matched
+    E(E),
+    /// The sixth alternative matched
+    F(F),
+}
+
+macro_rules! tuple_impl {
+    ($($idx: tt => $ty: ident),*) => {
+        impl<'q, $($ty: FetchItem<'q>,)*> FetchItem<'q> for Any<($($ty,)*)> {
+            type Item = AnyItem<$($ty::Item,)*>;
+        }
+
+        impl<'w, $($ty,)*> Fetch<'w> for Any<($($ty,)*)>
+        where
+            $($ty: Fetch<'w>,)*
+        {
+            const MUTABLE: bool = $($ty::MUTABLE)||*;
+
+            type Prepared = AnyItem<$($ty::Prepared,)*>;
+
+            fn prepare(&'w self, data: FetchPrepareData<'w>) -> Option<Self::Prepared> {
+                let inner = &self.0;
+                $(
+                    if inner.$idx.filter_arch(data.into()) {
+                        if let Some(v) = inner.$idx.prepare(data) {
+                            return Some(AnyItem::$ty(v));
+                        }
+                    }
+                )*
+
+                None
+            }
+
+            fn filter_arch(&self, data: FetchAccessData) -> bool {
+                let inner = &self.0;
+                $(inner.$idx.filter_arch(data))||*
+            }
+
+            fn access(&self, data: FetchAccessData, dst: &mut Vec<Access>) {
+                let inner = &self.0;
+                // A later alternative is used if an earlier one fails to prepare, so all matching
+                // alternatives may be accessed
+                $(
+                    if inner.$idx.filter_arch(data) {
+                        inner.$idx.access(data, dst);
+                    }
+                )*
+            }
+
+            fn describe(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.write_str("any(")?;
+                let inner = &self.0;
+                let mut first = true;
+                $(
+                    if !core::mem::take(&mut first) {
+                        f.write_str(", ")?;
+                    }
+                    inner.$idx.describe(f)?;
+                )*
+                f.write_str(")")
+            }
+
+            #[inline]
+            fn on_borrow(&self) {
+                let inner = &self.0;
+                $(inner.$idx.on_borrow();)*
+            }
+        }
+
+        impl<'q, $($ty,)*> PreparedFetch<'q> for AnyItem<$($ty,)*>
+        where
+            $($ty: PreparedFetch<'q>,)*
+        {
+            type Item = AnyItem<$($ty::Item,)*>;
+            type Chunk = AnyItem<$($ty::Chunk,)*>;
+
+            const HAS_FILTER: bool = $($ty::HAS_FILTER)||*;
+
+            #[inline]
+            unsafe fn filter_slots(&mut self, slots: Slice) -> Slice {
+                match self {
+                    $(AnyItem::$ty(v) => v.filter_slots(slots),)*
+                    #[allow(unreachable_patterns)]
+                    _ => unreachable!(),
+                }
+            }
+
+            unsafe fn create_chunk(&'q mut self, slots: Slice) -> Self::Chunk {
+                match self {
+                    $(AnyItem::$ty(v) => AnyItem::$ty(v.create_chunk(slots)),)*
+                    #[allow(unreachable_patterns)]
+                    _ => unreachable!(),
+                }
+            }
+
+            #[inline]
+            unsafe fn fetch_next(chunk: &mut Self::Chunk) -> Self::Item {
+                match chunk {
+                    $(AnyItem::$ty(v) => AnyItem::$ty($ty::fetch_next(v)),)*
+                    #[allow(unreachable_patterns)]
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        impl<'q, $($ty,)*> RandomFetch<'q> for AnyItem<$($ty,)*>
+        where
+            $($ty: RandomFetch<'q>,)*
+        {
+            #[inline]
+            unsafe fn fetch_shared(&'q self, slot: Slot) -> Self::Item {
+                match self {
+                    $(AnyItem::$ty(v) => AnyItem::$ty(v.fetch_shared(slot)),)*
+                    #[allow(unreachable_patterns)]
+                    _ => unreachable!(),
+                }
+            }
+
+            #[inline]
+            unsafe fn fetch_shared_chunk(chunk: &Self::Chunk, slot: Slot) -> Self::Item {
+                match chunk {
+                    $(AnyItem::$ty(v) => AnyItem::$ty($ty::fetch_shared_chunk(v, slot)),)*
+                    #[allow(unreachable_patterns)]
+                    _ => unreachable!(),
+                }
+            }
+        }
+    };
+}
+
+tuple_impl! { 0 => A, 1 => B }
+tuple_impl! { 0 => A, 1 => B, 2 => C }
+tuple_impl! { 0 => A, 1 => B, 2 => C, 3 => D }
+tuple_impl! { 0 => A, 1 => B, 2 => C, 3 => D, 4 => E }
+tuple_impl! { 0 => A, 1 => B, 2 => C, 3 => D, 4 => E, 5 => F }
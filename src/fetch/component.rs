@@ -30,6 +30,15 @@ impl<'w, 'q, T: 'q> PreparedFetch<'q> for ReadComponent<'w, T> {
     }
 }
 
+impl<'w, 'q, T: ComponentValue> SliceFetch<'q> for ReadComponent<'w, T> {
+    type Slices = &'q [T];
+
+    #[inline]
+    unsafe fn chunk_slices(chunk: Self::Chunk, len: usize) -> Self::Slices {
+        core::slice::from_raw_parts(chunk.as_ptr(), len)
+    }
+}
+
 impl<'w, 'q, T: ComponentValue> RandomFetch<'q> for ReadComponent<'w, T> {
     #[inline]
     unsafe fn fetch_shared(&'q self, slot: Slot) -> Self::Item {
@@ -47,6 +56,7 @@ where
     T: ComponentValue,
 {
     const MUTABLE: bool = false;
+    const TRIVIAL_FILTER_ARCH: bool = true;
 
     type Prepared = ReadComponent<'w, T>;
 
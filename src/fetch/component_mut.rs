@@ -10,7 +10,7 @@ use crate::{
     Component, Fetch, FetchItem,
 };
 
-use super::{FetchAccessData, FetchPrepareData, PreparedFetch};
+use super::{FetchAccessData, FetchPrepareData, PreparedFetch, SliceFetch};
 
 #[derive(Debug, Clone)]
 /// Mutable component fetch
@@ -22,6 +22,7 @@ where
     T: ComponentValue,
 {
     const MUTABLE: bool = true;
+    const TRIVIAL_FILTER_ARCH: bool = true;
 
     type Prepared = WriteComponent<'w, T>;
 
@@ -97,3 +98,12 @@ impl<'w, 'q, T: 'q + ComponentValue> PreparedFetch<'q> for WriteComponent<'w, T>
         &mut *old
     }
 }
+
+impl<'w, 'q, T: 'q + ComponentValue> SliceFetch<'q> for WriteComponent<'w, T> {
+    type Slices = &'q mut [T];
+
+    #[inline]
+    unsafe fn chunk_slices(chunk: Self::Chunk, len: usize) -> Self::Slices {
+        core::slice::from_raw_parts_mut(chunk.as_ptr(), len)
+    }
+}
@@ -59,6 +59,11 @@ where
     fn searcher(&self, searcher: &mut crate::ArchetypeSearcher) {
         self.0.searcher(searcher)
     }
+
+    #[inline]
+    fn on_borrow(&self) {
+        self.0.on_borrow()
+    }
 }
 
 impl<'q, F, V> PreparedFetch<'q> for Copied<F>
@@ -0,0 +1,235 @@
+use core::{
+    fmt::{self, Formatter},
+    mem,
+};
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use atomic_refcell::AtomicRefCell;
+
+use crate::{
+    archetype::{ArchetypeId, Slice},
+    component::ComponentValue,
+    system::Access,
+    util::LocalRef,
+    Component, Entity, Fetch, FetchItem, Mutable,
+};
+
+use super::{FetchAccessData, FetchPrepareData, PreparedFetch, WriteComponent};
+
+/// Mutably access a component, along with its value as of the previous time the query visited the
+/// entity.
+///
+/// The previous values are kept in a shadow column per archetype, which is owned by the fetch and
+/// updated when each yielded [`DeltaMut`] is dropped. This is useful for interpolation or
+/// computing deltas, where both the old and the new value are needed.
+///
+/// **Note**: the previous value is lost when the entity moves to another archetype, such as when
+/// a component is added or removed.
+///
+/// ```rust
+/// # use flax::{*, fetch::delta};
+/// component! {
+///     position: f32,
+/// }
+///
+/// let mut world = World::new();
+/// Entity::builder().set(position(), 1.0).spawn(&mut world);
+///
+/// let mut query = Query::new(delta(position()));
+///
+/// for mut pos in &mut query.borrow(&world) {
+///     assert_eq!(pos.prev(), None);
+///     *pos.current_mut() += 2.0;
+/// }
+///
+/// for pos in &mut query.borrow(&world) {
+///     assert_eq!(pos.prev(), Some(&3.0));
+///     assert_eq!(pos.current(), &3.0);
+/// }
+/// ```
+pub fn delta<T: ComponentValue + Clone>(component: Component<T>) -> Delta<T> {
+    Delta {
+        fetch: Mutable(component),
+        shadows: Default::default(),
+    }
+}
+
+/// See: [`delta`]
+pub struct Delta<T> {
+    fetch: Mutable<T>,
+    shadows: AtomicRefCell<BTreeMap<ArchetypeId, Shadow<T>>>,
+}
+
+/// The previous values of an archetype
+struct Shadow<T> {
+    ids: Vec<Entity>,
+    values: Vec<Option<T>>,
+}
+
+impl<T> Default for Shadow<T> {
+    fn default() -> Self {
+        Self {
+            ids: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+}
+
+impl<T> Shadow<T> {
+    /// Realigns the previous values with the current entities of the archetype
+    fn sync(&mut self, ids: &[Entity]) {
+        if self.ids == ids {
+            return;
+        }
+
+        let mut old: BTreeMap<_, _> = self
+            .ids
+            .drain(..)
+            .zip(self.values.drain(..))
+            .filter_map(|(id, v)| Some((id, v?)))
+            .collect();
+
+        self.ids.extend_from_slice(ids);
+        self.values.extend(ids.iter().map(|id| old.remove(id)));
+    }
+}
+
+impl<T: ComponentValue> fmt::Debug for Delta<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Delta").field(&self.fetch.0).finish()
+    }
+}
+
+impl<'q, T: ComponentValue + Clone> FetchItem<'q> for Delta<T> {
+    type Item = DeltaMut<'q, T>;
+}
+
+impl<'w, T: ComponentValue + Clone> Fetch<'w> for Delta<T> {
+    const MUTABLE: bool = true;
+
+    type Prepared = PreparedDelta<'w, T>;
+
+    fn prepare(&'w self, data: FetchPrepareData<'w>) -> Option<Self::Prepared> {
+        let fetch = self.fetch.prepare(data)?;
+
+        let mut shadow = self
+            .shadows
+            .borrow_mut()
+            .remove(&data.arch_id)
+            .unwrap_or_default();
+
+        shadow.sync(data.arch.entities());
+
+        Some(PreparedDelta {
+            fetch,
+            shadow,
+            arch_id: data.arch_id,
+            shadows: LocalRef::new(&self.shadows),
+        })
+    }
+
+    fn filter_arch(&self, data: FetchAccessData) -> bool {
+        self.fetch.filter_arch(data)
+    }
+
+    fn access(&self, data: FetchAccessData, dst: &mut Vec<Access>) {
+        self.fetch.access(data, dst)
+    }
+
+    fn describe(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("delta ")?;
+        f.write_str(self.fetch.0.name())
+    }
+
+    fn searcher(&self, searcher: &mut crate::ArchetypeSearcher) {
+        self.fetch.searcher(searcher)
+    }
+}
+
+#[doc(hidden)]
+pub struct PreparedDelta<'w, T> {
+    fetch: WriteComponent<'w, T>,
+    shadow: Shadow<T>,
+    arch_id: ArchetypeId,
+    /// Receives the shadow of the archetype back when dropped
+    shadows: LocalRef<'w, BTreeMap<ArchetypeId, Shadow<T>>>,
+}
+
+impl<'w, T> Drop for PreparedDelta<'w, T> {
+    fn drop(&mut self) {
+        self.shadows
+            .borrow_mut()
+            .insert(self.arch_id, mem::take(&mut self.shadow));
+    }
+}
+
+impl<'w, 'q, T: 'q + ComponentValue + Clone> PreparedFetch<'q> for PreparedDelta<'w, T> {
+    type Item = DeltaMut<'q, T>;
+    type Chunk = (
+        <WriteComponent<'w, T> as PreparedFetch<'q>>::Chunk,
+        *mut Option<T>,
+    );
+
+    const HAS_FILTER: bool = false;
+
+    unsafe fn create_chunk(&'q mut self, slots: Slice) -> Self::Chunk {
+        (
+            self.fetch.create_chunk(slots),
+            self.shadow.values.as_mut_ptr().add(slots.start),
+        )
+    }
+
+    unsafe fn fetch_next(chunk: &mut Self::Chunk) -> Self::Item {
+        let current = <WriteComponent<'w, T> as PreparedFetch<'q>>::fetch_next(&mut chunk.0);
+
+        let prev = chunk.1;
+        chunk.1 = prev.add(1);
+
+        DeltaMut {
+            prev: &mut *prev,
+            current,
+        }
+    }
+}
+
+/// A mutable reference to a component along with its previous value.
+///
+/// The previous value is replaced by the current value when the guard is dropped.
+///
+/// See: [`delta`]
+pub struct DeltaMut<'q, T: Clone> {
+    prev: &'q mut Option<T>,
+    current: &'q mut T,
+}
+
+impl<'q, T: Clone> DeltaMut<'q, T> {
+    /// Returns the value as of the previous time the query visited the entity, if any
+    pub fn prev(&self) -> Option<&T> {
+        self.prev.as_ref()
+    }
+
+    /// Returns the current value
+    pub fn current(&self) -> &T {
+        self.current
+    }
+
+    /// Returns the current value mutably
+    pub fn current_mut(&mut self) -> &mut T {
+        self.current
+    }
+}
+
+impl<'q, T: Clone + fmt::Debug> fmt::Debug for DeltaMut<'q, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeltaMut")
+            .field("prev", &self.prev)
+            .field("current", &self.current)
+            .finish()
+    }
+}
+
+impl<'q, T: Clone> Drop for DeltaMut<'q, T> {
+    fn drop(&mut self) {
+        *self.prev = Some(self.current.clone());
+    }
+}
@@ -0,0 +1,213 @@
+use core::fmt::{self, Formatter};
+
+use alloc::{collections::BTreeSet, sync::Arc, vec::Vec};
+use atomic_refcell::AtomicRefCell;
+use itertools::Either;
+
+use crate::{
+    archetype::{Slice, Slot},
+    component::ComponentValue,
+    system::{Access, BoxedSystem, System},
+    util::LocalRef,
+    CommandBuffer, Component, Entity, Fetch, FetchItem,
+};
+
+use super::{FetchAccessData, FetchPrepareData, PreparedFetch, RandomFetch, ReadComponent};
+
+/// Fetches a component, or a default value for entities which lack it.
+///
+/// See [`densify`].
+#[derive(Debug, Clone)]
+pub struct Densify<T: ComponentValue> {
+    component: Component<T>,
+    default: T,
+    missing: Arc<AtomicRefCell<BTreeSet<Entity>>>,
+}
+
+/// Query a component as if every entity has it.
+///
+/// Entities which lack the component yield `default` and are recorded as they are fetched. The
+/// recorded entities are given the default value through a command buffer, either each frame by
+/// adding [`Densify::flush_system`] to the schedule, or manually using [`Densify::flush`].
+///
+/// This is useful when introducing a new component which is required by all entities, as the
+/// world is gradually migrated to have the component.
+///
+/// **Note**: The component is inserted when the command buffer is applied, which means recorded
+/// entities will yield the default value without having the component until then, and
+/// modifications to the component can not be made during that time. This is usually a one frame
+/// lag.
+///
+/// ```rust
+/// # use flax::{*, fetch::densify};
+/// component! {
+///     health: f32,
+/// }
+///
+/// let mut world = World::new();
+///
+/// let id = Entity::builder().set(health(), 50.0).spawn(&mut world);
+/// let new_id = world.spawn();
+///
+/// let dense = densify(health(), 100.0);
+/// let mut query = Query::new((entity_ids(), dense.clone()));
+///
+/// let mut items = query.borrow(&world).iter().map(|(id, &v)| (id, v)).collect::<Vec<_>>();
+/// items.sort_by_key(|v| v.0);
+/// assert_eq!(items, [(id, 50.0), (new_id, 100.0)]);
+///
+/// let mut cmd = CommandBuffer::new();
+/// dense.flush(&mut cmd);
+/// cmd.apply(&mut world).unwrap();
+///
+/// assert_eq!(world.get_copy(new_id, health()), Ok(100.0));
+/// ```
+pub fn densify<T: ComponentValue>(component: Component<T>, default: T) -> Densify<T> {
+    Densify {
+        component,
+        default,
+        missing: Default::default(),
+    }
+}
+
+impl<T: ComponentValue> Densify<T> {
+    /// Insert the default value for all entities which were yielded without the component, and
+    /// clear the recorded entities.
+    ///
+    /// Entities which receive the component before the command buffer is applied keep their
+    /// value.
+    pub fn flush(&self, cmd: &mut CommandBuffer)
+    where
+        T: Clone,
+    {
+        let missing = core::mem::take(&mut *self.missing.borrow_mut());
+
+        for id in missing {
+            cmd.set_missing(id, self.component, self.default.clone());
+        }
+    }
+
+    /// Returns a system which flushes the recorded entities into the command buffer of the
+    /// schedule, such that they receive the component without calling [`Self::flush`].
+    ///
+    /// The components are inserted when the schedule flushes its command buffer.
+    pub fn flush_system(&self) -> BoxedSystem
+    where
+        T: Clone,
+    {
+        let densify = self.clone();
+        System::builder()
+            .with_name(alloc::format!("flush densify {}", self.component.name()))
+            .with_cmd_mut()
+            .build(move |cmd: &mut CommandBuffer| densify.flush(cmd))
+            .boxed()
+    }
+}
+
+impl<'w, T: ComponentValue> Fetch<'w> for Densify<T> {
+    const MUTABLE: bool = false;
+
+    type Prepared = PreparedDensify<'w, T>;
+
+    fn prepare(&'w self, data: FetchPrepareData<'w>) -> Option<Self::Prepared> {
+        Some(PreparedDensify {
+            fetch: self.component.prepare(data),
+            default: &self.default,
+            ids: data.arch.entities(),
+            missing: LocalRef::new(&self.missing),
+        })
+    }
+
+    fn filter_arch(&self, _: FetchAccessData) -> bool {
+        true
+    }
+
+    fn access(&self, data: FetchAccessData, dst: &mut Vec<Access>) {
+        self.component.access(data, dst)
+    }
+
+    fn describe(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("densify ")?;
+        f.write_str(self.component.name())
+    }
+}
+
+impl<'q, T: ComponentValue> FetchItem<'q> for Densify<T> {
+    type Item = &'q T;
+}
+
+#[doc(hidden)]
+pub struct PreparedDensify<'w, T> {
+    fetch: Option<ReadComponent<'w, T>>,
+    default: &'w T,
+    ids: &'w [Entity],
+    missing: LocalRef<'w, BTreeSet<Entity>>,
+}
+
+#[doc(hidden)]
+pub struct MissingChunk<'q, T> {
+    default: &'q T,
+    ids: &'q [Entity],
+    slot: Slot,
+    missing: LocalRef<'q, BTreeSet<Entity>>,
+}
+
+impl<'q, T> MissingChunk<'q, T> {
+    /// Records the entity at `slot`, as it is yielded without the component
+    fn record(&self, slot: Slot) -> &'q T {
+        self.missing.borrow_mut().insert(self.ids[slot]);
+        self.default
+    }
+}
+
+impl<'w, 'q, T: ComponentValue> PreparedFetch<'q> for PreparedDensify<'w, T> {
+    type Item = &'q T;
+    type Chunk = Either<<ReadComponent<'w, T> as PreparedFetch<'q>>::Chunk, MissingChunk<'q, T>>;
+
+    const HAS_FILTER: bool = false;
+
+    unsafe fn create_chunk(&'q mut self, slots: Slice) -> Self::Chunk {
+        match &mut self.fetch {
+            Some(fetch) => Either::Left(fetch.create_chunk(slots)),
+            None => Either::Right(MissingChunk {
+                default: self.default,
+                ids: self.ids,
+                slot: slots.start,
+                missing: self.missing,
+            }),
+        }
+    }
+
+    #[inline]
+    unsafe fn fetch_next(chunk: &mut Self::Chunk) -> Self::Item {
+        match chunk {
+            Either::Left(chunk) => ReadComponent::<T>::fetch_next(chunk),
+            Either::Right(chunk) => {
+                let slot = chunk.slot;
+                chunk.slot += 1;
+                chunk.record(slot)
+            }
+        }
+    }
+}
+
+impl<'w, 'q, T: ComponentValue> RandomFetch<'q> for PreparedDensify<'w, T> {
+    #[inline]
+    unsafe fn fetch_shared(&'q self, slot: Slot) -> Self::Item {
+        match &self.fetch {
+            Some(fetch) => fetch.fetch_shared(slot),
+            None => {
+                self.missing.borrow_mut().insert(self.ids[slot]);
+                self.default
+            }
+        }
+    }
+
+    #[inline]
+    unsafe fn fetch_shared_chunk(chunk: &Self::Chunk, slot: Slot) -> Self::Item {
+        match chunk {
+            Either::Left(chunk) => ReadComponent::<T>::fetch_shared_chunk(chunk, slot),
+            Either::Right(chunk) => chunk.record(slot),
+        }
+    }
+}
@@ -1,14 +1,16 @@
+use core::fmt::{self, Formatter};
+
 use alloc::vec::Vec;
 
 use crate::{
     archetype::ArchetypeId,
-    archetype::{Archetype, Slot},
+    archetype::{Archetype, Slice, Slot},
     entity::EntityLocation,
     system::{Access, AccessKind},
-    EntityRef, Fetch, FetchItem, World,
+    EntityRef, Fetch, FetchItem, ReadOnlyEntityRef, World,
 };
 
-use super::{FetchAccessData, PreparedFetch};
+use super::{FetchAccessData, FetchPrepareData, FmtQuery, PreparedFetch, TransformFetch};
 
 /// Access all components dynamically in a query
 pub struct EntityRefs;
@@ -76,7 +78,7 @@ impl<'w, 'q> PreparedFetch<'q> for PreparedEntityRef<'w> {
     type Chunk = Batch<'q>;
     const HAS_FILTER: bool = false;
 
-    unsafe fn create_chunk(&'q mut self, slice: crate::archetype::Slice) -> Self::Chunk {
+    unsafe fn create_chunk(&'q mut self, slice: Slice) -> Self::Chunk {
         Batch {
             world: self.world,
             arch: self.arch,
@@ -102,6 +104,138 @@ impl<'w, 'q> PreparedFetch<'q> for PreparedEntityRef<'w> {
     }
 }
 
+/// Yields a [`ReadOnlyEntityRef`] along with each item of the fetch.
+///
+/// See [`FetchExt::with_entity_ref`](crate::FetchExt::with_entity_ref)
+pub struct WithEntityRef<Q> {
+    pub(crate) fetch: Q,
+}
+
+impl<'q, Q> FetchItem<'q> for WithEntityRef<Q>
+where
+    Q: FetchItem<'q>,
+{
+    type Item = (ReadOnlyEntityRef<'q>, Q::Item);
+}
+
+impl<'w, Q> Fetch<'w> for WithEntityRef<Q>
+where
+    Q: Fetch<'w>,
+{
+    const MUTABLE: bool = Q::MUTABLE;
+
+    type Prepared = PreparedWithEntityRef<'w, Q::Prepared>;
+
+    fn prepare(&'w self, data: FetchPrepareData<'w>) -> Option<Self::Prepared> {
+        Some(PreparedWithEntityRef {
+            entity: PreparedEntityRef {
+                world: data.world,
+                arch: data.arch,
+                arch_id: data.arch_id,
+            },
+            fetch: self.fetch.prepare(data)?,
+        })
+    }
+
+    fn filter_arch(&self, data: FetchAccessData) -> bool {
+        self.fetch.filter_arch(data)
+    }
+
+    /// The entity reference reads the components of the archetype which are not already accessed
+    /// by the fetch, which allows the fetch to borrow components mutably.
+    fn access(&self, data: FetchAccessData, dst: &mut Vec<Access>) {
+        let mut inner = Vec::new();
+        self.fetch.access(data, &mut inner);
+
+        dst.push(Access {
+            kind: AccessKind::World,
+            mutable: false,
+        });
+
+        dst.extend(
+            data.arch
+                .components()
+                .keys()
+                .map(|&component| AccessKind::Archetype {
+                    id: data.arch_id,
+                    component,
+                })
+                .filter(|kind| inner.iter().all(|v| v.kind != *kind))
+                .map(|kind| Access {
+                    kind,
+                    mutable: false,
+                }),
+        );
+
+        dst.append(&mut inner);
+    }
+
+    fn describe(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("WithEntityRef")
+            .field(&FmtQuery(&self.fetch))
+            .finish()
+    }
+
+    fn searcher(&self, searcher: &mut crate::ArchetypeSearcher) {
+        self.fetch.searcher(searcher)
+    }
+
+    #[inline]
+    fn on_borrow(&self) {
+        self.fetch.on_borrow()
+    }
+}
+
+#[doc(hidden)]
+pub struct PreparedWithEntityRef<'w, Q> {
+    entity: PreparedEntityRef<'w>,
+    fetch: Q,
+}
+
+impl<'w, 'q, Q> PreparedFetch<'q> for PreparedWithEntityRef<'w, Q>
+where
+    Q: PreparedFetch<'q>,
+{
+    type Item = (ReadOnlyEntityRef<'q>, Q::Item);
+    type Chunk = (Batch<'q>, Q::Chunk);
+
+    const HAS_FILTER: bool = Q::HAS_FILTER;
+
+    unsafe fn create_chunk(&'q mut self, slots: Slice) -> Self::Chunk {
+        (
+            self.entity.create_chunk(slots),
+            self.fetch.create_chunk(slots),
+        )
+    }
+
+    #[inline]
+    unsafe fn fetch_next(chunk: &mut Self::Chunk) -> Self::Item {
+        (
+            ReadOnlyEntityRef::new(PreparedEntityRef::fetch_next(&mut chunk.0)),
+            Q::fetch_next(&mut chunk.1),
+        )
+    }
+
+    unsafe fn filter_slots(&mut self, slots: Slice) -> Slice {
+        self.fetch.filter_slots(slots)
+    }
+}
+
+impl<K, Q> TransformFetch<K> for WithEntityRef<Q>
+where
+    Q: TransformFetch<K>,
+    WithEntityRef<Q>: for<'x> Fetch<'x>,
+    WithEntityRef<Q::Output>: for<'x> Fetch<'x>,
+{
+    type Output = WithEntityRef<Q::Output>;
+
+    fn transform_fetch(self, method: K) -> Self::Output {
+        WithEntityRef {
+            fetch: self.fetch.transform_fetch(method),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use itertools::Itertools;
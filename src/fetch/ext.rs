@@ -1,6 +1,6 @@
 use crate::{
     component::ComponentValue,
-    filter::{Cmp, Equal, Filtered, Greater, GreaterEq, Less, LessEq},
+    filter::{Cmp, CmpWith, Equal, Filtered, Greater, GreaterEq, Less, LessEq, Ordered},
     relation::RelationExt,
     Fetch, FetchItem,
 };
@@ -12,7 +12,7 @@ use super::{
     opt::{Opt, OptOr},
     source::{FetchSource, FromRelation, Traverse},
     transform::Added,
-    Map, Modified, Satisfied, Source, TransformFetch,
+    Inspect, Map, Modified, Satisfied, Source, TransformFetch, WithEntityRef, WithId,
 };
 
 /// Extension trait for [crate::Fetch]
@@ -117,6 +117,56 @@ pub trait FetchExt: Sized {
         Cmp::new(self, Equal(other))
     }
 
+    /// Filter by comparing the item with the item of another fetch for the same entity.
+    ///
+    /// Both fetches must match the entity.
+    fn cmp_with<R, F>(self, rhs: R, func: F) -> CmpWith<Self, R, F>
+    where
+        for<'x> CmpWith<Self, R, F>: Fetch<'x>,
+    {
+        CmpWith::new(self, rhs, func)
+    }
+
+    /// Filter any component less than the value of `rhs` for the same entity.
+    fn lt_component<R>(self, rhs: R) -> CmpWith<Self, R, Ordered>
+    where
+        for<'x> CmpWith<Self, R, Ordered>: Fetch<'x>,
+    {
+        CmpWith::new(self, rhs, Ordered::LESS)
+    }
+
+    /// Filter any component greater than the value of `rhs` for the same entity.
+    fn gt_component<R>(self, rhs: R) -> CmpWith<Self, R, Ordered>
+    where
+        for<'x> CmpWith<Self, R, Ordered>: Fetch<'x>,
+    {
+        CmpWith::new(self, rhs, Ordered::GREATER)
+    }
+
+    /// Filter any component less than or equal to the value of `rhs` for the same entity.
+    fn le_component<R>(self, rhs: R) -> CmpWith<Self, R, Ordered>
+    where
+        for<'x> CmpWith<Self, R, Ordered>: Fetch<'x>,
+    {
+        CmpWith::new(self, rhs, Ordered::LESS_EQ)
+    }
+
+    /// Filter any component greater than or equal to the value of `rhs` for the same entity.
+    fn ge_component<R>(self, rhs: R) -> CmpWith<Self, R, Ordered>
+    where
+        for<'x> CmpWith<Self, R, Ordered>: Fetch<'x>,
+    {
+        CmpWith::new(self, rhs, Ordered::GREATER_EQ)
+    }
+
+    /// Filter any component equal to the value of `rhs` for the same entity.
+    fn eq_component<R>(self, rhs: R) -> CmpWith<Self, R, Ordered>
+    where
+        for<'x> CmpWith<Self, R, Ordered>: Fetch<'x>,
+    {
+        CmpWith::new(self, rhs, Ordered::EQUAL)
+    }
+
     /// Set the source entity for the fetch.
     ///
     /// This allows fetching or joining queries
@@ -199,6 +249,107 @@ pub trait FetchExt: Sized {
         Map { query: self, func }
     }
 
+    /// Calls `func` with a reference to each item of the query, and passes the item on unchanged.
+    ///
+    /// This is useful for debugging or tracing which entities a query touches without changing
+    /// the query loop.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     health: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// Entity::builder().set(health(), 50.0).spawn(&mut world);
+    ///
+    /// let mut query = Query::new(health().copied().inspect(|v| println!("health: {v}")));
+    /// assert_eq!(query.collect_vec(&world), [50.0]);
+    /// ```
+    fn inspect<F>(self, func: F) -> Inspect<Self, F>
+    where
+        Self: for<'x> FetchItem<'x>,
+        for<'x> F: Fn(&<Self as FetchItem<'x>>::Item),
+    {
+        Inspect { query: self, func }
+    }
+
+    /// Enters a [`tracing`] span named `fetch` while each item of the query is fetched.
+    ///
+    /// One span is created per matched archetype, with the given `name` and the archetype's
+    /// component set as fields. This attributes the time spent iterating to the archetypes
+    /// responsible for it when profiling.
+    ///
+    /// Requires the `tracing` feature.
+    #[cfg(feature = "tracing")]
+    fn traced(self, name: &'static str) -> super::Traced<Self>
+    where
+        Self: for<'x> FetchItem<'x>,
+    {
+        super::Traced::new(self, name)
+    }
+
+    /// Yields the entity id along with each item, as `(Entity, Item)`.
+    ///
+    /// This is equivalent to `(entity_ids(), self)`, but composes with other adapters without
+    /// nesting the query into a tuple.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     health: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let id = Entity::builder().set(health(), 50.0).spawn(&mut world);
+    ///
+    /// let mut query = Query::new(health().copied().with_id());
+    /// assert_eq!(query.collect_vec(&world), [(id, 50.0)]);
+    /// ```
+    fn with_id(self) -> WithId<Self>
+    where
+        Self: for<'x> FetchItem<'x>,
+    {
+        WithId { fetch: self }
+    }
+
+    /// Yields a [`ReadOnlyEntityRef`](crate::ReadOnlyEntityRef) along with each item of the fetch.
+    ///
+    /// This allows reading a few components efficiently through the fetch, while falling back
+    /// to dynamic access for rarely present components in the same loop.
+    ///
+    /// The entity reference is read-only, and is considered to read all components of the
+    /// archetype which are not accessed by the fetch, which allows the fetch itself to be mutable.
+    /// Accessing a component through the entity reference which is mutably borrowed by the fetch
+    /// will panic.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     health: f32,
+    ///     armor: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let id = Entity::builder()
+    ///     .set(health(), 50.0)
+    ///     .set(armor(), 0.5)
+    ///     .spawn(&mut world);
+    ///
+    /// let mut query = Query::new(health().as_mut().with_entity_ref());
+    /// for (entity, health) in &mut query.borrow(&world) {
+    ///     *health -= 20.0 * entity.get_copy(armor()).unwrap_or(1.0);
+    /// }
+    ///
+    /// assert_eq!(world.get(id, health()).as_deref(), Ok(&40.0));
+    /// ```
+    fn with_entity_ref(self) -> WithEntityRef<Self>
+    where
+        Self: for<'x> FetchItem<'x>,
+    {
+        WithEntityRef { fetch: self }
+    }
+
     /// Filter a fetch with another fetch as predicate
     fn filtered<F>(self, filter: F) -> Filtered<Self, F>
     where
@@ -0,0 +1,112 @@
+use core::fmt::{self, Formatter};
+
+use alloc::vec::Vec;
+
+use crate::{
+    archetype::{Slice, Slot},
+    component::{ComponentKey, ComponentValue},
+    system::Access,
+    Component, Fetch, FetchItem,
+};
+
+use super::{FetchAccessData, FetchPrepareData, PreparedFetch, RandomFetch};
+
+/// Yields true if the entity has the component.
+///
+/// Unlike [`opt`](crate::FetchExt::opt), the component is not borrowed and does not need to be
+/// present, which makes it suitable for branching on tag components inside a query.
+///
+/// ```rust
+/// # use flax::{*, fetch::has};
+/// component! {
+///     health: f32,
+///     poisoned: (),
+/// }
+///
+/// let mut world = World::new();
+/// let a = Entity::builder().set(health(), 50.0).spawn(&mut world);
+/// let b = Entity::builder()
+///     .set(health(), 50.0)
+///     .tag(poisoned())
+///     .spawn(&mut world);
+///
+/// let mut query = Query::new((health().as_mut(), has(poisoned())));
+/// for (health, poisoned) in &mut query.borrow(&world) {
+///     if poisoned {
+///         *health -= 10.0;
+///     }
+/// }
+///
+/// assert_eq!(world.get_copy(a, health()), Ok(50.0));
+/// assert_eq!(world.get_copy(b, health()), Ok(40.0));
+/// ```
+pub fn has<T: ComponentValue>(component: Component<T>) -> Has {
+    Has {
+        component: component.key(),
+        name: component.name(),
+    }
+}
+
+/// See: [`has`]
+#[derive(Debug, Clone)]
+pub struct Has {
+    component: ComponentKey,
+    name: &'static str,
+}
+
+impl<'q> FetchItem<'q> for Has {
+    type Item = bool;
+}
+
+impl<'w> Fetch<'w> for Has {
+    const MUTABLE: bool = false;
+    const TRIVIAL_FILTER_ARCH: bool = true;
+
+    type Prepared = PreparedHas;
+
+    fn prepare(&self, data: FetchPrepareData<'w>) -> Option<Self::Prepared> {
+        Some(PreparedHas(data.arch.has(self.component)))
+    }
+
+    fn filter_arch(&self, _: FetchAccessData) -> bool {
+        true
+    }
+
+    fn describe(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "has {}", self.name)
+    }
+
+    #[inline]
+    fn access(&self, _: FetchAccessData, _: &mut Vec<Access>) {}
+}
+
+#[doc(hidden)]
+pub struct PreparedHas(bool);
+
+impl<'q> PreparedFetch<'q> for PreparedHas {
+    type Item = bool;
+    type Chunk = bool;
+
+    const HAS_FILTER: bool = false;
+
+    unsafe fn create_chunk(&'q mut self, _: Slice) -> Self::Chunk {
+        self.0
+    }
+
+    #[inline]
+    unsafe fn fetch_next(chunk: &mut Self::Chunk) -> Self::Item {
+        *chunk
+    }
+}
+
+impl<'q> RandomFetch<'q> for PreparedHas {
+    #[inline]
+    unsafe fn fetch_shared(&self, _: Slot) -> Self::Item {
+        self.0
+    }
+
+    #[inline]
+    unsafe fn fetch_shared_chunk(chunk: &Self::Chunk, _: Slot) -> Self::Item {
+        *chunk
+    }
+}
@@ -0,0 +1,138 @@
+use core::fmt::{self, Formatter};
+
+use alloc::vec::Vec;
+
+use crate::{
+    archetype::{Slice, Slot},
+    system::Access,
+    Fetch, FetchItem,
+};
+
+use super::{
+    FetchAccessData, FetchPrepareData, FmtQuery, PreparedFetch, RandomFetch, TransformFetch,
+};
+
+/// Calls a function with a reference to each item of the fetch, and yields the item unchanged.
+///
+/// See [`FetchExt::inspect`](crate::FetchExt::inspect)
+pub struct Inspect<Q, F> {
+    pub(crate) query: Q,
+    pub(crate) func: F,
+}
+
+impl<Q, F> Inspect<Q, F> {
+    /// Creates a new inspecting query
+    pub const fn new(query: Q, func: F) -> Self {
+        Self { query, func }
+    }
+}
+
+impl<'q, Q, F> FetchItem<'q> for Inspect<Q, F>
+where
+    Q: FetchItem<'q>,
+{
+    type Item = Q::Item;
+}
+
+impl<'w, Q, F> Fetch<'w> for Inspect<Q, F>
+where
+    Q: Fetch<'w>,
+    F: for<'q> Fn(&<Q as FetchItem<'q>>::Item),
+    F: 'static,
+{
+    const MUTABLE: bool = Q::MUTABLE;
+
+    type Prepared = Inspect<Q::Prepared, &'w F>;
+
+    fn prepare(&'w self, data: FetchPrepareData<'w>) -> Option<Self::Prepared> {
+        Some(Inspect {
+            query: self.query.prepare(data)?,
+            func: &self.func,
+        })
+    }
+
+    fn filter_arch(&self, data: FetchAccessData) -> bool {
+        self.query.filter_arch(data)
+    }
+
+    fn access(&self, data: FetchAccessData, dst: &mut Vec<Access>) {
+        self.query.access(data, dst)
+    }
+
+    fn describe(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Inspect")
+            .field(&FmtQuery(&self.query))
+            .finish()
+    }
+
+    fn searcher(&self, searcher: &mut crate::ArchetypeSearcher) {
+        self.query.searcher(searcher)
+    }
+
+    #[inline]
+    fn on_borrow(&self) {
+        self.query.on_borrow()
+    }
+}
+
+impl<'q, Q, F> PreparedFetch<'q> for Inspect<Q, &F>
+where
+    Q: PreparedFetch<'q>,
+    F: Fn(&Q::Item),
+    F: 'static,
+{
+    type Item = Q::Item;
+
+    type Chunk = (&'q F, Q::Chunk);
+
+    const HAS_FILTER: bool = Q::HAS_FILTER;
+
+    unsafe fn create_chunk(&'q mut self, slots: Slice) -> Self::Chunk {
+        (self.func, self.query.create_chunk(slots))
+    }
+
+    unsafe fn fetch_next(chunk: &mut Self::Chunk) -> Self::Item {
+        let item = Q::fetch_next(&mut chunk.1);
+        (chunk.0)(&item);
+        item
+    }
+
+    unsafe fn filter_slots(&mut self, slots: Slice) -> Slice {
+        self.query.filter_slots(slots)
+    }
+}
+
+impl<'q, Q, F> RandomFetch<'q> for Inspect<Q, &F>
+where
+    Q: RandomFetch<'q>,
+    F: Fn(&Q::Item),
+    F: 'static,
+{
+    unsafe fn fetch_shared(&'q self, slot: Slot) -> Self::Item {
+        let item = self.query.fetch_shared(slot);
+        (self.func)(&item);
+        item
+    }
+
+    unsafe fn fetch_shared_chunk(chunk: &Self::Chunk, slot: Slot) -> Self::Item {
+        let item = Q::fetch_shared_chunk(&chunk.1, slot);
+        (chunk.0)(&item);
+        item
+    }
+}
+
+impl<K, Q, F> TransformFetch<K> for Inspect<Q, F>
+where
+    Q: TransformFetch<K>,
+    Inspect<Q, F>: for<'x> Fetch<'x>,
+    Inspect<Q::Output, F>: for<'x> Fetch<'x>,
+{
+    type Output = Inspect<Q::Output, F>;
+
+    fn transform_fetch(self, method: K) -> Self::Output {
+        Inspect {
+            query: self.query.transform_fetch(method),
+            func: self.func,
+        }
+    }
+}
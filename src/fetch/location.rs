@@ -0,0 +1,113 @@
+use core::fmt::{self, Formatter};
+
+use alloc::vec::Vec;
+
+use crate::{
+    archetype::{ArchetypeId, Slice, Slot},
+    system::Access,
+    EntityLocation, Fetch, FetchItem,
+};
+
+use super::{FetchAccessData, FetchPrepareData, PreparedFetch, RandomFetch};
+
+/// Yields the location of each entity within its archetype.
+///
+/// The locations can be stored for fast repeated access through
+/// [`World::get_by_location`](crate::World::get_by_location), which skips resolving the entity
+/// id. See [`EntityLocation`] for when a location is invalidated.
+///
+/// ```rust
+/// # use flax::{*, fetch::entity_location};
+/// component! {
+///     health: f32,
+/// }
+///
+/// let mut world = World::new();
+/// let id = Entity::builder().set(health(), 50.0).spawn(&mut world);
+///
+/// let locations = Query::new((entity_ids(), entity_location())).collect_vec(&world);
+///
+/// assert_eq!(locations, [(id, world.location(id).unwrap())]);
+/// assert_eq!(
+///     world.get_by_location(locations[0].1, health()).as_deref(),
+///     Some(&50.0)
+/// );
+/// ```
+pub fn entity_location() -> EntityLocations {
+    EntityLocations
+}
+
+/// See: [`entity_location`]
+#[derive(Debug, Clone)]
+pub struct EntityLocations;
+
+impl<'q> FetchItem<'q> for EntityLocations {
+    type Item = EntityLocation;
+}
+
+impl<'w> Fetch<'w> for EntityLocations {
+    const MUTABLE: bool = false;
+    const TRIVIAL_FILTER_ARCH: bool = true;
+
+    type Prepared = ReadLocations;
+
+    fn prepare(&self, data: FetchPrepareData<'w>) -> Option<Self::Prepared> {
+        Some(ReadLocations {
+            arch_id: data.arch_id,
+        })
+    }
+
+    fn filter_arch(&self, _: FetchAccessData) -> bool {
+        true
+    }
+
+    fn describe(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("entity_location")
+    }
+
+    #[inline]
+    fn access(&self, _: FetchAccessData, _: &mut Vec<Access>) {}
+}
+
+#[doc(hidden)]
+pub struct ReadLocations {
+    arch_id: ArchetypeId,
+}
+
+impl<'q> PreparedFetch<'q> for ReadLocations {
+    type Item = EntityLocation;
+    /// The location of the next entity
+    type Chunk = EntityLocation;
+
+    const HAS_FILTER: bool = false;
+
+    unsafe fn create_chunk(&'q mut self, slots: Slice) -> Self::Chunk {
+        EntityLocation {
+            slot: slots.start,
+            arch_id: self.arch_id,
+        }
+    }
+
+    unsafe fn fetch_next(chunk: &mut Self::Chunk) -> Self::Item {
+        let loc = *chunk;
+        chunk.slot += 1;
+        loc
+    }
+}
+
+impl<'q> RandomFetch<'q> for ReadLocations {
+    #[inline]
+    unsafe fn fetch_shared(&self, slot: Slot) -> Self::Item {
+        EntityLocation {
+            slot,
+            arch_id: self.arch_id,
+        }
+    }
+
+    unsafe fn fetch_shared_chunk(chunk: &Self::Chunk, slot: Slot) -> Self::Item {
+        EntityLocation {
+            slot: chunk.slot + slot,
+            arch_id: chunk.arch_id,
+        }
+    }
+}
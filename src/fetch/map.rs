@@ -59,6 +59,11 @@ where
     fn describe(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_tuple("Map").field(&FmtQuery(&self.query)).finish()
     }
+
+    #[inline]
+    fn on_borrow(&self) {
+        self.query.on_borrow()
+    }
 }
 
 impl<'w, 'q, Q, F, T> PreparedFetch<'q> for Map<Q, &'w F>
@@ -1,10 +1,16 @@
+mod any;
 mod as_deref;
 mod cloned;
 mod component;
 mod component_mut;
 mod copied;
+mod delta;
+mod densify;
 mod entity_ref;
 mod ext;
+mod has;
+mod inspect;
+mod location;
 mod map;
 mod maybe_mut;
 mod opt;
@@ -12,8 +18,13 @@ mod read_only;
 mod relations;
 mod relations_mut;
 mod satisfied;
+mod slices;
 mod source;
+#[cfg(feature = "tracing")]
+mod traced;
 mod transform;
+mod with_id;
+mod world_ref;
 
 use crate::{
     archetype::{Archetype, ArchetypeId, Slice, Slot},
@@ -26,22 +37,35 @@ use alloc::vec::Vec;
 use core::fmt::Debug;
 use core::fmt::{self, Formatter};
 
+pub use any::{any, Any, AnyItem};
 pub use as_deref::*;
 pub use cloned::*;
 pub use component::*;
 pub use component_mut::*;
 pub use copied::*;
+pub use delta::{delta, Delta, DeltaMut};
+pub use densify::{densify, Densify};
 pub use entity_ref::*;
 pub use ext::FetchExt;
+pub use has::{has, Has};
+pub use inspect::Inspect;
+pub use location::{entity_location, EntityLocations};
 pub use map::Map;
 pub use maybe_mut::{MaybeMut, MutGuard};
 pub use opt::*;
 pub use read_only::*;
-pub use relations::{nth_relation, relations_like, NthRelation, Relations, RelationsIter};
+pub use relations::{
+    nth_relation, relations, relations_like, NthRelation, Relations, RelationsIter,
+};
 pub use relations_mut::{relations_like_mut, RelationsIterMut, RelationsMut};
 pub use satisfied::Satisfied;
+pub use slices::SliceFetch;
 pub use source::{FromRelation, Source, Traverse};
+#[cfg(feature = "tracing")]
+pub use traced::Traced;
 pub use transform::{Added, Modified, TransformFetch};
+pub use with_id::WithId;
+pub use world_ref::{world_ref, WorldRef};
 
 #[doc(hidden)]
 pub struct FmtQuery<'r, Q>(pub &'r Q);
@@ -108,6 +132,10 @@ pub trait Fetch<'w>: for<'q> FetchItem<'q> {
     /// true if the fetch mutates any component and thus needs a change event
     const MUTABLE: bool;
 
+    /// true if [`Self::filter_arch`] accepts every archetype found by [`Self::searcher`], which
+    /// allows the query to skip calling it for each archetype
+    const TRIVIAL_FILTER_ARCH: bool = false;
+
     /// The prepared version of the fetch
     type Prepared: for<'x> PreparedFetch<'x, Item = <Self as FetchItem<'x>>::Item> + 'w;
 
@@ -131,6 +159,14 @@ pub trait Fetch<'w>: for<'q> FetchItem<'q> {
     #[inline]
     fn searcher(&self, _searcher: &mut ArchetypeSearcher) {}
 
+    /// Called each time a query containing the fetch is borrowed, before any archetype is
+    /// prepared.
+    ///
+    /// This resets state which only lives for a single borrow, such as the budget of
+    /// [`Take`](crate::filter::Take).
+    #[inline]
+    fn on_borrow(&self) {}
+
     /// Convert the fetch to a reference type which works with `HRTB`
     #[inline]
     fn by_ref(&self) -> RefFetch<Self>
@@ -230,6 +266,7 @@ impl UnionFilter for () {
 
 impl<'w> Fetch<'w> for () {
     const MUTABLE: bool = false;
+    const TRIVIAL_FILTER_ARCH: bool = true;
 
     type Prepared = ();
 
@@ -309,6 +346,7 @@ impl<'q> FetchItem<'q> for EntityIds {
 
 impl<'w> Fetch<'w> for EntityIds {
     const MUTABLE: bool = false;
+    const TRIVIAL_FILTER_ARCH: bool = true;
 
     type Prepared = ReadEntities<'w>;
 
@@ -347,6 +385,14 @@ impl<'w, 'q> PreparedFetch<'q> for ReadEntities<'w> {
     }
 }
 
+impl<'w, 'q> SliceFetch<'q> for ReadEntities<'w> {
+    type Slices = &'q [Entity];
+
+    unsafe fn chunk_slices(chunk: Self::Chunk, len: usize) -> Self::Slices {
+        core::slice::from_raw_parts(chunk.as_ptr(), len)
+    }
+}
+
 impl<'w, 'q> RandomFetch<'q> for ReadEntities<'w> {
     #[inline]
     unsafe fn fetch_shared(&self, slot: usize) -> Self::Item {
@@ -388,6 +434,19 @@ macro_rules! tuple_impl {
         }
 
 
+        impl<'q, $($ty, )*> SliceFetch<'q> for ($($ty,)*)
+        where $($ty: SliceFetch<'q>,)*
+        {
+            type Slices = ($($ty::Slices,)*);
+
+            #[inline]
+            unsafe fn chunk_slices(chunk: Self::Chunk, len: usize) -> Self::Slices {
+                ($(
+                    $ty::chunk_slices(chunk.$idx, len),
+                )*)
+            }
+        }
+
         impl<'q, $($ty, )*> PreparedFetch<'q> for ($($ty,)*)
             where $($ty: PreparedFetch<'q>,)*
         {
@@ -442,6 +501,7 @@ macro_rules! tuple_impl {
         where $($ty: Fetch<'w>,)*
         {
             const MUTABLE: bool =  $($ty::MUTABLE )||*;
+            const TRIVIAL_FILTER_ARCH: bool = $($ty::TRIVIAL_FILTER_ARCH )&&*;
             type Prepared       = ($($ty::Prepared,)*);
 
             #[inline]
@@ -468,6 +528,11 @@ macro_rules! tuple_impl {
             fn searcher(&self, searcher: &mut ArchetypeSearcher) {
                 $((self.$idx).searcher(searcher));*
             }
+
+            #[inline]
+            fn on_borrow(&self) {
+                $((self.$idx).on_borrow());*
+            }
         }
 
         impl< $($ty: StaticFilter, )*> StaticFilter for ($($ty,)*)
@@ -47,6 +47,11 @@ where
         f.write_str("opt ")?;
         self.fetch.describe(f)
     }
+
+    #[inline]
+    fn on_borrow(&self) {
+        self.fetch.on_borrow()
+    }
 }
 
 #[doc(hidden)]
@@ -94,7 +99,10 @@ where
     }
 }
 
-/// Transform a fetch into a optional fetch
+/// Transform a fetch into a optional fetch, yielding a default value if the fetch is not matched.
+///
+/// The default is stored once in the fetch and borrowed for every entity of an archetype which
+/// lacks the fetched component, so it is never cloned or recomputed per entity.
 #[derive(Debug, Clone)]
 pub struct OptOr<F, V> {
     fetch: F,
@@ -140,6 +148,11 @@ where
         self.fetch.describe(f)?;
         f.write_str(")")
     }
+
+    #[inline]
+    fn on_borrow(&self) {
+        self.fetch.on_borrow()
+    }
 }
 
 impl<'q, F: FetchItem<'q, Item = &'q V>, V: 'static> FetchItem<'q> for OptOr<F, V> {
@@ -10,6 +10,7 @@ use crate::{
     component::ComponentValue,
     relation::{Relation, RelationExt},
     system::{Access, AccessKind},
+    util::Ptr,
     Entity, Fetch, FetchItem,
 };
 
@@ -19,6 +20,7 @@ use super::{FetchAccessData, FetchPrepareData, PreparedFetch, RandomFetch};
 #[derive(Debug, Clone)]
 pub struct Relations<T: ComponentValue> {
     relation: Relation<T>,
+    required: bool,
 }
 
 impl<'w, T> Fetch<'w> for Relations<T>
@@ -42,8 +44,13 @@ where
         Some(PreparedRelations { borrows })
     }
 
-    fn filter_arch(&self, _: FetchAccessData) -> bool {
-        true
+    fn filter_arch(&self, data: FetchAccessData) -> bool {
+        !self.required
+            || data
+                .arch
+                .relations_like(self.relation.id())
+                .next()
+                .is_some()
     }
 
     fn access(&self, data: FetchAccessData, dst: &mut Vec<Access>) {
@@ -128,6 +135,43 @@ impl<'a, T> Iterator for RelationsIter<'a, T> {
 pub fn relations_like<T: ComponentValue>(relation: impl RelationExt<T>) -> Relations<T> {
     Relations {
         relation: relation.as_relation(),
+        required: false,
+    }
+}
+
+/// Access all relations of the specified type on the entity, yielding the target and value of
+/// each relation.
+///
+/// In contrast to [`relations_like`], this only matches entities which have at least one relation
+/// of the specified type.
+///
+/// ```rust
+/// # use flax::{*, components::name};
+/// component! {
+///     likes(other): f32,
+/// }
+///
+/// let mut world = World::new();
+///
+/// let apple = Entity::builder().set(name(), "apple".into()).spawn(&mut world);
+/// let pear = Entity::builder().set(name(), "pear".into()).spawn(&mut world);
+///
+/// let bob = Entity::builder()
+///     .set(likes(apple), 0.5)
+///     .set(likes(pear), 1.0)
+///     .spawn(&mut world);
+///
+/// let mut query = Query::new((entity_ids(), relations(likes)));
+///
+/// for (id, likes) in &mut query.borrow(&world) {
+///     assert_eq!(id, bob);
+///     assert_eq!(likes.collect::<Vec<_>>(), [(apple, &0.5), (pear, &1.0)]);
+/// }
+/// ```
+pub fn relations<T: ComponentValue>(relation: impl RelationExt<T>) -> Relations<T> {
+    Relations {
+        relation: relation.as_relation(),
+        required: true,
     }
 }
 
@@ -207,9 +251,7 @@ impl<'w, 'q, T: ComponentValue> RandomFetch<'q> for PreparedNthRelation<'w, T> {
     }
 
     unsafe fn fetch_shared_chunk(chunk: &Self::Chunk, slot: Slot) -> Self::Item {
-        let (id, borrow) = &*chunk.borrow;
-
-        (*id, &borrow.get()[slot])
+        (chunk.id, chunk.ptr.add(slot).as_ref())
     }
 }
 
@@ -223,8 +265,8 @@ pub struct PreparedNthRelation<'a, T> {
 }
 
 pub struct NthBatch<'a, T> {
-    borrow: *const (Entity, CellGuard<'a, [T]>),
-    slot: Slot,
+    id: Entity,
+    ptr: Ptr<'a, T>,
 }
 
 impl<'w, 'q, T> PreparedFetch<'q> for PreparedNthRelation<'w, T>
@@ -239,18 +281,15 @@ where
 
     unsafe fn create_chunk(&'q mut self, slice: crate::archetype::Slice) -> Self::Chunk {
         NthBatch {
-            borrow: &self.borrow,
-            slot: slice.start,
+            id: self.borrow.0,
+            ptr: Ptr::new(self.borrow.1.get()[slice.as_range()].as_ptr()),
         }
     }
 
+    #[inline]
     unsafe fn fetch_next(chunk: &mut Self::Chunk) -> Self::Item {
-        let slot = chunk.slot;
-        chunk.slot += 1;
-
-        let (id, borrow) = unsafe { &*chunk.borrow };
-
-        let borrow = &borrow.get()[slot];
-        (*id, borrow)
+        let old = chunk.ptr.as_ref();
+        chunk.ptr.advance(1);
+        (chunk.id, old)
     }
 }
@@ -27,7 +27,7 @@ impl<'w, T> Fetch<'w> for RelationsMut<T>
 where
     T: ComponentValue,
 {
-    const MUTABLE: bool = false;
+    const MUTABLE: bool = true;
 
     type Prepared = PreparedRelationsMut<'w, T>;
 
@@ -33,6 +33,11 @@ impl<'w, F: Fetch<'w>> Fetch<'w> for Satisfied<F> {
     }
 
     fn access(&self, _: super::FetchAccessData, _: &mut Vec<crate::system::Access>) {}
+
+    #[inline]
+    fn on_borrow(&self) {
+        self.0.on_borrow()
+    }
 }
 
 #[doc(hidden)]
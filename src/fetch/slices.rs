@@ -0,0 +1,19 @@
+use super::PreparedFetch;
+
+/// A prepared fetch whose items are stored contiguously, which allows yielding all items of a
+/// chunk as slices.
+///
+/// This is implemented for component fetches, both shared and mutable, entity ids, and tuples
+/// thereof.
+///
+/// See [`QueryBorrow::chunks_mut`](crate::QueryBorrow::chunks_mut)
+pub trait SliceFetch<'q>: PreparedFetch<'q> {
+    /// The items of a chunk, such as `&'q [T]` for a component
+    type Slices;
+
+    /// Returns the remaining items of `chunk` as slices
+    ///
+    /// # Safety
+    /// `chunk` must have at least `len` remaining items
+    unsafe fn chunk_slices(chunk: Self::Chunk, len: usize) -> Self::Slices;
+}
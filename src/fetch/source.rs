@@ -211,6 +211,11 @@ where
             )
         }
     }
+
+    #[inline]
+    fn on_borrow(&self) {
+        self.fetch.on_borrow()
+    }
 }
 
 // impl<'w, 'q, Q> ReadOnlyFetch<'q> for PreparedSource<Q>
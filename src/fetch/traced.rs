@@ -0,0 +1,133 @@
+use core::fmt::{self, Formatter};
+
+use alloc::vec::Vec;
+use tracing::Span;
+
+use crate::{
+    archetype::{Slice, Slot},
+    system::Access,
+    Fetch, FetchItem,
+};
+
+use super::{FetchAccessData, FetchPrepareData, FmtQuery, PreparedFetch, RandomFetch};
+
+/// Enters a [`tracing`] span around each fetched item, with one span per archetype.
+///
+/// See [`FetchExt::traced`](crate::FetchExt::traced)
+pub struct Traced<Q> {
+    pub(crate) query: Q,
+    pub(crate) name: &'static str,
+}
+
+impl<Q> Traced<Q> {
+    /// Creates a new traced query
+    pub const fn new(query: Q, name: &'static str) -> Self {
+        Self { query, name }
+    }
+}
+
+impl<'q, Q> FetchItem<'q> for Traced<Q>
+where
+    Q: FetchItem<'q>,
+{
+    type Item = Q::Item;
+}
+
+impl<'w, Q> Fetch<'w> for Traced<Q>
+where
+    Q: Fetch<'w>,
+{
+    const MUTABLE: bool = Q::MUTABLE;
+
+    type Prepared = PreparedTraced<Q::Prepared>;
+
+    fn prepare(&'w self, data: FetchPrepareData<'w>) -> Option<Self::Prepared> {
+        let span = tracing::info_span!(
+            "fetch",
+            name = self.name,
+            archetype = ?data.arch.components_desc().map(|v| v.name()).collect::<Vec<_>>(),
+        );
+
+        let query = {
+            let _enter = span.enter();
+            self.query.prepare(data)?
+        };
+
+        Some(PreparedTraced { query, span })
+    }
+
+    fn filter_arch(&self, data: FetchAccessData) -> bool {
+        self.query.filter_arch(data)
+    }
+
+    fn access(&self, data: FetchAccessData, dst: &mut Vec<Access>) {
+        self.query.access(data, dst)
+    }
+
+    fn describe(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Traced")
+            .field(&self.name)
+            .field(&FmtQuery(&self.query))
+            .finish()
+    }
+
+    fn searcher(&self, searcher: &mut crate::ArchetypeSearcher) {
+        self.query.searcher(searcher)
+    }
+
+    #[inline]
+    fn on_borrow(&self) {
+        self.query.on_borrow()
+    }
+}
+
+#[doc(hidden)]
+pub struct PreparedTraced<Q> {
+    query: Q,
+    span: Span,
+}
+
+impl<'q, Q> PreparedFetch<'q> for PreparedTraced<Q>
+where
+    Q: PreparedFetch<'q>,
+{
+    type Item = Q::Item;
+
+    /// The span is entered while each item is fetched, which keeps the chunk `Send`
+    type Chunk = (Span, Q::Chunk);
+
+    const HAS_FILTER: bool = Q::HAS_FILTER;
+
+    unsafe fn create_chunk(&'q mut self, slots: Slice) -> Self::Chunk {
+        let chunk = {
+            let _enter = self.span.enter();
+            self.query.create_chunk(slots)
+        };
+        (self.span.clone(), chunk)
+    }
+
+    unsafe fn fetch_next(chunk: &mut Self::Chunk) -> Self::Item {
+        let _enter = chunk.0.enter();
+        Q::fetch_next(&mut chunk.1)
+    }
+
+    unsafe fn filter_slots(&mut self, slots: Slice) -> Slice {
+        let _enter = self.span.enter();
+        self.query.filter_slots(slots)
+    }
+}
+
+impl<'q, Q> RandomFetch<'q> for PreparedTraced<Q>
+where
+    Q: RandomFetch<'q>,
+{
+    unsafe fn fetch_shared(&'q self, slot: Slot) -> Self::Item {
+        let _enter = self.span.enter();
+        self.query.fetch_shared(slot)
+    }
+
+    unsafe fn fetch_shared_chunk(chunk: &Self::Chunk, slot: Slot) -> Self::Item {
+        let _enter = chunk.0.enter();
+        Q::fetch_shared_chunk(&chunk.1, slot)
+    }
+}
@@ -0,0 +1,132 @@
+use core::fmt::{self, Formatter};
+
+use alloc::vec::Vec;
+
+use crate::{
+    archetype::{Slice, Slot},
+    system::Access,
+    util::Ptr,
+    Entity, Fetch, FetchItem,
+};
+
+use super::{
+    FetchAccessData, FetchPrepareData, FmtQuery, PreparedFetch, RandomFetch, TransformFetch,
+};
+
+/// Yields the entity id along with each item of the fetch.
+///
+/// See [`FetchExt::with_id`](crate::FetchExt::with_id)
+pub struct WithId<Q> {
+    pub(crate) fetch: Q,
+}
+
+impl<'q, Q> FetchItem<'q> for WithId<Q>
+where
+    Q: FetchItem<'q>,
+{
+    type Item = (Entity, Q::Item);
+}
+
+impl<'w, Q> Fetch<'w> for WithId<Q>
+where
+    Q: Fetch<'w>,
+{
+    const MUTABLE: bool = Q::MUTABLE;
+
+    type Prepared = PreparedWithId<'w, Q::Prepared>;
+
+    fn prepare(&'w self, data: FetchPrepareData<'w>) -> Option<Self::Prepared> {
+        Some(PreparedWithId {
+            entities: data.arch.entities(),
+            fetch: self.fetch.prepare(data)?,
+        })
+    }
+
+    fn filter_arch(&self, data: FetchAccessData) -> bool {
+        self.fetch.filter_arch(data)
+    }
+
+    fn access(&self, data: FetchAccessData, dst: &mut Vec<Access>) {
+        self.fetch.access(data, dst)
+    }
+
+    fn describe(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("WithId")
+            .field(&FmtQuery(&self.fetch))
+            .finish()
+    }
+
+    fn searcher(&self, searcher: &mut crate::ArchetypeSearcher) {
+        self.fetch.searcher(searcher)
+    }
+
+    #[inline]
+    fn on_borrow(&self) {
+        self.fetch.on_borrow()
+    }
+}
+
+#[doc(hidden)]
+pub struct PreparedWithId<'w, Q> {
+    entities: &'w [Entity],
+    fetch: Q,
+}
+
+impl<'w, 'q, Q> PreparedFetch<'q> for PreparedWithId<'w, Q>
+where
+    Q: PreparedFetch<'q>,
+{
+    type Item = (Entity, Q::Item);
+    type Chunk = (Ptr<'q, Entity>, Q::Chunk);
+
+    const HAS_FILTER: bool = Q::HAS_FILTER;
+
+    unsafe fn create_chunk(&'q mut self, slots: Slice) -> Self::Chunk {
+        (
+            Ptr::new(self.entities[slots.as_range()].as_ptr()),
+            self.fetch.create_chunk(slots),
+        )
+    }
+
+    #[inline]
+    unsafe fn fetch_next(chunk: &mut Self::Chunk) -> Self::Item {
+        let id = *chunk.0.as_ptr();
+        chunk.0.advance(1);
+        (id, Q::fetch_next(&mut chunk.1))
+    }
+
+    unsafe fn filter_slots(&mut self, slots: Slice) -> Slice {
+        self.fetch.filter_slots(slots)
+    }
+}
+
+impl<'w, 'q, Q> RandomFetch<'q> for PreparedWithId<'w, Q>
+where
+    Q: RandomFetch<'q>,
+{
+    unsafe fn fetch_shared(&'q self, slot: Slot) -> Self::Item {
+        (self.entities[slot], self.fetch.fetch_shared(slot))
+    }
+
+    unsafe fn fetch_shared_chunk(chunk: &Self::Chunk, slot: Slot) -> Self::Item {
+        (
+            *chunk.0.add(slot).as_ref(),
+            Q::fetch_shared_chunk(&chunk.1, slot),
+        )
+    }
+}
+
+impl<K, Q> TransformFetch<K> for WithId<Q>
+where
+    Q: TransformFetch<K>,
+    WithId<Q>: for<'x> Fetch<'x>,
+    WithId<Q::Output>: for<'x> Fetch<'x>,
+{
+    type Output = WithId<Q::Output>;
+
+    fn transform_fetch(self, method: K) -> Self::Output {
+        WithId {
+            fetch: self.fetch.transform_fetch(method),
+        }
+    }
+}
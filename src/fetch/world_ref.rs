@@ -0,0 +1,121 @@
+use alloc::vec::Vec;
+
+use crate::{
+    archetype::{Slice, Slot},
+    system::{Access, AccessKind},
+    Fetch, FetchItem, World,
+};
+
+use super::{FetchAccessData, FetchPrepareData, PreparedFetch, RandomFetch};
+
+/// Yields a shared reference to the world for each item.
+///
+/// See [`world_ref`]
+#[derive(Debug, Clone)]
+pub struct WorldRef;
+
+/// Yields a shared reference to the world alongside each item of the query.
+///
+/// This allows looking up the components of other entities, such as the target of a relation,
+/// while iterating a query.
+///
+/// The query declares shared access to the whole world, which prevents it from running in
+/// parallel with systems which modify any component.
+///
+/// **Note**: the components borrowed mutably by the query itself can not be accessed through the
+/// world, and will panic if attempted. Components borrowed immutably by the query can be accessed.
+///
+/// ```rust
+/// # use flax::{*, fetch::world_ref};
+/// component! {
+///     position: f32,
+///     velocity: f32,
+///     follows(id): (),
+/// }
+///
+/// let mut world = World::new();
+///
+/// let leader = Entity::builder()
+///     .set(position(), 5.0)
+///     .spawn(&mut world);
+///
+/// let follower = Entity::builder()
+///     .set(position(), 0.0)
+///     .set(velocity(), 0.0)
+///     .set(follows(leader), ())
+///     .spawn(&mut world);
+///
+/// let mut query = Query::new((position(), velocity().as_mut(), relations_like(follows), world_ref()));
+///
+/// for (pos, vel, mut targets, world) in &mut query.borrow(&world) {
+///     if let Some((target, _)) = targets.next() {
+///         *vel = world.get_copy(target, position()).unwrap() - pos;
+///     }
+/// }
+///
+/// assert_eq!(world.get_copy(follower, velocity()), Ok(5.0));
+/// ```
+pub fn world_ref() -> WorldRef {
+    WorldRef
+}
+
+impl<'q> FetchItem<'q> for WorldRef {
+    type Item = &'q World;
+}
+
+impl<'w> Fetch<'w> for WorldRef {
+    const MUTABLE: bool = false;
+
+    type Prepared = PreparedWorldRef<'w>;
+
+    fn prepare(&'w self, data: FetchPrepareData<'w>) -> Option<Self::Prepared> {
+        Some(PreparedWorldRef { world: data.world })
+    }
+
+    fn filter_arch(&self, _: FetchAccessData) -> bool {
+        true
+    }
+
+    fn access(&self, _: FetchAccessData, dst: &mut Vec<Access>) {
+        dst.push(Access {
+            kind: AccessKind::World,
+            mutable: false,
+        })
+    }
+
+    fn describe(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "world_ref")
+    }
+
+    fn searcher(&self, _: &mut crate::ArchetypeSearcher) {}
+}
+
+#[doc(hidden)]
+pub struct PreparedWorldRef<'a> {
+    world: &'a World,
+}
+
+impl<'w, 'q> PreparedFetch<'q> for PreparedWorldRef<'w> {
+    type Item = &'q World;
+    type Chunk = &'q World;
+    const HAS_FILTER: bool = false;
+
+    unsafe fn create_chunk(&'q mut self, _: Slice) -> Self::Chunk {
+        self.world
+    }
+
+    #[inline]
+    unsafe fn fetch_next(chunk: &mut Self::Chunk) -> Self::Item {
+        chunk
+    }
+}
+
+impl<'w, 'q> RandomFetch<'q> for PreparedWorldRef<'w> {
+    unsafe fn fetch_shared(&'q self, _: Slot) -> Self::Item {
+        self.world
+    }
+
+    unsafe fn fetch_shared_chunk(chunk: &Self::Chunk, _: Slot) -> Self::Item {
+        chunk
+    }
+}
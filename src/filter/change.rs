@@ -1,11 +1,13 @@
 use alloc::vec::Vec;
+use atomic_refcell::AtomicRef;
 use core::fmt::Formatter;
 use itertools::Itertools;
+use smallvec::SmallVec;
 
-use crate::archetype::{CellGuard, Change, Slot};
-use crate::component::ComponentValue;
+use crate::archetype::{CellGuard, Change, Changes, Slot};
+use crate::component::{ComponentDesc, ComponentValue};
 use crate::fetch::{FetchAccessData, FetchPrepareData, PreparedFetch, RandomFetch};
-use crate::system::Access;
+use crate::system::{Access, AccessKind};
 use crate::util::Ptr;
 use crate::{
     archetype::{ChangeKind, Slice},
@@ -187,6 +189,179 @@ impl<'w, 'q, T: ComponentValue> PreparedFetch<'q> for PreparedChangeFilter<'w, T
     }
 }
 
+/// Filter which yields entities where any of the given components were modified or added.
+///
+/// This is equivalent to combining a [`modified`](crate::FetchExt::modified) filter for each
+/// component using `|`, but scans the changes of all components together rather than evaluating
+/// a separate filter per component, which is preferable when checking many inputs.
+///
+/// Archetypes with at least one of the components are considered.
+///
+/// ```rust
+/// # use flax::{*, filter::any_modified};
+/// component! {
+///     position: f32,
+///     rotation: f32,
+///     scale: f32,
+/// }
+///
+/// let mut world = World::new();
+/// let a = Entity::builder()
+///     .set(position(), 0.0)
+///     .set(rotation(), 0.0)
+///     .spawn(&mut world);
+/// let b = Entity::builder().set(scale(), 1.0).spawn(&mut world);
+///
+/// let mut query = Query::new(entity_ids()).filter(any_modified([
+///     position().desc(),
+///     rotation().desc(),
+///     scale().desc(),
+/// ]));
+///
+/// assert_eq!(query.collect_vec(&world), [a, b]);
+/// assert!(query.collect_vec(&world).is_empty());
+///
+/// *world.get_mut(b, scale()).unwrap() = 2.0;
+/// assert_eq!(query.collect_vec(&world), [b]);
+/// ```
+pub fn any_modified(components: impl IntoIterator<Item = ComponentDesc>) -> AnyModified {
+    AnyModified {
+        components: components.into_iter().collect(),
+    }
+}
+
+/// See: [`any_modified`]
+#[derive(Debug, Clone)]
+pub struct AnyModified {
+    components: Vec<ComponentDesc>,
+}
+
+impl<'q> FetchItem<'q> for AnyModified {
+    type Item = ();
+}
+
+impl<'w> Fetch<'w> for AnyModified {
+    const MUTABLE: bool = false;
+
+    type Prepared = PreparedAnyModified<'w>;
+
+    fn prepare(&'w self, data: FetchPrepareData<'w>) -> Option<Self::Prepared> {
+        let changes: SmallVec<[_; 4]> = self
+            .components
+            .iter()
+            .filter_map(|desc| data.arch.changes(desc.key()))
+            .collect();
+
+        if changes.is_empty() {
+            return None;
+        }
+
+        // Make sure to enable modification tracking if it is actively used
+        for changes in &changes {
+            changes.set_track_modified();
+        }
+
+        Some(PreparedAnyModified {
+            changes,
+            old_tick: data.old_tick,
+        })
+    }
+
+    fn filter_arch(&self, data: FetchAccessData) -> bool {
+        self.components.iter().any(|desc| data.arch.has(desc.key()))
+    }
+
+    fn access(&self, data: FetchAccessData, dst: &mut Vec<Access>) {
+        dst.extend(
+            self.components
+                .iter()
+                .filter(|desc| data.arch.has(desc.key()))
+                .map(|desc| Access {
+                    kind: AccessKind::Archetype {
+                        id: data.arch_id,
+                        component: desc.key(),
+                    },
+                    mutable: false,
+                }),
+        )
+    }
+
+    fn describe(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str("any_modified(")?;
+        for (i, desc) in self.components.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            f.write_str(desc.name())?;
+        }
+        f.write_str(")")
+    }
+}
+
+#[doc(hidden)]
+pub struct PreparedAnyModified<'w> {
+    changes: SmallVec<[AtomicRef<'w, Changes>; 4]>,
+    old_tick: u32,
+}
+
+impl<'w, 'q> PreparedFetch<'q> for PreparedAnyModified<'w> {
+    type Item = ();
+    type Chunk = ();
+
+    const HAS_FILTER: bool = true;
+
+    #[inline]
+    unsafe fn filter_slots(&mut self, slots: Slice) -> Slice {
+        let mut first: Option<Slice> = None;
+
+        for changes in &self.changes {
+            // The changes are sorted by slot, so only the first overlapping change of each
+            // component can start before the current candidate
+            let change = changes
+                .get(ChangeKind::Modified)
+                .iter()
+                .filter(|v| v.tick > self.old_tick)
+                .take_while(|v| v.slice.start < slots.end)
+                .find_map(|v| v.slice.intersect(&slots));
+
+            if let Some(change) = change {
+                let earlier = match first {
+                    Some(cur) => {
+                        change.start < cur.start
+                            || (change.start == cur.start && change.end > cur.end)
+                    }
+                    None => true,
+                };
+
+                if earlier {
+                    first = Some(change);
+                }
+            }
+
+            // The remaining components can not yield more than all slots
+            if first == Some(slots) {
+                break;
+            }
+        }
+
+        first.unwrap_or(Slice::new(slots.end, slots.end))
+    }
+
+    #[inline]
+    unsafe fn create_chunk(&'q mut self, _: Slice) -> Self::Chunk {}
+
+    #[inline]
+    unsafe fn fetch_next(_: &mut Self::Chunk) -> Self::Item {}
+}
+
+impl<'w, 'q> RandomFetch<'q> for PreparedAnyModified<'w> {
+    #[inline]
+    unsafe fn fetch_shared(&'q self, _: Slot) -> Self::Item {}
+
+    #[inline]
+    unsafe fn fetch_shared_chunk(_: &Self::Chunk, _: Slot) -> Self::Item {}
+}
+
 #[doc(hidden)]
 #[cfg(test)]
 pub struct ChangeFetch<'w> {
@@ -154,6 +154,11 @@ where
     fn searcher(&self, searcher: &mut crate::ArchetypeSearcher) {
         self.fetch.searcher(searcher)
     }
+
+    #[inline]
+    fn on_borrow(&self) {
+        self.fetch.on_borrow()
+    }
 }
 
 pub struct PreparedCmp<'w, F, M> {
@@ -234,6 +239,218 @@ where
     }
 }
 
+/// Compares the items of both fetches of a [`CmpWith`] filter.
+///
+/// This trait is sealed, and implemented for [`Ordered`] and for closures of the form
+/// `Fn(L, R) -> bool`.
+pub trait CmpWithMethod<L, R>: sealed::Sealed<L, R> {
+    /// Returns true if the entity with the given items passes the comparison
+    fn compare(&self, lhs: L, rhs: R) -> bool;
+}
+
+mod sealed {
+    pub trait Sealed<L, R> {}
+
+    impl<L, R> Sealed<L, R> for super::Ordered {}
+
+    impl<L, R, F> Sealed<L, R> for F where F: Fn(L, R) -> bool {}
+}
+
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy)]
+/// Compares two values using the accepted orderings
+pub struct Ordered {
+    less: bool,
+    equal: bool,
+    greater: bool,
+}
+
+impl Ordered {
+    pub(crate) const LESS: Self = Self::new(true, false, false);
+    pub(crate) const LESS_EQ: Self = Self::new(true, true, false);
+    pub(crate) const GREATER: Self = Self::new(false, false, true);
+    pub(crate) const GREATER_EQ: Self = Self::new(false, true, true);
+    pub(crate) const EQUAL: Self = Self::new(false, true, false);
+
+    const fn new(less: bool, equal: bool, greater: bool) -> Self {
+        Self {
+            less,
+            equal,
+            greater,
+        }
+    }
+}
+
+impl<L, R> CmpWithMethod<L, R> for Ordered
+where
+    L: PartialOrd<R>,
+{
+    fn compare(&self, lhs: L, rhs: R) -> bool {
+        match lhs.partial_cmp(&rhs) {
+            Some(Ordering::Less) => self.less,
+            Some(Ordering::Equal) => self.equal,
+            Some(Ordering::Greater) => self.greater,
+            None => false,
+        }
+    }
+}
+
+impl<L, R, F> CmpWithMethod<L, R> for F
+where
+    F: Fn(L, R) -> bool,
+{
+    fn compare(&self, lhs: L, rhs: R) -> bool {
+        (self)(lhs, rhs)
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Filter which compares the items of two fetches for the same entity.
+///
+/// Yields the left hand side fetch
+pub struct CmpWith<L, R, M> {
+    lhs: L,
+    rhs: R,
+    method: M,
+}
+
+impl<L, R, M> CmpWith<L, R, M> {
+    /// Creates a new comparison filter between two fetches
+    pub const fn new(lhs: L, rhs: R, method: M) -> Self {
+        Self { lhs, rhs, method }
+    }
+}
+
+impl<'q, L: FetchItem<'q>, R, M> FetchItem<'q> for CmpWith<L, R, M> {
+    type Item = L::Item;
+}
+
+impl<'w, L, R, M> Fetch<'w> for CmpWith<L, R, M>
+where
+    L: Fetch<'w>,
+    L::Prepared: for<'x> RandomFetch<'x>,
+    R: Fetch<'w>,
+    R::Prepared: for<'x> RandomFetch<'x>,
+    M: for<'x> CmpWithMethod<
+            <L::Prepared as PreparedFetch<'x>>::Item,
+            <R::Prepared as PreparedFetch<'x>>::Item,
+        > + 'w,
+{
+    const MUTABLE: bool = L::MUTABLE;
+
+    type Prepared = PreparedCmpWith<'w, L::Prepared, R::Prepared, M>;
+
+    fn prepare(&'w self, data: FetchPrepareData<'w>) -> Option<Self::Prepared> {
+        Some(PreparedCmpWith {
+            lhs: self.lhs.prepare(data)?,
+            rhs: self.rhs.prepare(data)?,
+            method: &self.method,
+        })
+    }
+
+    fn filter_arch(&self, data: FetchAccessData) -> bool {
+        self.lhs.filter_arch(data) && self.rhs.filter_arch(data)
+    }
+
+    #[inline]
+    fn access(&self, data: FetchAccessData, dst: &mut Vec<Access>) {
+        self.lhs.access(data, dst);
+        self.rhs.access(data, dst);
+    }
+
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} <=> {:?} ({})",
+            FmtQuery(&self.lhs),
+            FmtQuery(&self.rhs),
+            &type_name::<M>()
+        )
+    }
+
+    fn searcher(&self, searcher: &mut crate::ArchetypeSearcher) {
+        self.lhs.searcher(searcher);
+        self.rhs.searcher(searcher);
+    }
+
+    #[inline]
+    fn on_borrow(&self) {
+        self.lhs.on_borrow();
+        self.rhs.on_borrow();
+    }
+}
+
+/// The prepared state of a [`CmpWith`] filter, holding both prepared fetches
+#[doc(hidden)]
+pub struct PreparedCmpWith<'w, L, R, M> {
+    lhs: L,
+    rhs: R,
+    method: &'w M,
+}
+
+impl<'w, 'q, L, R, M> RandomFetch<'q> for PreparedCmpWith<'w, L, R, M>
+where
+    L: for<'x> RandomFetch<'x>,
+    R: for<'x> RandomFetch<'x>,
+    M: for<'x> CmpWithMethod<<L as PreparedFetch<'x>>::Item, <R as PreparedFetch<'x>>::Item> + 'w,
+{
+    unsafe fn fetch_shared(&'q self, slot: Slot) -> Self::Item {
+        self.lhs.fetch_shared(slot)
+    }
+
+    unsafe fn fetch_shared_chunk(chunk: &Self::Chunk, slot: Slot) -> Self::Item {
+        L::fetch_shared_chunk(chunk, slot)
+    }
+}
+
+impl<'w, 'q, L, R, M> PreparedFetch<'q> for PreparedCmpWith<'w, L, R, M>
+where
+    L: for<'x> RandomFetch<'x>,
+    R: for<'x> RandomFetch<'x>,
+    M: for<'x> CmpWithMethod<<L as PreparedFetch<'x>>::Item, <R as PreparedFetch<'x>>::Item> + 'w,
+{
+    type Item = <L as PreparedFetch<'q>>::Item;
+
+    const HAS_FILTER: bool = true;
+
+    #[inline]
+    unsafe fn filter_slots(&mut self, slots: Slice) -> Slice {
+        let slots = self.lhs.filter_slots(slots);
+        let slots = self.rhs.filter_slots(slots);
+
+        let mut cmp = |slot: Slot| {
+            let lhs = unsafe { self.lhs.fetch_shared(slot) };
+            let rhs = unsafe { self.rhs.fetch_shared(slot) };
+            self.method.compare(lhs, rhs)
+        };
+
+        // Find the first slot which yield true
+        let first = slots.iter().position(&mut cmp).unwrap_or(slots.len());
+
+        let count = slots
+            .iter()
+            .skip(first)
+            .take_while(|&slot| cmp(slot))
+            .count();
+
+        Slice {
+            start: slots.start + first,
+            end: slots.start + first + count,
+        }
+    }
+
+    type Chunk = <L as PreparedFetch<'q>>::Chunk;
+
+    unsafe fn create_chunk(&'q mut self, slots: Slice) -> Self::Chunk {
+        self.lhs.create_chunk(slots)
+    }
+
+    #[inline]
+    unsafe fn fetch_next(chunk: &mut Self::Chunk) -> Self::Item {
+        L::fetch_next(chunk)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use alloc::string::ToString;
@@ -241,7 +458,8 @@ mod test {
     use pretty_assertions::assert_eq;
 
     use crate::{
-        component, components::name, entity_ids, BatchSpawn, CommandBuffer, FetchExt, Query, World,
+        component, components::name, entity_ids, BatchSpawn, CommandBuffer, Entity, FetchExt,
+        Query, World,
     };
 
     #[test]
@@ -317,4 +535,36 @@ mod test {
 
         assert_eq!(changed.collect_vec(&world), changed_ids);
     }
+
+    #[test]
+    fn cmp_component() {
+        component! {
+            health: i32,
+            max_health: i32,
+        }
+
+        let mut world = World::new();
+
+        let ids = (0..8)
+            .map(|i| {
+                Entity::builder()
+                    .set(health(), i)
+                    .set(max_health(), 4)
+                    .spawn(&mut world)
+            })
+            .collect_vec();
+
+        // Missing the right hand side
+        Entity::builder().set(health(), 10).spawn(&mut world);
+
+        let mut query = Query::new(entity_ids()).filter(health().gt_component(max_health()));
+        assert_eq!(query.collect_vec(&world), ids[5..]);
+
+        let mut query = Query::new(entity_ids()).filter(health().eq_component(max_health()));
+        assert_eq!(query.collect_vec(&world), [ids[4]]);
+
+        let mut query = Query::new(entity_ids())
+            .filter(health().cmp_with(max_health(), |a: &i32, b: &i32| *a + *b < 8));
+        assert_eq!(query.collect_vec(&world), ids[..4]);
+    }
 }
@@ -2,7 +2,7 @@ use crate::{
     archetype::{Slice, Slot},
     fetch::{FetchAccessData, FetchPrepareData, PreparedFetch, RandomFetch},
     system::Access,
-    Entity, Fetch, FetchItem,
+    ArchetypeSearcher, Entity, Fetch, FetchItem,
 };
 use alloc::vec::Vec;
 use core::fmt::{self, Formatter};
@@ -24,7 +24,8 @@ impl<'a> Fetch<'a> for Nothing {
 
     #[inline(always)]
     fn prepare(&self, _: FetchPrepareData) -> Option<Self::Prepared> {
-        unreachable!()
+        // Prepared when nested in e.g; `Or` or `Not`, which do not forward the searcher
+        None
     }
 
     #[inline(always)]
@@ -37,6 +38,10 @@ impl<'a> Fetch<'a> for Nothing {
     }
 
     fn access(&self, _data: FetchAccessData, _dst: &mut Vec<Access>) {}
+
+    fn searcher(&self, searcher: &mut ArchetypeSearcher) {
+        searcher.set_unsatisfiable()
+    }
 }
 
 impl StaticFilter for Nothing {
@@ -71,6 +76,7 @@ impl<'q> FetchItem<'q> for All {
 
 impl<'w> Fetch<'w> for All {
     const MUTABLE: bool = false;
+    const TRIVIAL_FILTER_ARCH: bool = true;
 
     type Prepared = All;
 
@@ -222,7 +228,7 @@ impl<'w> PreparedFetch<'w> for PreparedEntity {
     const HAS_FILTER: bool = false;
 
     unsafe fn create_chunk(&'w mut self, slots: Slice) -> Self::Chunk {
-        assert!(slots.start == self.slot && slots.end == self.slot);
+        assert!(slots.start == self.slot && slots.end == self.slot + 1);
         self.id
     }
 
@@ -307,6 +313,12 @@ impl<'w> Fetch<'w> for bool {
 
     #[inline]
     fn access(&self, _: FetchAccessData, _: &mut Vec<Access>) {}
+
+    fn searcher(&self, searcher: &mut ArchetypeSearcher) {
+        if !*self {
+            searcher.set_unsatisfiable()
+        }
+    }
 }
 
 impl StaticFilter for bool {
@@ -0,0 +1,187 @@
+use core::fmt::{self, Formatter};
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use atomic_refcell::AtomicRefCell;
+
+use crate::{
+    archetype::Slice,
+    component::ComponentValue,
+    fetch::{FetchAccessData, FetchPrepareData, FmtQuery, PreparedFetch},
+    system::Access,
+    util::LocalRef,
+    ArchetypeSearcher, Entity, Fetch, FetchItem, World,
+};
+
+/// Filter which only yields entities whose key differs from the key last seen for the entity.
+///
+/// The key is the item of the `key` fetch, and is compared to the key which was recorded the
+/// previous time the entity was yielded. Entities which have not been yielded before are always
+/// yielded.
+///
+/// This is useful for recomputing derived data only when the inputs actually changed, even if
+/// they were spuriously modified, such as by writing the same value.
+///
+/// See [`Query::dedup_by`](crate::Query::dedup_by).
+///
+/// **Note**: the key is recorded when the batch containing the entity is yielded, after all other
+/// filters have been applied. Entities which are only counted through
+/// [`Query::count_filtered`](crate::Query::count_filtered) are not recorded. The key may not refer
+/// to components which are fetched mutably by the query.
+///
+/// Keys of despawned entities are removed periodically, as the number of recorded keys grows.
+pub struct Dedup<K, V> {
+    key: K,
+    cache: AtomicRefCell<Cache<V>>,
+}
+
+/// The last key yielded for each entity
+struct Cache<V> {
+    keys: BTreeMap<Entity, V>,
+    /// The number of keys the last time despawned entities were removed
+    pruned_len: usize,
+}
+
+impl<V> Default for Cache<V> {
+    fn default() -> Self {
+        Self {
+            keys: BTreeMap::new(),
+            pruned_len: 0,
+        }
+    }
+}
+
+impl<V> Cache<V> {
+    /// Removes the keys of despawned entities once the number of keys has doubled since the last
+    /// time, which amortizes the cost over the insertions
+    fn prune(&mut self, world: &World) {
+        if self.keys.len() <= (self.pruned_len * 2).max(64) {
+            return;
+        }
+
+        self.keys.retain(|&id, _| world.is_alive(id));
+        self.pruned_len = self.keys.len();
+    }
+}
+
+impl<K, V> Dedup<K, V> {
+    /// Creates a new filter comparing the item of `key` to the last seen key for each entity.
+    pub fn new(key: K) -> Self {
+        Self {
+            key,
+            cache: Default::default(),
+        }
+    }
+}
+
+impl<K, V> fmt::Debug for Dedup<K, V>
+where
+    K: for<'x> Fetch<'x>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dedup")
+            .field("key", &FmtQuery(&self.key))
+            .finish()
+    }
+}
+
+impl<'q, K, V> FetchItem<'q> for Dedup<K, V> {
+    type Item = ();
+}
+
+impl<'w, K, V> Fetch<'w> for Dedup<K, V>
+where
+    K: Fetch<'w>,
+    K: for<'x> FetchItem<'x, Item = V>,
+    V: ComponentValue + PartialEq,
+{
+    const MUTABLE: bool = false;
+
+    type Prepared = PreparedDedup<'w, K::Prepared, V>;
+
+    fn prepare(&'w self, data: FetchPrepareData<'w>) -> Option<Self::Prepared> {
+        let key = self.key.prepare(data)?;
+        self.cache.borrow_mut().prune(data.world);
+
+        Some(PreparedDedup {
+            key,
+            ids: data.arch.entities(),
+            cache: LocalRef::new(&self.cache),
+        })
+    }
+
+    fn filter_arch(&self, data: FetchAccessData) -> bool {
+        self.key.filter_arch(data)
+    }
+
+    fn access(&self, data: FetchAccessData, dst: &mut Vec<Access>) {
+        self.key.access(data, dst)
+    }
+
+    fn describe(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "dedup {:?}", FmtQuery(&self.key))
+    }
+
+    fn searcher(&self, searcher: &mut ArchetypeSearcher) {
+        self.key.searcher(searcher)
+    }
+
+    #[inline]
+    fn on_borrow(&self) {
+        self.key.on_borrow()
+    }
+}
+
+#[doc(hidden)]
+pub struct PreparedDedup<'w, K, V> {
+    key: K,
+    ids: &'w [Entity],
+    /// Shared by all archetypes, as an entity keeps its key when moved to another archetype
+    cache: LocalRef<'w, Cache<V>>,
+}
+
+impl<'w, 'q, K, V> PreparedFetch<'q> for PreparedDedup<'w, K, V>
+where
+    K: for<'x> PreparedFetch<'x, Item = V>,
+    V: PartialEq,
+{
+    type Item = ();
+    type Chunk = ();
+
+    const HAS_FILTER: bool = true;
+
+    unsafe fn filter_slots(&mut self, slots: Slice) -> Slice {
+        let cache = self.cache.borrow_mut();
+        let mut chunk = self.key.create_chunk(slots);
+
+        // The leftmost run of changed keys
+        let mut start = slots.end;
+        let mut end = slots.end;
+
+        for slot in slots.as_range() {
+            let value = <K as PreparedFetch<'_>>::fetch_next(&mut chunk);
+
+            if cache.keys.get(&self.ids[slot]) != Some(&value) {
+                start = start.min(slot);
+            } else if start != slots.end {
+                end = slot;
+                break;
+            }
+        }
+
+        Slice::new(start, end)
+    }
+
+    unsafe fn create_chunk(&'q mut self, slots: Slice) -> Self::Chunk {
+        // The chunk is created once the slots are yielded, after all filters have been applied
+        let mut cache = self.cache.borrow_mut();
+        let mut chunk = self.key.create_chunk(slots);
+
+        for &id in &self.ids[slots.as_range()] {
+            let value = <K as PreparedFetch<'_>>::fetch_next(&mut chunk);
+            cache.keys.insert(id, value);
+        }
+    }
+
+    #[inline]
+    unsafe fn fetch_next(_: &mut Self::Chunk) -> Self::Item {}
+}
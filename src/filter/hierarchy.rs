@@ -0,0 +1,179 @@
+use core::{
+    fmt::{self, Formatter},
+    ops,
+};
+
+use alloc::{collections::BTreeSet, vec::Vec};
+
+use crate::{
+    archetype::Archetype,
+    component::{ComponentKey, ComponentValue},
+    fetch::{FetchAccessData, FetchPrepareData},
+    relation::RelationExt,
+    system::Access,
+    Component, Entity, Fetch, FetchItem, World,
+};
+
+use super::{All, And, Or};
+
+/// Filter which yields entities where any ancestor along the specified relation has the
+/// component, such as for inherited visibility in a scene graph.
+///
+/// The entity itself is not considered, combine with [`With`](crate::filter::With) using `|` to
+/// include it.
+///
+/// Use `!` to yield the entities where no ancestor has the component, which includes entities
+/// without any ancestors. Prefer this over wrapping the filter in [`Not`](crate::filter::Not),
+/// as the latter is decided once per archetype and would exclude all entities with the relation.
+///
+/// ```rust
+/// # use flax::{*, components::child_of, filter::ancestor_has};
+/// component! {
+///     hidden: (),
+/// }
+///
+/// let mut world = World::new();
+///
+/// let root = Entity::builder().tag(hidden()).spawn(&mut world);
+/// let child = Entity::builder().set(child_of(root), ()).spawn(&mut world);
+/// let grandchild = Entity::builder().set(child_of(child), ()).spawn(&mut world);
+///
+/// let mut query = Query::new(entity_ids()).filter(ancestor_has(child_of, hidden()));
+/// assert_eq!(query.collect_vec(&world), [child, grandchild]);
+///
+/// let mut visible = Query::new(entity_ids())
+///     .with_relation(child_of)
+///     .filter(!ancestor_has(child_of, hidden()));
+/// assert!(visible.collect_vec(&world).is_empty());
+///
+/// world.remove(root, hidden()).unwrap();
+/// assert_eq!(visible.collect_vec(&world), [child, grandchild]);
+/// ```
+///
+/// # Cost
+///
+/// The relation targets of an entity are part of its archetype, which means all entities of an
+/// archetype share the same ancestors. The ancestors are therefore walked once per archetype each
+/// time the query is borrowed, rather than per entity, and the result always reflects the
+/// current state of the world. The cost is proportional to the depth of the hierarchy, and to the
+/// number of targets for non-exclusive relations.
+///
+/// # Cycles
+///
+/// Each ancestor is visited at most once, so cyclic relations terminate.
+pub fn ancestor_has<T, U>(relation: impl RelationExt<T>, component: Component<U>) -> AncestorHas
+where
+    T: ComponentValue,
+    U: ComponentValue,
+{
+    AncestorHas {
+        relation: relation.id(),
+        relation_name: relation.vtable().name,
+        component: component.key(),
+        name: component.name(),
+        negated: false,
+    }
+}
+
+/// See: [`ancestor_has`]
+#[derive(Debug, Clone)]
+pub struct AncestorHas {
+    relation: Entity,
+    relation_name: &'static str,
+    component: ComponentKey,
+    name: &'static str,
+    negated: bool,
+}
+
+impl AncestorHas {
+    fn matches(&self, world: &World, arch: &Archetype) -> bool {
+        let mut visited = BTreeSet::new();
+        let mut stack: Vec<_> = targets(arch, self.relation).collect();
+
+        while let Some(id) = stack.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+
+            let Ok(loc) = world.location(id) else {
+                continue;
+            };
+
+            let arch = world.archetypes.get(loc.arch_id);
+            if arch.has(self.component) {
+                return true;
+            }
+
+            stack.extend(targets(arch, self.relation));
+        }
+
+        false
+    }
+}
+
+fn targets(arch: &Archetype, relation: Entity) -> impl Iterator<Item = Entity> + '_ {
+    arch.relations_like(relation)
+        .map(|(key, _)| key.target.unwrap())
+}
+
+impl<'q> FetchItem<'q> for AncestorHas {
+    type Item = ();
+}
+
+impl<'w> Fetch<'w> for AncestorHas {
+    const MUTABLE: bool = false;
+
+    type Prepared = All;
+
+    fn prepare(&self, data: FetchPrepareData) -> Option<Self::Prepared> {
+        if self.matches(data.world, data.arch) != self.negated {
+            Some(All)
+        } else {
+            None
+        }
+    }
+
+    fn filter_arch(&self, data: FetchAccessData) -> bool {
+        // The ancestors may change without creating new archetypes, so they are only checked
+        // when preparing
+        self.negated || targets(data.arch, self.relation).next().is_some()
+    }
+
+    fn describe(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.negated {
+            f.write_str("!")?;
+        }
+
+        write!(f, "ancestor_has({}, {})", self.relation_name, self.name)
+    }
+
+    #[inline]
+    fn access(&self, _: FetchAccessData, _: &mut Vec<Access>) {}
+}
+
+impl<R> ops::BitOr<R> for AncestorHas {
+    type Output = Or<(Self, R)>;
+
+    fn bitor(self, rhs: R) -> Self::Output {
+        Or((self, rhs))
+    }
+}
+
+impl<R> ops::BitAnd<R> for AncestorHas {
+    type Output = And<Self, R>;
+
+    fn bitand(self, rhs: R) -> Self::Output {
+        And(self, rhs)
+    }
+}
+
+impl ops::Not for AncestorHas {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self {
+            negated: !self.negated,
+            ..self
+        }
+    }
+}
@@ -1,9 +1,12 @@
 mod change;
 mod cmp;
 mod constant;
+mod dedup;
+mod hierarchy;
 mod set;
+mod take;
 
-use alloc::vec::Vec;
+use alloc::{collections::BTreeSet, vec::Vec};
 use core::{
     any::type_name,
     fmt::{self, Formatter},
@@ -12,7 +15,7 @@ use core::{
 };
 
 use crate::{
-    archetype::{Archetype, Slice, Slot},
+    archetype::{Archetype, ArchetypeId, Slice, Slot},
     component::ComponentKey,
     components::component_info,
     fetch::{FetchAccessData, FetchPrepareData, PreparedFetch},
@@ -20,11 +23,14 @@ use crate::{
     ArchetypeSearcher, Entity, Fetch, FetchItem,
 };
 
-pub use change::ChangeFilter;
-pub use cmp::{Cmp, Equal, Greater, GreaterEq, Less, LessEq};
+pub use change::{any_modified, AnyModified, ChangeFilter};
+pub use cmp::{Cmp, CmpWith, CmpWithMethod, Equal, Greater, GreaterEq, Less, LessEq, Ordered};
 pub(crate) use constant::NoEntities;
 pub use constant::{All, Nothing};
+pub use dedup::Dedup;
+pub use hierarchy::{ancestor_has, AncestorHas};
 pub use set::{And, Not, Or, Union};
+pub use take::Take;
 
 macro_rules! gen_bitops {
     ($ty:ident[$($p: tt),*]) => {
@@ -96,14 +102,18 @@ where
 {
     /// Only F is fetched
     const MUTABLE: bool = Q::MUTABLE;
+    const TRIVIAL_FILTER_ARCH: bool = Q::TRIVIAL_FILTER_ARCH && F::TRIVIAL_FILTER_ARCH;
 
     type Prepared = Filtered<Q::Prepared, F::Prepared>;
 
     #[inline]
     fn prepare(&'w self, data: FetchPrepareData<'w>) -> Option<Self::Prepared> {
+        // The filter is prepared first, such that nothing is borrowed if it rejects the archetype
+        let filter = self.filter.prepare(data)?;
+
         Some(Filtered {
             fetch: self.fetch.prepare(data)?,
-            filter: self.filter.prepare(data)?,
+            filter,
             include_components: self.include_components,
         })
     }
@@ -132,6 +142,16 @@ where
     fn searcher(&self, searcher: &mut ArchetypeSearcher) {
         self.fetch.searcher(searcher);
         self.filter.searcher(searcher);
+
+        if !self.include_components {
+            searcher.add_excluded(component_info().key());
+        }
+    }
+
+    #[inline]
+    fn on_borrow(&self) {
+        self.fetch.on_borrow();
+        self.filter.on_borrow();
     }
 }
 
@@ -152,6 +172,8 @@ where
     type Chunk = Q::Chunk;
 
     unsafe fn create_chunk(&'q mut self, slots: Slice) -> Self::Chunk {
+        // Notify the filter that the slots are yielded, such that e.g. `Dedup` records the keys
+        self.filter.create_chunk(slots);
         self.fetch.create_chunk(slots)
     }
 
@@ -163,6 +185,8 @@ where
 gen_bitops! {
     All[];
     And[A,B];
+    AnyModified[];
+    InArchetypes[];
     BatchSize[];
     ChangeFilter[T];
     Nothing[];
@@ -173,6 +197,8 @@ gen_bitops! {
     WithoutRelation[];
     Without[];
     Cmp[A,B];
+    CmpWith[A,B,C];
+    Dedup[K,V];
 }
 
 #[derive(Debug, Clone)]
@@ -289,6 +315,7 @@ impl<'q> FetchItem<'q> for Without {
 
 impl<'w> Fetch<'w> for Without {
     const MUTABLE: bool = false;
+    const TRIVIAL_FILTER_ARCH: bool = true;
 
     type Prepared = All;
 
@@ -310,6 +337,10 @@ impl<'w> Fetch<'w> for Without {
 
     #[inline]
     fn access(&self, _: FetchAccessData, _: &mut Vec<Access>) {}
+
+    fn searcher(&self, searcher: &mut ArchetypeSearcher) {
+        searcher.add_excluded(self.component)
+    }
 }
 
 impl StaticFilter for Without {
@@ -318,6 +349,86 @@ impl StaticFilter for Without {
     }
 }
 
+/// Filter which only yields entities in the given archetypes, such as a set of archetypes
+/// discovered earlier which should be processed as a fixed snapshot.
+///
+/// The archetype of an entity is available through
+/// [`EntityLocation::arch_id`](crate::EntityLocation::arch_id), and all archetypes are listed by
+/// [`World::archetype_info`](crate::World::archetype_info).
+///
+/// Archetypes created afterwards are never matched, even if they have the same components as one
+/// of the given archetypes.
+///
+/// ```rust
+/// # use flax::{*, filter::in_archetypes};
+/// component! {
+///     health: f32,
+///     armor: f32,
+/// }
+///
+/// let mut world = World::new();
+/// let a = Entity::builder().set(health(), 50.0).spawn(&mut world);
+/// let b = Entity::builder()
+///     .set(health(), 50.0)
+///     .set(armor(), 10.0)
+///     .spawn(&mut world);
+///
+/// let arch_id = world.location(a).unwrap().arch_id();
+/// let mut query = Query::new(entity_ids()).filter(in_archetypes([arch_id]));
+/// assert_eq!(query.collect_vec(&world), [a]);
+///
+/// // Entities moving into the archetype are included
+/// world.remove(b, armor()).unwrap();
+/// assert_eq!(query.collect_vec(&world), [a, b]);
+/// ```
+///
+/// # Invalidation
+///
+/// Archetypes which become empty may be removed by [`World::prune_archetypes`](crate::World::prune_archetypes),
+/// after which the id no longer refers to the archetype, and a recreated archetype with the same
+/// components receives a new id.
+pub fn in_archetypes(archetypes: impl IntoIterator<Item = ArchetypeId>) -> InArchetypes {
+    InArchetypes {
+        archetypes: archetypes.into_iter().collect(),
+    }
+}
+
+/// See: [`in_archetypes`]
+#[derive(Debug, Clone)]
+pub struct InArchetypes {
+    archetypes: BTreeSet<ArchetypeId>,
+}
+
+impl<'q> FetchItem<'q> for InArchetypes {
+    type Item = ();
+}
+
+impl<'w> Fetch<'w> for InArchetypes {
+    const MUTABLE: bool = false;
+
+    type Prepared = All;
+
+    fn prepare(&self, data: FetchPrepareData) -> Option<Self::Prepared> {
+        if self.archetypes.contains(&data.arch_id) {
+            Some(All)
+        } else {
+            None
+        }
+    }
+
+    fn filter_arch(&self, data: FetchAccessData) -> bool {
+        self.archetypes.contains(&data.arch_id)
+    }
+
+    fn describe(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("in_archetypes ")?;
+        f.debug_set().entries(&self.archetypes).finish()
+    }
+
+    #[inline]
+    fn access(&self, _: FetchAccessData, _: &mut Vec<Access>) {}
+}
+
 #[derive(Debug, Clone)]
 /// Yields all entities with the relation of the specified kind
 pub(crate) struct WithTarget {
@@ -522,6 +633,11 @@ where
     fn searcher(&self, searcher: &mut ArchetypeSearcher) {
         (*self.0).searcher(searcher)
     }
+
+    #[inline]
+    fn on_borrow(&self) {
+        (*self.0).on_borrow()
+    }
 }
 
 impl<'a, 'q, F> FetchItem<'q> for &'a F
@@ -564,6 +680,11 @@ where
     fn searcher(&self, searcher: &mut ArchetypeSearcher) {
         (*self).searcher(searcher)
     }
+
+    #[inline]
+    fn on_borrow(&self) {
+        (*self).on_borrow()
+    }
 }
 
 /// Limit the batch size for a query
@@ -34,6 +34,7 @@ where
     R: Fetch<'w>,
 {
     const MUTABLE: bool = false;
+    const TRIVIAL_FILTER_ARCH: bool = L::TRIVIAL_FILTER_ARCH && R::TRIVIAL_FILTER_ARCH;
 
     type Prepared = And<L::Prepared, R::Prepared>;
 
@@ -63,6 +64,11 @@ where
         self.0.searcher(searcher);
         self.1.searcher(searcher);
     }
+
+    fn on_borrow(&self) {
+        self.0.on_borrow();
+        self.1.on_borrow();
+    }
 }
 
 impl<'q, L, R> PreparedFetch<'q> for And<L, R>
@@ -128,6 +134,10 @@ where
     fn describe(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "!{:?}", FmtQuery(&self.0))
     }
+
+    fn on_borrow(&self) {
+        self.0.on_borrow()
+    }
 }
 
 impl<'q, F> PreparedFetch<'q> for Not<Option<F>>
@@ -226,6 +236,10 @@ where
     fn describe(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_tuple("Union").field(&FmtQuery(&self.0)).finish()
     }
+
+    fn on_borrow(&self) {
+        self.0.on_borrow()
+    }
 }
 
 impl<T> UnionFilter for Union<T>
@@ -299,6 +313,10 @@ macro_rules! tuple_impl {
                 )*
                 s.finish()
             }
+
+            fn on_borrow(&self) {
+                $(self.0.$idx.on_borrow();)*
+            }
         }
 
         impl<$($ty: StaticFilter, )*> StaticFilter for Or<($($ty,)*)> {
@@ -0,0 +1,123 @@
+use core::{
+    fmt::{self, Formatter},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use alloc::vec::Vec;
+
+use crate::{
+    archetype::Slice,
+    fetch::{FetchAccessData, FetchPrepareData, PreparedFetch},
+    system::Access,
+    Fetch, FetchItem,
+};
+
+/// Filter which only yields the first `limit` entities of each borrow of the query, across all
+/// archetypes.
+///
+/// The budget is restored every time the query is borrowed, and is shared by everything which
+/// reads through the same [`QueryBorrow`](crate::QueryBorrow). Fetching an entity using
+/// [`QueryBorrow::get`](crate::QueryBorrow::get) thus counts towards the limit of a subsequent
+/// iteration.
+///
+/// Archetypes are prepared as they are iterated, so no further archetypes are borrowed once the
+/// limit is reached.
+///
+/// This is useful for spreading expensive per-entity work across frames, by only processing a
+/// limited number of entities each time the query is borrowed.
+///
+/// See [`Query::take`](crate::Query::take).
+///
+/// **Note**: the limit counts the slots which pass the filters *before* this one. It should
+/// therefore be the last filter of the query, as entities rejected by subsequent filters still
+/// count towards the limit.
+pub struct Take {
+    limit: usize,
+    remaining: AtomicUsize,
+}
+
+impl Take {
+    /// Creates a new filter yielding at most `limit` entities
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            remaining: AtomicUsize::new(limit),
+        }
+    }
+}
+
+impl fmt::Debug for Take {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Take").field("limit", &self.limit).finish()
+    }
+}
+
+impl<'q> FetchItem<'q> for Take {
+    type Item = ();
+}
+
+impl<'w> Fetch<'w> for Take {
+    const MUTABLE: bool = false;
+
+    type Prepared = PreparedTake<'w>;
+
+    fn prepare(&'w self, _: FetchPrepareData<'w>) -> Option<Self::Prepared> {
+        if self.remaining.load(Ordering::Relaxed) == 0 {
+            return None;
+        }
+
+        Some(PreparedTake {
+            remaining: &self.remaining,
+        })
+    }
+
+    #[inline]
+    fn filter_arch(&self, _: FetchAccessData) -> bool {
+        true
+    }
+
+    #[inline]
+    fn access(&self, _: FetchAccessData, _: &mut Vec<Access>) {}
+
+    fn describe(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "take {}", self.limit)
+    }
+
+    fn on_borrow(&self) {
+        self.remaining.store(self.limit, Ordering::Relaxed);
+    }
+}
+
+#[doc(hidden)]
+pub struct PreparedTake<'w> {
+    remaining: &'w AtomicUsize,
+}
+
+impl<'q, 'w> PreparedFetch<'q> for PreparedTake<'w> {
+    type Item = ();
+    type Chunk = ();
+
+    const HAS_FILTER: bool = true;
+
+    unsafe fn filter_slots(&mut self, slots: Slice) -> Slice {
+        let remaining = self
+            .remaining
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |v| {
+                Some(v - v.min(slots.len()))
+            })
+            .unwrap();
+
+        let count = slots.len().min(remaining);
+        if count == 0 {
+            return Slice::new(slots.end, slots.end);
+        }
+
+        Slice::new(slots.start, slots.start + count)
+    }
+
+    #[inline]
+    unsafe fn create_chunk(&'q mut self, _: Slice) -> Self::Chunk {}
+
+    #[inline]
+    unsafe fn fetch_next(_: &mut Self::Chunk) -> Self::Item {}
+}
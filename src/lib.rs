@@ -210,6 +210,8 @@ pub mod buffer;
 pub mod commands;
 /// Low level component construction
 pub mod component;
+/// Compute and apply the difference between worlds
+pub mod diff;
 /// Provides entity identifiers
 pub mod entity;
 /// Filter items yielded queries
@@ -256,26 +258,30 @@ pub mod vtable;
 mod writer;
 
 // Required due to macro
-pub use archetype::{BatchSpawn, RefMut};
+pub use archetype::{BatchSpawn, LazyRefMut, RefMut};
 pub use commands::CommandBuffer;
 pub use component::Component;
-pub use entity::{entity_ids, Entity, EntityBuilder};
-pub use entity_ref::{EntityRef, EntityRefMut};
+pub use entity::{entity_ids, Entity, EntityBuilder, EntityLocation, EntityPool};
+pub use entity_ref::{EntityRef, EntityRefMut, ReadOnlyEntityRef};
 pub use entry::{Entry, OccupiedEntry, VacantEntry};
 pub use error::Error;
 pub use fetch::{
-    relations_like, EntityIds, Fetch, FetchExt, FetchItem, Mutable, Opt, OptOr, Relations,
+    relations, relations_like, relations_like_mut, EntityIds, Fetch, FetchExt, FetchItem, Mutable,
+    Opt, OptOr, Relations,
 };
 
-pub use metadata::{Debuggable, Exclusive};
+pub use metadata::{Cloneable, Debuggable, Diffable, Exclusive};
 
 pub use query::{
-    Children, Dfs, DfsBorrow, DfsIter, EntityBorrow, EntityQuery, Planar, Query, QueryBorrow,
-    QueryIter, Topo,
+    Bucketed, Children, Dfs, DfsBorrow, DfsIter, EntityBorrow, EntityQuery, Planar, Query,
+    QueryBorrow, QueryIter, QueryIterRev, Topo,
 };
 pub use relation::RelationExt;
-pub use schedule::{Schedule, ScheduleBuilder, SystemInfo};
-pub use system::{BoxedSystem, SharedResource, System, SystemBuilder};
+pub use schedule::{
+    AccessConflict, ConflictReport, Schedule, ScheduleBuilder, ScheduleDiagnostic,
+    SystemDescription, SystemInfo,
+};
+pub use system::{BoxedSystem, Resources, SharedResource, System, SystemBuilder};
 pub use world::World;
 
 pub(crate) use query::ArchetypeSearcher;
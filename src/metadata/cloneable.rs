@@ -0,0 +1,36 @@
+use crate::{
+    buffer::ComponentBuffer,
+    component::{ComponentDesc, ComponentValue},
+};
+
+use super::Metadata;
+
+component! {
+    /// Allows cloning the component when using an entity as a template
+    pub cloneable: Cloneable,
+}
+
+#[derive(Clone)]
+/// Clones component values using [`Clone`].
+///
+/// Only components with this metadata are copied by
+/// [`World::entity_builder_from`](crate::World::entity_builder_from).
+pub struct Cloneable {
+    pub(crate) clone: unsafe fn(*const u8, ComponentDesc, &mut ComponentBuffer),
+}
+
+impl<T> Metadata<T> for Cloneable
+where
+    T: Sized + Clone + ComponentValue,
+{
+    fn attach(_: ComponentDesc, buffer: &mut ComponentBuffer) {
+        buffer.set(
+            cloneable(),
+            Cloneable {
+                clone: |src, desc, buffer| unsafe {
+                    buffer.set(desc.downcast::<T>(), (*src.cast::<T>()).clone());
+                },
+            },
+        );
+    }
+}
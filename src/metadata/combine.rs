@@ -0,0 +1,85 @@
+use core::{marker::PhantomData, ops::AddAssign};
+
+use crate::{
+    buffer::ComponentBuffer,
+    component::{ComponentDesc, ComponentValue},
+};
+
+use super::Metadata;
+
+component! {
+    /// Combines a value set to a component which already exists with the current value.
+    ///
+    /// See [`Combine`].
+    pub combine: CombineInfo,
+}
+
+/// Combines a new value of a component into the current value.
+///
+/// See [`Combine`].
+pub trait CombineWith<T>: 'static {
+    /// Combine `value` into `current`
+    fn combine(current: &mut T, value: T);
+}
+
+/// Combines the value with the current value when a component is set on an entity which
+/// already has it, rather than replacing it.
+///
+/// This applies to [`World::set`], [`CommandBuffer::set`], and
+/// [`EntityBuilder::append_to`]. Setting a component which the entity does not have inserts
+/// the value as is.
+///
+/// Combining a value counts as a modification, and fires the same change events as replacing
+/// it. As the previous value is not replaced, [`World::set`] returns `None`.
+///
+/// ```rust
+/// # use flax::{*, metadata::{Combine, Sum}};
+/// component! {
+///     damage: f32 => [Combine<Sum>],
+/// }
+///
+/// let mut world = World::new();
+/// let id = world.spawn();
+///
+/// world.set(id, damage(), 5.0).unwrap();
+/// world.set(id, damage(), 2.5).unwrap();
+///
+/// assert_eq!(world.get_copy(id, damage()), Ok(7.5));
+/// ```
+///
+/// [`World::set`]: crate::World::set
+/// [`CommandBuffer::set`]: crate::CommandBuffer::set
+/// [`EntityBuilder::append_to`]: crate::EntityBuilder::append_to
+pub struct Combine<F>(PhantomData<F>);
+
+/// Combines values by adding them together
+pub struct Sum;
+
+impl<T: AddAssign> CombineWith<T> for Sum {
+    fn combine(current: &mut T, value: T) {
+        *current += value;
+    }
+}
+
+/// Type erased combine function of a component
+#[derive(Clone)]
+pub struct CombineInfo {
+    pub(crate) combine: unsafe fn(*mut u8, *mut u8),
+}
+
+impl<T, F> Metadata<T> for Combine<F>
+where
+    T: ComponentValue,
+    F: CombineWith<T>,
+{
+    fn attach(_: ComponentDesc, buffer: &mut ComponentBuffer) {
+        buffer.set(
+            combine(),
+            CombineInfo {
+                combine: |current, value| unsafe {
+                    F::combine(&mut *current.cast::<T>(), value.cast::<T>().read())
+                },
+            },
+        );
+    }
+}
@@ -0,0 +1,38 @@
+use crate::{
+    buffer::ComponentBuffer,
+    component::{ComponentDesc, ComponentValue},
+};
+
+use super::Metadata;
+
+component! {
+    /// Allows comparing and cloning the component when computing the difference between worlds
+    pub diffable: Diffable,
+}
+
+#[derive(Clone)]
+/// Compares and clones component values using [`PartialEq`] and [`Clone`].
+///
+/// Only components with this metadata are considered by
+/// [`World::diff`](crate::World::diff).
+pub struct Diffable {
+    pub(crate) eq: unsafe fn(*const u8, *const u8) -> bool,
+    pub(crate) clone: unsafe fn(*const u8, ComponentDesc, &mut ComponentBuffer),
+}
+
+impl<T> Metadata<T> for Diffable
+where
+    T: Sized + PartialEq + Clone + ComponentValue,
+{
+    fn attach(_: ComponentDesc, buffer: &mut ComponentBuffer) {
+        buffer.set(
+            diffable(),
+            Diffable {
+                eq: |a, b| unsafe { *a.cast::<T>() == *b.cast::<T>() },
+                clone: |src, desc, buffer| unsafe {
+                    buffer.set(desc.downcast::<T>(), (*src.cast::<T>()).clone());
+                },
+            },
+        );
+    }
+}
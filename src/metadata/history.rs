@@ -0,0 +1,160 @@
+use core::any::Any;
+
+use alloc::{
+    boxed::Box,
+    collections::{vec_deque, BTreeMap, VecDeque},
+};
+
+use crate::{
+    buffer::ComponentBuffer,
+    component::{ComponentDesc, ComponentValue},
+    Entity,
+};
+
+use super::Metadata;
+
+component! {
+    /// Keeps the last values of the component for each entity.
+    ///
+    /// See [`History`].
+    pub history: HistoryInfo,
+}
+
+/// Keep the last `N` values set for the component of each entity.
+///
+/// The values are recorded whenever the component is set, such as through [`World::set`], an
+/// [`Entry`], a [`CommandBuffer`], or when spawning an entity, and can be retrieved using
+/// [`World::component_history`]. Mutations through queries or [`World::get_mut`] are not
+/// recorded.
+///
+/// This is useful for interpolating between the values of previous ticks, such as for networked
+/// entities.
+///
+/// **Note**: each entity stores up to `N` clones of the value in addition to the current value,
+/// and the history is kept until the component is removed or the entity is despawned. The values
+/// are kept in a ring buffer per entity. Only components which opt in through this metadata are
+/// affected.
+///
+/// ```rust
+/// # use flax::{*, metadata::History};
+/// component! {
+///     position: (f32, f32) => [History<2>],
+/// }
+///
+/// let mut world = World::new();
+///
+/// let id = Entity::builder()
+///     .set(position(), (0.0, 0.0))
+///     .spawn(&mut world);
+///
+/// world.set(id, position(), (1.0, 0.0)).unwrap();
+/// world.set(id, position(), (2.0, 0.0)).unwrap();
+///
+/// assert!(world
+///     .component_history(id, position())
+///     .eq(&[(1.0, 0.0), (2.0, 0.0)]));
+/// ```
+///
+/// [`World::set`]: crate::World::set
+/// [`World::get_mut`]: crate::World::get_mut
+/// [`World::component_history`]: crate::World::component_history
+/// [`CommandBuffer`]: crate::CommandBuffer
+/// [`Entry`]: crate::Entry
+pub struct History<const N: usize>;
+
+/// Type erased history of a component
+#[derive(Clone)]
+pub struct HistoryInfo {
+    create: fn(usize) -> Box<dyn HistoryStore>,
+    record: unsafe fn(&mut dyn HistoryStore, Entity, *const u8),
+    capacity: usize,
+}
+
+impl HistoryInfo {
+    pub(crate) fn create(&self) -> Box<dyn HistoryStore> {
+        (self.create)(self.capacity)
+    }
+
+    /// # Safety
+    /// `value` must point to a valid value of the component type of `store`
+    pub(crate) unsafe fn record(&self, store: &mut dyn HistoryStore, id: Entity, value: *const u8) {
+        (self.record)(store, id, value)
+    }
+}
+
+impl<T, const N: usize> Metadata<T> for History<N>
+where
+    T: ComponentValue + Clone,
+{
+    fn attach(_: ComponentDesc, buffer: &mut ComponentBuffer) {
+        buffer.set(
+            history(),
+            HistoryInfo {
+                create: |capacity| {
+                    Box::new(HistoryBuffer::<T> {
+                        capacity,
+                        values: BTreeMap::new(),
+                    })
+                },
+                record: |store, id, value| {
+                    let store = store
+                        .as_any_mut()
+                        .downcast_mut::<HistoryBuffer<T>>()
+                        .expect("Mismatched history type");
+
+                    store.push(id, unsafe { &*value.cast::<T>() }.clone())
+                },
+                capacity: N,
+            },
+        );
+    }
+}
+
+pub(crate) trait HistoryStore: 'static + Send + Sync {
+    fn remove(&mut self, id: Entity);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+pub(crate) struct HistoryBuffer<T> {
+    capacity: usize,
+    values: BTreeMap<Entity, VecDeque<T>>,
+}
+
+impl<T> HistoryBuffer<T> {
+    fn push(&mut self, id: Entity, value: T) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let capacity = self.capacity;
+        let values = self
+            .values
+            .entry(id)
+            .or_insert_with(|| VecDeque::with_capacity(capacity));
+
+        if values.len() == capacity {
+            values.pop_front();
+        }
+
+        values.push_back(value);
+    }
+
+    pub(crate) fn get(&self, id: Entity) -> vec_deque::Iter<'_, T> {
+        self.values.get(&id).map(|v| v.iter()).unwrap_or_default()
+    }
+}
+
+impl<T: ComponentValue> HistoryStore for HistoryBuffer<T> {
+    fn remove(&mut self, id: Entity) {
+        self.values.remove(&id);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
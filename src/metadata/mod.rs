@@ -4,11 +4,23 @@ use crate::{
     components::name,
 };
 
+mod cloneable;
+mod combine;
 mod debuggable;
+mod diffable;
+mod history;
 mod relation;
+mod requires;
 
+pub use cloneable::{cloneable, Cloneable};
+pub use combine::{combine, Combine, CombineInfo, CombineWith, Sum};
 pub use debuggable::*;
+pub use diffable::{diffable, Diffable};
+pub use history::{history, History, HistoryInfo};
+pub(crate) use history::{HistoryBuffer, HistoryStore};
 pub use relation::*;
+pub(crate) use requires::{add_required, add_required_batch, collect_required};
+pub use requires::{required, Required, RequiredComponents, Requires};
 
 /// Additional data that can attach itself to a component
 ///
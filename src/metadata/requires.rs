@@ -0,0 +1,182 @@
+use core::marker::PhantomData;
+
+use alloc::{collections::BTreeMap, vec::Vec};
+
+use crate::{
+    archetype::{BatchSpawn, Storage},
+    buffer::ComponentBuffer,
+    component::{ComponentDesc, ComponentKey, ComponentValue},
+};
+
+use super::Metadata;
+
+component! {
+    /// The components which are inserted along with the component.
+    ///
+    /// See [`Requires`].
+    pub required: Required,
+}
+
+/// Declares the components which are required by another component, and their default values.
+///
+/// See [`Requires`].
+pub trait RequiredComponents: 'static {
+    /// Write the required components to `buffer`
+    fn write(buffer: &mut ComponentBuffer);
+}
+
+/// Inserts the components of `R` with their default values when the component is inserted
+/// into an entity which lacks them.
+///
+/// The required components are added in the same archetype move as the component itself, and
+/// requirements of the required components are resolved as well. Components which are already
+/// present on the entity, or inserted at the same time, are left as is.
+///
+/// This applies to every way of inserting the component, such as spawning, [`World::set`],
+/// [`EntityRefMut::set_missing`], [`Entry::or_insert`] or a [`CommandBuffer`].
+///
+/// [`World::set`]: crate::World::set
+/// [`EntityRefMut::set_missing`]: crate::EntityRefMut::set_missing
+/// [`Entry::or_insert`]: crate::Entry::or_insert
+/// [`CommandBuffer`]: crate::CommandBuffer
+///
+/// ```rust
+/// # use flax::{*, buffer::ComponentBuffer, metadata::{RequiredComponents, Requires}};
+/// struct Motion;
+///
+/// impl RequiredComponents for Motion {
+///     fn write(buffer: &mut ComponentBuffer) {
+///         buffer.set(previous_position(), (0.0, 0.0));
+///     }
+/// }
+///
+/// component! {
+///     position: (f32, f32) => [Requires<Motion>],
+///     previous_position: (f32, f32),
+/// }
+///
+/// let mut world = World::new();
+///
+/// let id = Entity::builder()
+///     .set(position(), (1.0, 2.0))
+///     .spawn(&mut world);
+///
+/// assert_eq!(world.get_copy(id, previous_position()), Ok((0.0, 0.0)));
+/// ```
+pub struct Requires<R>(PhantomData<R>);
+
+/// The functions writing the components required by a component
+#[derive(Clone)]
+pub struct Required {
+    writers: Vec<fn(&mut ComponentBuffer)>,
+}
+
+impl<T, R> Metadata<T> for Requires<R>
+where
+    T: ComponentValue,
+    R: RequiredComponents,
+{
+    fn attach(_: ComponentDesc, buffer: &mut ComponentBuffer) {
+        match buffer.get_mut(required()) {
+            Some(required) => required.writers.push(R::write),
+            None => {
+                buffer.set(
+                    required(),
+                    Required {
+                        writers: alloc::vec![R::write],
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Adds the components required by the components in `buffer` which are neither in `buffer` nor
+/// satisfied by `exists`.
+pub(crate) fn add_required(buffer: &mut ComponentBuffer, exists: impl Fn(ComponentKey) -> bool) {
+    let pending: Vec<ComponentDesc> = buffer
+        .components()
+        .filter(|v| v.meta_ref().has(required()))
+        .copied()
+        .collect();
+
+    write_required(pending, buffer, exists)
+}
+
+/// Returns the components required by `desc`, and their requirements in turn, which are not
+/// satisfied by `exists`.
+pub(crate) fn collect_required(
+    desc: ComponentDesc,
+    exists: impl Fn(ComponentKey) -> bool,
+) -> ComponentBuffer {
+    let mut buffer = ComponentBuffer::new();
+    write_required(alloc::vec![desc], &mut buffer, |key| {
+        key == desc.key() || exists(key)
+    });
+    buffer
+}
+
+/// Adds a column for each component required by the components of `batch` which is not already
+/// in the batch.
+///
+/// The required components are written once per row, as their values can not be cloned.
+pub(crate) fn add_required_batch(batch: &mut BatchSpawn) {
+    let roots: Vec<ComponentDesc> = batch
+        .components()
+        .filter(|v| v.meta_ref().has(required()))
+        .collect();
+
+    if roots.is_empty() {
+        return;
+    }
+
+    let mut columns: BTreeMap<ComponentKey, Storage> = BTreeMap::new();
+    let mut buffer = ComponentBuffer::new();
+    for _ in 0..batch.len() {
+        write_required(roots.clone(), &mut buffer, |key| batch.has_key(key));
+
+        for (desc, value) in buffer.drain() {
+            let column = columns
+                .entry(desc.key())
+                .or_insert_with(|| Storage::with_capacity(desc, batch.len()));
+
+            // Ownership of the value is moved into the column
+            unsafe { column.extend(value, 1) }
+        }
+    }
+
+    for column in columns.into_values() {
+        // Every row writes the same components
+        batch.append(column).unwrap();
+    }
+}
+
+/// Writes the components required by `pending`, and their requirements in turn, to `buffer`
+fn write_required(
+    mut pending: Vec<ComponentDesc>,
+    buffer: &mut ComponentBuffer,
+    exists: impl Fn(ComponentKey) -> bool,
+) {
+    let mut scratch = ComponentBuffer::new();
+    while let Some(desc) = pending.pop() {
+        let Some(required) = desc.meta_ref().get(required()) else {
+            continue;
+        };
+
+        for write in &required.writers {
+            write(&mut scratch);
+        }
+
+        for (desc, value) in scratch.drain() {
+            let key = desc.key();
+            unsafe {
+                if buffer.has_key(key) || exists(key) {
+                    desc.drop(value);
+                } else {
+                    buffer.set_dyn(desc, value);
+                    pending.push(desc);
+                }
+            }
+        }
+    }
+}
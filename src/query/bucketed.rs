@@ -0,0 +1,276 @@
+use core::marker::PhantomData;
+
+use alloc::vec::Vec;
+use smallvec::SmallVec;
+
+use crate::{
+    archetype::{ArchetypeId, Slice, Slot},
+    fetch::{FetchAccessData, FetchPrepareData, PreparedFetch},
+    filter::{next_slice, Filtered},
+    system::{Access, AccessKind},
+    Fetch, FetchItem, World,
+};
+
+use super::{borrow::QueryBorrowState, Chunk, Planar, PreparedArchetype, QueryStrategy};
+
+/// Visit entities grouped by a bucket computed from each entity, rather than in archetype order.
+///
+/// The bucket of each entity is computed by applying `bucket` to the item of the `key` fetch.
+/// Entities are visited in ascending bucket order, and entities within the same bucket are
+/// visited in archetype order.
+///
+/// This is useful for iterating spatially local entities consecutively, such as using a spatial
+/// hash of the position as the bucket.
+///
+/// Entities which do not match the `key` fetch are not visited.
+///
+/// **Note**: the buckets are recomputed each time the query is borrowed, and the key is borrowed
+/// before the query fetch is prepared. As such, the key may refer to components which are
+/// fetched mutably by the query.
+///
+/// ```rust
+/// # use flax::*;
+/// component! {
+///     position: (f32, f32),
+/// }
+///
+/// let mut world = World::new();
+///
+/// for pos in [(15.0, 3.0), (1.0, 2.0), (12.0, 8.0), (3.0, 4.0)] {
+///     Entity::builder().set(position(), pos).spawn(&mut world);
+/// }
+///
+/// // Group the entities into 10x10 cells
+/// let mut query = Query::new(position().as_mut()).with_strategy(Bucketed::new(
+///     position(),
+///     |&(x, y): &(f32, f32)| ((x / 10.0) as i32, (y / 10.0) as i32),
+/// ));
+///
+/// let items = query.borrow(&world).iter().map(|v| *v).collect::<Vec<_>>();
+/// assert_eq!(items, [(1.0, 2.0), (3.0, 4.0), (15.0, 3.0), (12.0, 8.0)]);
+/// ```
+pub struct Bucketed<K, Func> {
+    key: K,
+    bucket: Func,
+    archetypes: Vec<ArchetypeId>,
+    /// Contiguous slots to visit in order, as an index into the prepared archetypes
+    runs: Vec<(usize, Slice)>,
+}
+
+impl<K, Func> core::fmt::Debug for Bucketed<K, Func> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("Bucketed").finish()
+    }
+}
+
+impl<K, Func> Bucketed<K, Func> {
+    /// Visit entities in the order of the bucket computed from the item of `key`.
+    pub fn new(key: K, bucket: Func) -> Self {
+        Self {
+            key,
+            bucket,
+            archetypes: Vec::new(),
+            runs: Vec::new(),
+        }
+    }
+}
+
+impl<'w, Q, F, K, Func, B> QueryStrategy<'w, Q, F> for Bucketed<K, Func>
+where
+    Q: 'w + Fetch<'w>,
+    F: 'w + Fetch<'w>,
+    K: 'w + for<'x> Fetch<'x>,
+    Func: 'w + for<'x> Fn(<K as FetchItem<'x>>::Item) -> B,
+    B: Ord,
+{
+    type Borrow = BucketedBorrow<'w, Q, F>;
+
+    fn borrow(&'w mut self, state: QueryBorrowState<'w, Q, F>, dirty: bool) -> Self::Borrow {
+        let world = state.world;
+
+        if dirty {
+            self.archetypes.clear();
+            Planar::update_state(world, state.fetch, &mut self.archetypes);
+
+            let key = &self.key;
+            self.archetypes.retain(|&arch_id| {
+                key.filter_arch(FetchAccessData {
+                    world,
+                    arch: world.archetypes.get(arch_id),
+                    arch_id,
+                })
+            });
+        }
+
+        // Compute the bucket of every entity before the fetch is prepared, as the key may borrow
+        // the same components
+        let mut entries = Vec::new();
+        for (arch_index, &arch_id) in self.archetypes.iter().enumerate() {
+            let arch = world.archetypes.get(arch_id);
+            let Some(mut key) = self.key.prepare(FetchPrepareData {
+                world,
+                arch,
+                arch_id,
+                old_tick: state.old_tick,
+                new_tick: state.new_tick,
+            }) else {
+                continue;
+            };
+
+            // Entities rejected by a filter of the key, such as a change filter, are not visited
+            let mut slots = arch.slots();
+            while let Some(slice) = next_slice(&mut slots, &mut key) {
+                let mut chunk = unsafe { key.create_chunk(slice) };
+                for slot in slice.iter() {
+                    let item =
+                        unsafe { <K::Prepared as PreparedFetch<'_>>::fetch_next(&mut chunk) };
+                    entries.push(((self.bucket)(item), arch_index, slot));
+                }
+            }
+        }
+
+        let mut prepared = SmallVec::new();
+        // Maps each archetype to its prepared index and the slots matched by the query
+        let mut matched: Vec<Option<(usize, Vec<bool>)>> = Vec::new();
+        for &arch_id in &self.archetypes {
+            let arch = world.archetypes.get(arch_id);
+            let Some(mut p) = state.prepare_fetch(arch_id, arch) else {
+                matched.push(None);
+                continue;
+            };
+
+            let mut mask = alloc::vec![false; arch.len()];
+            let mut slots = arch.slots();
+            while let Some(slice) = next_slice(&mut slots, &mut p.fetch) {
+                mask[slice.as_range()].fill(true);
+            }
+
+            matched.push(Some((prepared.len(), mask)));
+            prepared.push(p);
+        }
+
+        entries.retain(|&(_, arch_index, slot): &(B, usize, Slot)| {
+            matched[arch_index]
+                .as_ref()
+                .is_some_and(|(_, mask)| mask[slot])
+        });
+
+        entries.sort_unstable();
+
+        self.runs.clear();
+        for (_, arch_index, slot) in entries {
+            let (index, _) = matched[arch_index].as_ref().unwrap();
+            match self.runs.last_mut() {
+                Some((last, slice)) if last == index && slice.end == slot => slice.end += 1,
+                _ => self.runs.push((*index, Slice::single(slot))),
+            }
+        }
+
+        BucketedBorrow {
+            prepared,
+            runs: &self.runs,
+        }
+    }
+
+    fn access(&self, world: &'w World, fetch: &'w Filtered<Q, F>, dst: &mut Vec<Access>) {
+        let mut archetypes = Vec::new();
+        Planar::update_state(world, fetch, &mut archetypes);
+
+        archetypes.iter().for_each(|&arch_id| {
+            let arch = world.archetypes.get(arch_id);
+            let data = FetchAccessData {
+                world,
+                arch,
+                arch_id,
+            };
+
+            if self.key.filter_arch(data) {
+                fetch.access(data, dst);
+                self.key.access(data, dst);
+            }
+        });
+
+        dst.push(Access {
+            kind: AccessKind::World,
+            mutable: false,
+        });
+    }
+}
+
+/// Borrowed state for the [`Bucketed`] strategy
+pub struct BucketedBorrow<'w, Q, F>
+where
+    Q: Fetch<'w>,
+    F: Fetch<'w>,
+{
+    prepared: SmallVec<[PreparedArchetype<'w, Q::Prepared, F::Prepared>; 8]>,
+    runs: &'w [(usize, Slice)],
+}
+
+impl<'w, 'q, Q, F> IntoIterator for &'q mut BucketedBorrow<'w, Q, F>
+where
+    Q: Fetch<'w>,
+    F: Fetch<'w>,
+{
+    type Item = <Q as FetchItem<'q>>::Item;
+
+    type IntoIter = BucketedIter<'w, 'q, Q, F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'w, Q, F> BucketedBorrow<'w, Q, F>
+where
+    Q: Fetch<'w>,
+    F: Fetch<'w>,
+{
+    /// Iterate all items matched by query and filter in bucket order.
+    pub fn iter<'q>(&'q mut self) -> BucketedIter<'w, 'q, Q, F> {
+        BucketedIter {
+            prepared: self.prepared.as_mut_ptr(),
+            runs: self.runs.iter(),
+            current: None,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Iterates entities in bucket order.
+pub struct BucketedIter<'w, 'q, Q, F>
+where
+    Q: Fetch<'w>,
+    F: Fetch<'w>,
+    'w: 'q,
+{
+    prepared: *mut PreparedArchetype<'w, Q::Prepared, F::Prepared>,
+    runs: core::slice::Iter<'q, (usize, Slice)>,
+    current: Option<Chunk<'q, Filtered<Q::Prepared, F::Prepared>>>,
+    _marker: PhantomData<&'q mut PreparedArchetype<'w, Q::Prepared, F::Prepared>>,
+}
+
+impl<'w, 'q, Q, F> Iterator for BucketedIter<'w, 'q, Q, F>
+where
+    Q: Fetch<'w>,
+    F: Fetch<'w>,
+    'w: 'q,
+{
+    type Item = <Q::Prepared as PreparedFetch<'q>>::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.current.as_mut().and_then(|v| v.next()) {
+                return Some(item);
+            }
+
+            let &(index, slice) = self.runs.next()?;
+
+            // Safety: each slot is visited at most once, so all chunks are disjoint
+            let p = unsafe { &mut *self.prepared.add(index) };
+            let chunk = unsafe { p.fetch.create_chunk(slice) };
+            self.current = Some(Chunk::new(p.arch, chunk, slice));
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use alloc::vec::Vec;
+
+use crate::{
+    archetype::Slot,
+    fetch::{PreparedFetch, RandomFetch},
+    filter::next_slice,
+    Fetch,
+};
+
+use super::{PreparedArchetype, QueryBorrow};
+
+/// Iterates all unordered combinations of `N` distinct entities matched by a query.
+///
+/// See: [`QueryBorrow::combinations`]
+pub struct Combinations<'w, 'q, Q, F, const N: usize>
+where
+    Q: Fetch<'w>,
+    F: Fetch<'w>,
+{
+    prepared: &'q [PreparedArchetype<'w, Q::Prepared, F::Prepared>],
+    locations: Vec<(usize, Slot)>,
+    indices: Option<[usize; N]>,
+}
+
+impl<'w, 'q, Q, F, const N: usize> Iterator for Combinations<'w, 'q, Q, F, N>
+where
+    Q: Fetch<'w>,
+    F: Fetch<'w>,
+    Q::Prepared: RandomFetch<'q>,
+    'w: 'q,
+{
+    type Item = [<Q::Prepared as PreparedFetch<'q>>::Item; N];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let indices = self.indices.as_mut()?;
+        let prepared = self.prepared;
+
+        let items = indices.map(|index| {
+            let (arch, slot) = self.locations[index];
+            // Safety: the slot was yielded by the filter and the fetch is read only
+            unsafe { prepared[arch].fetch.fetch.fetch_shared(slot) }
+        });
+
+        if !next_combination(indices, self.locations.len()) {
+            self.indices = None;
+        }
+
+        Some(items)
+    }
+}
+
+impl<'w, Q, F> QueryBorrow<'w, Q, F>
+where
+    Q: Fetch<'w>,
+    F: Fetch<'w>,
+{
+    /// Iterate all unordered combinations of `N` distinct entities matched by the query.
+    ///
+    /// Each combination is yielded exactly once, with the entities in iteration order.
+    ///
+    /// Items of different combinations may alias, and as such the fetch is required to be read
+    /// only. See [`Self::for_each_combination`] for mutable fetches.
+    pub fn combinations<'q, const N: usize>(&'q mut self) -> Combinations<'w, 'q, Q, F, N>
+    where
+        Q::Prepared: RandomFetch<'q>,
+        'w: 'q,
+    {
+        self.prepare_all();
+        let locations = collect_locations(&mut self.prepared);
+
+        Combinations {
+            indices: first_combination(locations.len()),
+            prepared: &self.prepared,
+            locations,
+        }
+    }
+
+    /// Execute a closure for each unordered combination of `N` distinct entities matched by the
+    /// query.
+    ///
+    /// As the entities of a combination never repeat, this allows mutable access to all items of
+    /// the combination at once.
+    ///
+    /// The items of all matched entities are fetched once up front, and each combination borrows
+    /// them, so mutable fetches only mark each entity as modified once.
+    pub fn for_each_combination<'q, const N: usize>(
+        &'q mut self,
+        mut func: impl FnMut([&mut <Q::Prepared as PreparedFetch<'q>>::Item; N]),
+    ) where
+        'w: 'q,
+    {
+        let mut items = self.iter().collect::<Vec<_>>();
+
+        let Some(mut indices) = first_combination::<N>(items.len()) else {
+            return;
+        };
+
+        let len = items.len();
+        let ptr = items.as_mut_ptr();
+        loop {
+            // Safety: the indices of a combination are distinct, and thus the borrows are disjoint
+            func(indices.map(|index| unsafe { &mut *ptr.add(index) }));
+
+            if !next_combination(&mut indices, len) {
+                break;
+            }
+        }
+    }
+}
+
+/// Collects the archetype index and slot of all entities which pass the filter
+fn collect_locations<Q, F>(prepared: &mut [PreparedArchetype<'_, Q, F>]) -> Vec<(usize, Slot)>
+where
+    Q: for<'x> PreparedFetch<'x>,
+    F: for<'x> PreparedFetch<'x>,
+{
+    let mut locations = Vec::new();
+    for (index, p) in prepared.iter_mut().enumerate() {
+        let mut slots = p.arch.slots();
+        while let Some(slice) = next_slice(&mut slots, &mut p.fetch) {
+            locations.extend(slice.iter().map(|slot| (index, slot)));
+        }
+    }
+
+    locations
+}
+
+fn first_combination<const N: usize>(len: usize) -> Option<[usize; N]> {
+    if N > len {
+        return None;
+    }
+
+    Some(core::array::from_fn(|i| i))
+}
+
+/// Advances to the next combination in lexicographic order.
+///
+/// Returns false if there are no more combinations
+fn next_combination<const N: usize>(indices: &mut [usize; N], len: usize) -> bool {
+    // Find the rightmost index which has not yet reached its maximum position
+    for i in (0..N).rev() {
+        if indices[i] < len - N + i {
+            indices[i] += 1;
+            for j in i + 1..N {
+                indices[j] = indices[j - 1] + 1;
+            }
+
+            return true;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn combination_order() {
+        let mut indices = first_combination::<2>(4).unwrap();
+        let mut combinations = alloc::vec![indices];
+        while next_combination(&mut indices, 4) {
+            combinations.push(indices);
+        }
+
+        assert_eq!(
+            combinations,
+            [[0, 1], [0, 2], [0, 3], [1, 2], [1, 3], [2, 3]]
+        );
+
+        assert_eq!(first_combination::<3>(2), None);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     archetype::{Archetype, Slice, Slot},
-    fetch::PreparedFetch,
+    fetch::{PreparedFetch, SliceFetch},
     filter::{next_slice, Filtered},
     Entity,
 };
@@ -73,6 +73,18 @@ where
     }
 }
 
+impl<'q, Q> Chunk<'q, Q>
+where
+    Q: SliceFetch<'q>,
+{
+    /// Returns the remaining items of the chunk as slices, such as `&[T]` for a component and
+    /// `&mut [T]` for a mutable component.
+    pub fn into_slices(self) -> Q::Slices {
+        let len = self.len();
+        unsafe { Q::chunk_slices(self.fetch, len) }
+    }
+}
+
 impl<'q, Q> Chunk<'q, Q>
 where
     Q: PreparedFetch<'q>,
@@ -1,4 +1,6 @@
 mod borrow;
+mod bucketed;
+mod combinations;
 mod data;
 mod dfs;
 mod difference;
@@ -17,8 +19,11 @@ use core::fmt::Debug;
 use crate::{
     archetype::Slot,
     component::ComponentValue,
-    fetch::FmtQuery,
-    filter::{All, BatchSize, Filtered, With, WithRelation, Without, WithoutRelation},
+    fetch::{FetchPrepareData, FmtQuery},
+    filter::{
+        next_slice, All, BatchSize, Dedup, Filtered, Take, With, WithRelation, Without,
+        WithoutRelation,
+    },
     relation::RelationExt,
     system::Access,
     util::TuplePush,
@@ -28,6 +33,8 @@ use alloc::vec::Vec;
 
 use self::borrow::QueryBorrowState;
 pub(crate) use borrow::*;
+pub use bucketed::{Bucketed, BucketedBorrow, BucketedIter};
+pub use combinations::Combinations;
 pub use data::*;
 pub use dfs::*;
 pub use entity::EntityBorrow;
@@ -202,6 +209,67 @@ where
         let mut borrow = self.borrow(world);
         borrow.iter().sorted().collect()
     }
+
+    /// Returns the number of entities which match the query, without borrowing the fetched
+    /// components.
+    ///
+    /// Only the filters of the query are prepared and evaluated for each archetype, which makes
+    /// this cheaper than [`QueryBorrow::count`] when only the number of matches is of interest,
+    /// and allows counting while the fetched components are borrowed elsewhere.
+    ///
+    /// **Note**: per-entity filters which are part of the fetch itself, such as
+    /// [`FetchExt::modified`](crate::FetchExt::modified), are not evaluated. Use
+    /// [`Query::filter`] for those.
+    ///
+    /// This does not advance the change tick of the query.
+    pub fn count_filtered(&mut self, world: &World) -> usize {
+        profile_function!();
+
+        let archetype_gen = world.archetype_gen();
+        if archetype_gen > self.archetype_gen {
+            self.strategy.archetypes.clear();
+            Planar::update_state(world, &self.fetch, &mut self.strategy.archetypes);
+            self.archetype_gen = archetype_gen;
+        }
+
+        let new_tick = world.change_tick();
+        let old_tick = if new_tick < self.change_tick {
+            0
+        } else {
+            self.change_tick
+        };
+
+        // Per-borrow state, such as the budget of `Take`, is restored as for a borrow, such that
+        // the count does not depend on previous borrows
+        let filter = &self.fetch.filter;
+        filter.on_borrow();
+
+        self.strategy
+            .archetypes
+            .iter()
+            .map(|&arch_id| {
+                let arch = world.archetypes.get(arch_id);
+
+                let Some(mut prepared) = filter.prepare(FetchPrepareData {
+                    world,
+                    arch,
+                    arch_id,
+                    old_tick,
+                    new_tick,
+                }) else {
+                    return 0;
+                };
+
+                let mut slots = arch.slots();
+                let mut count = 0;
+                while let Some(slice) = next_slice(&mut slots, &mut prepared) {
+                    count += slice.len();
+                }
+
+                count
+            })
+            .sum()
+    }
 }
 
 impl<Q, F, S> Query<Q, F, S>
@@ -273,6 +341,82 @@ where
         self.filter(component.with())
     }
 
+    /// Only yield entities whose item of `key` differs from when the entity was last visited by
+    /// this query.
+    ///
+    /// The key is typically a tuple of copied or cloned inputs, and can be combined with change
+    /// filters to skip entities which were modified without actually changing value.
+    ///
+    /// See [`Dedup`] for details.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     position: f32,
+    ///     scale: f32,
+    ///     transform: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    ///
+    /// let id = Entity::builder()
+    ///     .set(position(), 1.0)
+    ///     .set(scale(), 2.0)
+    ///     .set_default(transform())
+    ///     .spawn(&mut world);
+    ///
+    /// let mut query = Query::new(transform().as_mut())
+    ///     .filter(position().modified() | scale().modified())
+    ///     .dedup_by((position().copied(), scale().copied()));
+    ///
+    /// assert_eq!(query.borrow(&world).count(), 1);
+    ///
+    /// // Writing the same value is a modification, but not a change of the key
+    /// world.set(id, position(), 1.0).unwrap();
+    /// assert_eq!(query.borrow(&world).count(), 0);
+    ///
+    /// world.set(id, position(), 3.0).unwrap();
+    /// assert_eq!(query.borrow(&world).count(), 1);
+    /// ```
+    pub fn dedup_by<K, V>(self, key: K) -> Query<Q, F::PushRight, S>
+    where
+        F: TuplePush<Dedup<K, V>>,
+    {
+        self.filter(Dedup::new(key))
+    }
+
+    /// Only yield the first `limit` entities each time the query is borrowed, across all
+    /// archetypes.
+    ///
+    /// This limits the work done per frame for incremental workloads. It should be the last filter
+    /// of the query.
+    ///
+    /// See [`Take`] for details.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     health: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    ///
+    /// for i in 0..10 {
+    ///     Entity::builder().set(health(), i as f32).spawn(&mut world);
+    /// }
+    ///
+    /// let mut query = Query::new(health()).take(4);
+    ///
+    /// assert_eq!(query.borrow(&world).count(), 4);
+    /// assert_eq!(query.borrow(&world).count(), 4);
+    /// ```
+    pub fn take(self, limit: usize) -> Query<Q, F::PushRight, S>
+    where
+        F: TuplePush<Take>,
+    {
+        self.filter(Take::new(limit))
+    }
+
     /// Prepare the next change tick and return the old one for the last time
     /// the query ran
     fn prepare_tick(&mut self, world: &World) -> (u32, u32) {
@@ -317,6 +461,7 @@ where
     {
         profile_function!();
         let (old_tick, new_tick) = self.prepare_tick(world);
+        self.fetch.on_borrow();
 
         let borrow_state = QueryBorrowState {
             old_tick,
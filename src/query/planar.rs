@@ -1,15 +1,18 @@
-use alloc::vec::Vec;
-use core::{iter::Flatten, slice::IterMut};
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{
+    iter::{Flatten, Rev},
+    slice::IterMut,
+};
 use smallvec::SmallVec;
 
 use crate::{
     archetype::{ArchetypeId, Slice},
     entity::EntityLocation,
     error::{MissingComponent, Result},
-    fetch::{FetchAccessData, PreparedFetch},
-    filter::{All, Filtered},
+    fetch::{FetchAccessData, FetchPrepareData, PreparedFetch, RandomFetch, SliceFetch},
+    filter::{next_slice, All, Filtered},
     system::{Access, AccessKind},
-    Entity, Error, Fetch, FetchItem, World,
+    CommandBuffer, Entity, Error, Fetch, FetchItem, World,
 };
 
 use super::{
@@ -39,7 +42,7 @@ impl Planar {
 
 impl Planar {
     // Make sure the archetypes to visit are up to date
-    fn update_state<'w, Q: Fetch<'w>, F: Fetch<'w>>(
+    pub(super) fn update_state<'w, Q: Fetch<'w>, F: Fetch<'w>>(
         world: &crate::World,
         fetch: &Filtered<Q, F>,
         result: &mut Vec<ArchetypeId>,
@@ -48,11 +51,13 @@ impl Planar {
         fetch.searcher(&mut searcher);
 
         searcher.find_archetypes(&world.archetypes, |arch_id, arch| {
-            if !fetch.filter_arch(FetchAccessData {
-                world,
-                arch,
-                arch_id,
-            }) {
+            if !Filtered::<Q, F>::TRIVIAL_FILTER_ARCH
+                && !fetch.filter_arch(FetchAccessData {
+                    world,
+                    arch,
+                    arch_id,
+                })
+            {
                 return;
             }
 
@@ -77,6 +82,7 @@ where
 
         QueryBorrow {
             prepared: SmallVec::new(),
+            prepared_until: None,
             archetypes: &self.archetypes,
             state,
         }
@@ -108,16 +114,19 @@ where
 /// each archetype matched.
 ///
 /// The borrowing is lazy, as such, calling [`QueryBorrow::get`] will only borrow the one required archetype.
-/// [`QueryBorrow::iter`] will borrow the components from all archetypes and release them once the prepared query drops.
+/// [`QueryBorrow::iter`] will borrow the components of each archetype as it is visited and release them once the prepared query drops.
 /// Subsequent calls to iter will use the same borrow.
 pub struct QueryBorrow<'w, Q, F = All>
 where
     Q: Fetch<'w>,
     F: Fetch<'w>,
 {
-    prepared: SmallVec<[PreparedArchetype<'w, Q::Prepared, F::Prepared>; 8]>,
-    archetypes: &'w [ArchetypeId],
-    state: QueryBorrowState<'w, Q, F>,
+    pub(super) prepared: SmallVec<[PreparedArchetype<'w, Q::Prepared, F::Prepared>; 8]>,
+    /// The number of matched archetypes which have been visited when preparing them in order, or
+    /// `None` if `prepared` holds archetypes prepared out of order
+    prepared_until: Option<usize>,
+    pub(super) archetypes: &'w [ArchetypeId],
+    pub(super) state: QueryBorrowState<'w, Q, F>,
 }
 
 impl<'w, 'q, Q, F> IntoIterator for &'q mut QueryBorrow<'w, Q, F>
@@ -151,6 +160,64 @@ where
         }
     }
 
+    /// Iterate all items matched by query and filter.
+    ///
+    /// This is the same as [`Self::iter`], and is provided to make the mutable borrow explicit
+    /// when iterating mutable fetches.
+    ///
+    /// **Note**: a `QueryBorrow` can not be iterated by value, such as through
+    /// `for item in query.borrow(&world)`, as the items reference the borrowed storage held by the
+    /// `QueryBorrow` itself. Use `&mut query.borrow(&world)` or this method instead.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     health: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let id = Entity::builder().set(health(), 50.0).spawn(&mut world);
+    ///
+    /// let mut query = Query::new(health().as_mut());
+    /// for health in query.borrow(&world).iter_mut() {
+    ///     *health -= 10.0;
+    /// }
+    ///
+    /// assert_eq!(world.get_copy(id, health()), Ok(40.0));
+    /// ```
+    #[inline]
+    pub fn iter_mut<'q>(&'q mut self) -> QueryIter<'w, 'q, Q, F>
+    where
+        'w: 'q,
+    {
+        self.iter()
+    }
+
+    /// Iterate all items matched by query and filter in reverse order.
+    ///
+    /// Both the order of the archetypes and the order of the entities within each archetype are
+    /// reversed, which is useful for e.g; back-to-front rendering.
+    ///
+    /// All fetches and filters are supported, as the filters of each archetype are still
+    /// evaluated front to back. The items of an archetype are fetched in order and buffered before
+    /// being yielded in reverse.
+    ///
+    /// **Note**: the archetypes are visited in reverse, so a filter which limits the number of
+    /// items across archetypes, such as [`Take`](crate::filter::Take), may select other entities
+    /// than [`Self::iter`].
+    pub fn iter_rev<'q>(&'q mut self) -> QueryIterRev<'w, 'q, Q, F>
+    where
+        'w: 'q,
+    {
+        self.prepare_all();
+
+        QueryIterRev {
+            archetypes: self.prepared.iter_mut().rev(),
+            slices: Vec::new(),
+            items: Vec::new(),
+        }
+    }
+
     /// Returns the first item
     pub fn first(&mut self) -> Option<<Q as FetchItem<'_>>::Item> {
         self.iter().next()
@@ -161,30 +228,214 @@ where
     where
         'w: 'q,
     {
-        // Prepare all archetypes only if it is not already done
-        // Clear previous borrows
-        if self.prepared.len() != self.archetypes.len() {
-            self.clear_borrows();
-            self.prepared = self
-                .archetypes
-                .iter()
-                .filter_map(|&arch_id| {
-                    let arch = self.state.world.archetypes.get(arch_id);
-                    if arch.is_empty() {
-                        return None;
-                    }
+        self.begin_prepare();
 
-                    self.state.prepare_fetch(arch_id, arch)
-                })
-                .collect();
-        }
+        // The remaining archetypes are prepared as they are visited, such that e.g. a `Take`
+        // filter which has used up its budget stops further archetypes from being borrowed
+        let borrow: *mut Self = self;
 
         BatchedIter {
-            archetypes: self.prepared.iter_mut(),
+            archetypes: unsafe { (*borrow).prepared.iter_mut() },
+            pending: Some(borrow),
+            current: None,
+        }
+    }
+
+    /// Iterate the matched items as slices, one per batch of contiguous entities.
+    ///
+    /// This is available for queries consisting only of components, mutable components, and entity
+    /// ids, and allows writing loops over slices which the compiler can more easily vectorize.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     position: f32,
+    ///     velocity: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let id = Entity::builder()
+    ///     .set(position(), 0.0)
+    ///     .set(velocity(), 2.0)
+    ///     .spawn(&mut world);
+    ///
+    /// let mut query = Query::new((position().as_mut(), velocity()));
+    /// for (pos, vel) in query.borrow(&world).chunks_mut() {
+    ///     for (pos, vel) in pos.iter_mut().zip(vel) {
+    ///         *pos += vel * 0.5;
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(world.get_copy(id, position()), Ok(1.0));
+    /// ```
+    pub fn chunks_mut<'q>(
+        &'q mut self,
+    ) -> impl Iterator<Item = <Q::Prepared as SliceFetch<'q>>::Slices> + 'q
+    where
+        'w: 'q,
+        Q::Prepared: SliceFetch<'q>,
+    {
+        self.iter_batched().map(|batch| batch.into_slices())
+    }
+
+    /// Iterate the items of this query together with the items of `other` for the same entity.
+    ///
+    /// Entities which are not matched by `other`, or filtered by it, are skipped.
+    ///
+    /// The filter of `other` is evaluated once up front, after which its items are fetched
+    /// through random access, which requires the fetch of `other` to be read only.
+    ///
+    /// **Note**: both queries hold their borrows at the same time, which means they can not access
+    /// the same component mutably, or mutably and immutably. Doing so will panic.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     position: f32,
+    ///     velocity: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let id = Entity::builder()
+    ///     .set(position(), 1.0)
+    ///     .set(velocity(), 2.0)
+    ///     .spawn(&mut world);
+    ///
+    /// Entity::builder().set(position(), 5.0).spawn(&mut world);
+    ///
+    /// let mut positions = Query::new((entity_ids(), position().as_mut()));
+    /// let mut velocities = Query::new(velocity());
+    ///
+    /// let mut velocities = velocities.borrow(&world);
+    /// for ((id, pos), vel) in positions.borrow(&world).join(&mut velocities) {
+    ///     *pos += vel;
+    /// }
+    ///
+    /// assert_eq!(world.get_copy(id, position()), Ok(3.0));
+    /// ```
+    pub fn join<'q, 'w2, Q2, F2>(
+        &'q mut self,
+        other: &'q mut QueryBorrow<'w2, Q2, F2>,
+    ) -> JoinIter<'w, 'w2, 'q, Q, F, Q2, F2>
+    where
+        'w: 'q,
+        'w2: 'q,
+        Q2: Fetch<'w2>,
+        F2: Fetch<'w2>,
+        Q2::Prepared: RandomFetch<'q>,
+    {
+        // All archetypes of `other` must be prepared ahead, as preparing more may move the
+        // prepared fetches the yielded items borrow from
+        other.prepare_all();
+
+        let index = other
+            .prepared
+            .iter_mut()
+            .enumerate()
+            .map(|(idx, p)| {
+                let mut slots = p.arch.slots();
+                let matched = core::iter::from_fn(|| next_slice(&mut slots, &mut p.fetch))
+                    .collect::<Vec<_>>();
+
+                (p.arch_id, (idx, matched))
+            })
+            .collect();
+
+        JoinIter {
+            iter: self.iter_batched(),
             current: None,
+            world: other.state.world,
+            other: &other.prepared,
+            index,
         }
     }
 
+    /// Prepare all archetypes only if it is not already done
+    pub(super) fn prepare_all(&mut self) {
+        self.begin_prepare();
+        while self.prepare_next().is_some() {}
+    }
+
+    /// Start preparing the matched archetypes in order, unless already started
+    fn begin_prepare(&mut self) {
+        if self.prepared_until.is_none() {
+            // Clear previous borrows
+            self.clear_borrows();
+
+            // Preparing more archetypes must not move the already prepared fetches, as the
+            // yielded items borrow from them
+            self.prepared.reserve_exact(self.archetypes.len());
+            self.prepared_until = Some(0);
+        }
+    }
+
+    /// Prepare the next matched archetype in order, if any.
+    ///
+    /// Archetypes which are empty or can not be prepared are skipped.
+    fn prepare_next(&mut self) -> Option<&mut PreparedArchetype<'w, Q::Prepared, F::Prepared>> {
+        let until = self.prepared_until.as_mut()?;
+
+        while let Some(&arch_id) = self.archetypes.get(*until) {
+            *until += 1;
+
+            let arch = self.state.world.archetypes.get(arch_id);
+            if arch.is_empty() {
+                continue;
+            }
+
+            if let Some(p) = self.state.prepare_fetch(arch_id, arch) {
+                debug_assert!(self.prepared.len() < self.prepared.capacity());
+                self.prepared.push(p);
+                return self.prepared.last_mut();
+            }
+        }
+
+        None
+    }
+
+    /// Prepare all matched archetypes in parallel.
+    ///
+    /// Preparing acquires the borrows for each archetype, which is independent between archetypes.
+    /// For queries matching a large number of archetypes this reduces the cost of the first
+    /// iteration.
+    ///
+    /// Subsequent calls to [`Self::iter`] and [`Self::iter_batched`] will reuse the prepared
+    /// archetypes until the borrows are cleared.
+    #[cfg(feature = "rayon")]
+    pub fn par_prepare(&mut self)
+    where
+        Q: Sync,
+        Q::Prepared: Send,
+        F: Sync,
+        F::Prepared: Send,
+    {
+        use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+
+        if self.prepared_until == Some(self.archetypes.len()) {
+            return;
+        }
+
+        self.clear_borrows();
+
+        // Each archetype is only visited once, so the acquired borrows are disjoint
+        let state = &self.state;
+        let prepared: Vec<_> = self
+            .archetypes
+            .par_iter()
+            .filter_map(|&arch_id| {
+                let arch = state.world.archetypes.get(arch_id);
+                if arch.is_empty() {
+                    return None;
+                }
+
+                state.prepare_fetch(arch_id, arch)
+            })
+            .collect();
+
+        self.prepared = prepared.into();
+        self.prepared_until = Some(self.archetypes.len());
+    }
+
     /// Execute a closure for each item in the iterator.
     ///
     /// This is more efficient than `.iter().for_each(|v| {})` as the archetypes can be temporarily
@@ -207,6 +458,43 @@ where
         }
     }
 
+    /// Execute a closure for each item in the iterator, with access to a command buffer.
+    ///
+    /// This allows the closure to enqueue structural changes, such as spawning or despawning
+    /// entities, while retaining the temporarily borrowed archetypes of [`QueryBorrow::for_each`].
+    ///
+    /// The commands are not applied until the buffer is applied to the world after the query
+    /// borrow has been released.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     health: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let id = Entity::builder().set(health(), 0.0).spawn(&mut world);
+    ///
+    /// let mut cmd = CommandBuffer::new();
+    /// Query::new((entity_ids(), health()))
+    ///     .borrow(&world)
+    ///     .for_each_with(&mut cmd, |cmd, (id, &health)| {
+    ///         if health <= 0.0 {
+    ///             cmd.despawn(id);
+    ///         }
+    ///     });
+    ///
+    /// cmd.apply(&mut world).unwrap();
+    /// assert!(!world.is_alive(id));
+    /// ```
+    pub fn for_each_with(
+        &mut self,
+        cmd: &mut CommandBuffer,
+        mut func: impl FnMut(&mut CommandBuffer, <Q as FetchItem<'_>>::Item) + Send + Sync,
+    ) {
+        self.for_each(|item| func(cmd, item))
+    }
+
     /// See: [`QueryBorrow::for_each`]
     pub fn try_for_each<E>(
         &mut self,
@@ -231,10 +519,54 @@ where
         Ok(())
     }
 
+    /// Returns a parallel iterator over the batches of the query.
+    ///
+    /// The batches are split across the rayon thread pool, which allows idle threads to steal
+    /// the remaining batches. When executed by [`Schedule::execute_par`](crate::Schedule::execute_par),
+    /// this includes the threads of other systems in the same batch which have already finished,
+    /// which evens out uneven workloads.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// use rayon::prelude::*;
+    /// component! {
+    ///     position: f32,
+    ///     velocity: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// for i in 0..100 {
+    ///     Entity::builder()
+    ///         .set(position(), 0.0)
+    ///         .set(velocity(), i as f32)
+    ///         .spawn(&mut world);
+    /// }
+    ///
+    /// let mut query = Query::new((position().as_mut(), velocity()));
+    /// query
+    ///     .borrow(&world)
+    ///     .par_iter_batched()
+    ///     .for_each(|batch| batch.for_each(|(pos, vel)| *pos += *vel));
+    ///
+    /// assert_eq!(Query::new(position()).borrow(&world).iter().sum::<f32>(), 4950.0);
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_iter_batched<'q>(
+        &'q mut self,
+    ) -> impl rayon::iter::IndexedParallelIterator<Item = Chunk<'q, Q::Prepared>>
+    where
+        'w: 'q,
+        for<'x> <Q::Prepared as PreparedFetch<'x>>::Chunk: Send,
+    {
+        use rayon::prelude::IntoParallelIterator;
+
+        let batches: Vec<_> = self.iter_batched().collect();
+        batches.into_par_iter()
+    }
+
     /// Shorthand for:
     /// ```rust,ignore
-    /// self.iter_batched()
-    ///     .par_bridge()
+    /// self.par_iter_batched()
     ///     .for_each(|v| v.for_each(&func))
     /// ```
     #[cfg(feature = "rayon")]
@@ -246,17 +578,66 @@ where
         F: Sync,
         F::Prepared: Send,
     {
-        use rayon::prelude::{ParallelBridge, ParallelIterator};
+        use rayon::prelude::ParallelIterator;
 
-        self.iter_batched()
-            .par_bridge()
+        self.par_iter_batched()
             .for_each(|batch| batch.for_each(&func))
     }
 
+    /// Folds the items of each batch in parallel, starting from `identity`, and then combines the
+    /// partial results using `combine`.
+    ///
+    /// The partial results are combined in batch order rather than completion order, which
+    /// makes the result deterministic even for non-associative operations such as float
+    /// addition.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     mass: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// for i in 0..100 {
+    ///     Entity::builder().set(mass(), i as f32).spawn(&mut world);
+    /// }
+    ///
+    /// let total = Query::new(mass())
+    ///     .borrow(&world)
+    ///     .par_fold(|| 0.0, |acc, v| acc + *v, |a, b| a + b);
+    ///
+    /// assert_eq!(total, 4950.0);
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_fold<T>(
+        &mut self,
+        identity: impl Fn() -> T + Send + Sync,
+        fold: impl Fn(T, <Q as FetchItem<'_>>::Item) -> T + Send + Sync,
+        combine: impl Fn(T, T) -> T,
+    ) -> T
+    where
+        T: Send,
+        Q: Sync,
+        Q::Prepared: Send,
+        for<'x> <Q::Prepared as PreparedFetch<'x>>::Chunk: Send,
+        F: Sync,
+        F::Prepared: Send,
+    {
+        use rayon::prelude::ParallelIterator;
+
+        let partials: Vec<T> = self
+            .par_iter_batched()
+            .map(|batch| batch.fold(identity(), &fold))
+            .collect();
+
+        partials.into_iter().fold(identity(), combine)
+    }
+
     /// Release all borrowed archetypes
     #[inline]
     pub fn clear_borrows(&mut self) {
-        self.prepared.clear()
+        self.prepared.clear();
+        self.prepared_until = None;
     }
 
     /// Consumes the iterator and returns the number of entities visited.
@@ -268,6 +649,140 @@ where
         self.iter_batched().map(|v| v.slots().len()).sum()
     }
 
+    /// Returns the ids of all entities matched by the query.
+    ///
+    /// Only the filters of the query are prepared, which makes this cheaper than fetching
+    /// [`entity_ids`](crate::entity_ids) when the component data is not needed.
+    ///
+    /// **Note**: per-entity filters which are part of the fetch itself, such as
+    /// [`FetchExt::modified`](crate::FetchExt::modified), are not evaluated. Use
+    /// [`Query::filter`](crate::Query::filter) for those.
+    ///
+    /// This releases all borrowed archetypes.
+    pub fn entities(&mut self) -> Vec<Entity> {
+        self.clear_borrows();
+
+        let state = &self.state;
+        let mut result = Vec::new();
+        for &arch_id in self.archetypes {
+            let arch = state.world.archetypes.get(arch_id);
+
+            let Some(mut filter) = state.fetch.filter.prepare(FetchPrepareData {
+                world: state.world,
+                arch,
+                arch_id,
+                old_tick: state.old_tick,
+                new_tick: state.new_tick,
+            }) else {
+                continue;
+            };
+
+            let mut slots = arch.slots();
+            while let Some(slice) = next_slice(&mut slots, &mut filter) {
+                result.extend_from_slice(&arch.entities()[slice.as_range()]);
+            }
+        }
+
+        result
+    }
+
+    /// Returns the items of up to `n` entities chosen at random from the matched entities.
+    ///
+    /// `rng` returns a uniformly distributed random number in `0..bound` given `bound`, which
+    /// allows using any random number generator, e.g; `|bound| rng.gen_range(0..bound)`.
+    ///
+    /// The entities are selected using reservoir sampling over the matched slots, after which
+    /// only the selected entities are fetched, so neither the full set of matched entities nor
+    /// their items are collected. This also means only the selected entities are marked as
+    /// modified for mutable fetches.
+    ///
+    /// The items are returned in iteration order.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// use rand::Rng;
+    /// component! {
+    ///     health: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// for _ in 0..100 {
+    ///     Entity::builder().set(health(), 100.0).spawn(&mut world);
+    /// }
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let mut query = Query::new(health().as_mut());
+    /// for health in query.borrow(&world).sample(10, |bound| rng.gen_range(0..bound)) {
+    ///     *health -= 50.0;
+    /// }
+    ///
+    /// let damaged = Query::new(health())
+    ///     .borrow(&world)
+    ///     .iter()
+    ///     .filter(|&&v| v < 100.0)
+    ///     .count();
+    ///
+    /// assert_eq!(damaged, 10);
+    /// ```
+    pub fn sample<'q>(
+        &'q mut self,
+        n: usize,
+        mut rng: impl FnMut(usize) -> usize,
+    ) -> Vec<<Q::Prepared as PreparedFetch<'q>>::Item>
+    where
+        'w: 'q,
+    {
+        self.prepare_all();
+
+        // The selected slot of each prepared archetype
+        let mut reservoir = Vec::with_capacity(n);
+        let mut seen = 0;
+
+        for (idx, p) in self.prepared.iter_mut().enumerate() {
+            let mut slots = p.arch.slots();
+            while let Some(slice) = next_slice(&mut slots, &mut p.fetch) {
+                for slot in slice {
+                    if reservoir.len() < n {
+                        reservoir.push((idx, slot));
+                    } else {
+                        let j = rng(seen + 1);
+                        if j < n {
+                            reservoir[j] = (idx, slot);
+                        }
+                    }
+
+                    seen += 1;
+                }
+            }
+        }
+
+        reservoir.sort_unstable();
+
+        // Coalesce the selected slots of each archetype into contiguous runs
+        let mut runs: Vec<(usize, Slice)> = Vec::new();
+        for (idx, slot) in reservoir {
+            match runs.last_mut() {
+                Some((last, slice)) if *last == idx && slice.end == slot => slice.end += 1,
+                _ => runs.push((idx, Slice::single(slot))),
+            }
+        }
+
+        let mut items = Vec::with_capacity(n);
+        let mut runs = runs.into_iter().peekable();
+        for (idx, p) in self.prepared.iter_mut().enumerate() {
+            let p: *mut PreparedArchetype<_, _> = p;
+            while let Some((_, slice)) = runs.next_if(|v| v.0 == idx) {
+                // Safety: the runs are disjoint, and the filter has already been evaluated for
+                // the selected slots
+                let p = unsafe { &mut *p };
+                let chunk = unsafe { p.fetch.create_chunk(slice) };
+                items.extend(Chunk::<Q::Prepared>::new(p.arch, chunk, slice));
+            }
+        }
+
+        items
+    }
+
     fn prepare_archetype(&mut self, arch_id: ArchetypeId) -> Option<usize> {
         let prepared = &mut self.prepared;
 
@@ -289,6 +804,7 @@ where
             // let arch_id = *self.archetypes.iter().find(|&&v| v == arch_id)?;
 
             prepared.push(fetch);
+            self.prepared_until = None;
 
             Some(prepared.len() - 1)
         }
@@ -321,6 +837,126 @@ where
 
         Ok(item)
     }
+
+    /// Get the fetch items for an entity which is known to match the query.
+    ///
+    /// Unlike [`Self::get`] and [`Self::try_get`], the archetype filters are not evaluated again.
+    /// Instead, the archetype of the entity is looked up among the archetypes already matched by
+    /// the query, and no diagnostics are gathered on failure. This is intended for hot loops over
+    /// ids which have already been validated, such as resolving a list of neighbors collected by
+    /// the same query.
+    ///
+    /// Returns `None` if the entity does not exist, if its archetype is not matched by the query,
+    /// or if the fetch or a per-entity filter can not be prepared or does not yield the entity.
+    pub fn get_matched(&mut self, id: Entity) -> Option<<Q::Prepared as PreparedFetch<'_>>::Item> {
+        let EntityLocation { arch_id, slot } = self.state.world.location(id).ok()?;
+
+        let idx = match self.prepared.iter().position(|v| v.arch_id == arch_id) {
+            Some(idx) => idx,
+            None => {
+                // Only archetypes matched by the query may be cached, as they are reused by
+                // subsequent iteration and are covered by the declared access
+                if !self.archetypes.contains(&arch_id) {
+                    return None;
+                }
+
+                let arch = self.state.world.archetypes.get(arch_id);
+                let fetch = self.state.prepare_fetch(arch_id, arch)?;
+                self.prepared.push(fetch);
+                self.prepared_until = None;
+                self.prepared.len() - 1
+            }
+        };
+
+        let p = &mut self.prepared[idx];
+        // Safety: &mut self
+        let mut chunk = unsafe { p.create_chunk(Slice::single(slot))? };
+
+        chunk.next()
+    }
+
+    /// Get the fetch items for an entity, or `None` if the entity does not match.
+    ///
+    /// Unlike [`Self::get`], the reason for not matching is not determined, which makes this
+    /// cheaper for entities which are expected to not match.
+    pub fn try_get(&mut self, id: Entity) -> Option<<Q::Prepared as PreparedFetch<'_>>::Item> {
+        let EntityLocation { arch_id, slot } = self.state.world.location(id).ok()?;
+
+        let idx = self.prepare_archetype(arch_id)?;
+
+        let p = &mut self.prepared[idx];
+        // Safety: &mut self
+        let mut chunk = unsafe { p.create_chunk(Slice::single(slot))? };
+
+        chunk.next()
+    }
+}
+
+/// Iterates the items of two queries for the same entities.
+///
+/// See: [`QueryBorrow::join`]
+pub struct JoinIter<'w, 'w2, 'q, Q, F, Q2, F2>
+where
+    Q: Fetch<'w>,
+    F: Fetch<'w>,
+    Q2: Fetch<'w2>,
+    F2: Fetch<'w2>,
+    'w: 'q,
+{
+    iter: BatchedIter<'w, 'q, Q, F>,
+    current: Option<Chunk<'q, Q::Prepared>>,
+    world: &'w2 World,
+    other: &'q [PreparedArchetype<'w2, Q2::Prepared, F2::Prepared>],
+    /// The index into `other` and the slots which pass the filter of `other` for each archetype
+    index: BTreeMap<ArchetypeId, (usize, Vec<Slice>)>,
+}
+
+impl<'w, 'w2, 'q, Q, F, Q2, F2> Iterator for JoinIter<'w, 'w2, 'q, Q, F, Q2, F2>
+where
+    Q: Fetch<'w>,
+    F: Fetch<'w>,
+    Q2: Fetch<'w2>,
+    F2: Fetch<'w2>,
+    Q2::Prepared: RandomFetch<'q>,
+    'w: 'q,
+    'w2: 'q,
+{
+    type Item = (
+        <Q::Prepared as PreparedFetch<'q>>::Item,
+        <Q2::Prepared as PreparedFetch<'q>>::Item,
+    );
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let chunk = match &mut self.current {
+                Some(v) => v,
+                None => self.current.insert(self.iter.next()?),
+            };
+
+            let Some((id, item)) = chunk.next_with_id() else {
+                self.current = None;
+                continue;
+            };
+
+            let Ok(EntityLocation { arch_id, slot }) = self.world.location(id) else {
+                continue;
+            };
+
+            let Some((idx, matched)) = self.index.get(&arch_id) else {
+                continue;
+            };
+
+            // The matched slices are sorted and disjoint
+            let i = matched.partition_point(|v| v.end <= slot);
+            if !matched.get(i).is_some_and(|v| v.contains(slot)) {
+                continue;
+            }
+
+            // Safety: the slot passed the filter, and the fetch is read only
+            let other = unsafe { self.other[*idx].fetch.fetch.fetch_shared(slot) };
+            return Some((item, other));
+        }
+    }
 }
 
 /// The query iterator
@@ -346,6 +982,58 @@ where
     }
 }
 
+/// Iterates the items of a query in reverse order.
+///
+/// See: [`QueryBorrow::iter_rev`]
+pub struct QueryIterRev<'w, 'q, Q, F>
+where
+    Q: Fetch<'w>,
+    F: Fetch<'w>,
+    'w: 'q,
+{
+    archetypes: Rev<IterMut<'q, PreparedArchetype<'w, Q::Prepared, F::Prepared>>>,
+    /// The matched slices of the current archetype
+    slices: Vec<Slice>,
+    /// The items of the current archetype which have not yet been yielded
+    items: Vec<<Q::Prepared as PreparedFetch<'q>>::Item>,
+}
+
+impl<'w, 'q, Q, F> Iterator for QueryIterRev<'w, 'q, Q, F>
+where
+    Q: Fetch<'w>,
+    F: Fetch<'w>,
+    'w: 'q,
+{
+    type Item = <Q::Prepared as PreparedFetch<'q>>::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.items.pop() {
+                return Some(item);
+            }
+
+            let p = self.archetypes.next()?;
+
+            // The filters are evaluated in order, as some of them rely on it
+            let mut slots = p.arch.slots();
+            while let Some(v) = next_slice(&mut slots, &mut p.fetch) {
+                self.slices.push(v);
+            }
+
+            let fetch: *mut Filtered<Q::Prepared, F::Prepared> = &mut p.fetch;
+            for slice in self.slices.drain(..) {
+                // Safety: the slices are disjoint, and so are the items of each chunk. The
+                // prepared archetype is borrowed for `'q`
+                let chunk = unsafe { (*fetch).create_chunk(slice) };
+                self.items
+                    .extend(Chunk::<Filtered<Q::Prepared, F::Prepared>>::new(
+                        p.arch, chunk, slice,
+                    ));
+            }
+        }
+    }
+}
+
 // struct SlicePtrIter<T> {
 //     ptr: *mut T,
 //     count: usize,
@@ -386,9 +1074,29 @@ where
     'w: 'q,
 {
     pub(crate) archetypes: IterMut<'q, PreparedArchetype<'w, Q::Prepared, F::Prepared>>,
+    /// Prepares the remaining archetypes of the borrow once `archetypes` is exhausted
+    pending: Option<*mut QueryBorrow<'w, Q, F>>,
     pub(crate) current: Option<ArchetypeChunks<'q, Q::Prepared, F::Prepared>>,
 }
 
+unsafe impl<'w, 'q, Q, F> Send for BatchedIter<'w, 'q, Q, F>
+where
+    Q: Fetch<'w>,
+    F: Fetch<'w>,
+    'w: 'q,
+    &'q mut QueryBorrow<'w, Q, F>: Send,
+{
+}
+
+unsafe impl<'w, 'q, Q, F> Sync for BatchedIter<'w, 'q, Q, F>
+where
+    Q: Fetch<'w>,
+    F: Fetch<'w>,
+    'w: 'q,
+    &'q mut QueryBorrow<'w, Q, F>: Sync,
+{
+}
+
 /// Iterates over archetypes, yielding batches
 impl<'w, 'q, Q, F> BatchedIter<'w, 'q, Q, F>
 where
@@ -401,6 +1109,7 @@ where
     ) -> Self {
         Self {
             archetypes,
+            pending: None,
             current: None,
         }
     }
@@ -423,12 +1132,15 @@ where
                 }
             }
 
-            let p = unsafe {
-                &mut *(self.archetypes.next()?
-                    as *mut PreparedArchetype<'w, Q::Prepared, F::Prepared>)
-            };
+            let p: *mut PreparedArchetype<'w, Q::Prepared, F::Prepared> =
+                match self.archetypes.next() {
+                    Some(p) => p,
+                    // Safety: the prepared archetypes do not move when more are prepared, and each
+                    // archetype is visited once
+                    None => unsafe { (*self.pending?).prepare_next()? },
+                };
 
-            self.current = Some(p.chunks());
+            self.current = Some(unsafe { &mut *p }.chunks());
         }
     }
 }
@@ -11,6 +11,8 @@ use crate::{
 /// Declares search terms for a queries archetypes
 pub struct ArchetypeSearcher {
     pub(crate) required: Vec<ComponentKey>,
+    pub(crate) excluded: Vec<ComponentKey>,
+    pub(crate) unsatisfiable: bool,
 }
 
 impl ArchetypeSearcher {
@@ -19,16 +21,43 @@ impl ArchetypeSearcher {
         self.required.push(component)
     }
 
+    /// Add an excluded component.
+    ///
+    /// Archetypes which have the component, and thereby all archetypes further down the
+    /// archetype graph, are skipped entirely.
+    pub fn add_excluded(&mut self, component: ComponentKey) {
+        self.excluded.push(component)
+    }
+
+    /// Declares that no archetype can match, such as for a statically false filter.
+    ///
+    /// The archetypes are not traversed at all.
+    pub fn set_unsatisfiable(&mut self) {
+        self.unsatisfiable = true
+    }
+
     #[inline]
     pub(crate) fn find_archetypes<'a>(
         &mut self,
         archetypes: &'a Archetypes,
         mut result: impl FnMut(ArchetypeId, &'a Archetype),
     ) {
+        if self.unsatisfiable {
+            return;
+        }
+
         self.required.sort();
         self.required.dedup();
+        self.excluded.sort();
+        self.excluded.dedup();
 
-        traverse_archetypes(archetypes, archetypes.root(), &self.required, &mut result);
+        traverse_archetypes(
+            archetypes,
+            archetypes.root(),
+            &self.required,
+            &self.excluded,
+            &mut result,
+        );
     }
 }
 
@@ -37,6 +66,7 @@ pub(crate) fn traverse_archetypes<'a>(
     archetypes: &'a Archetypes,
     cur: ArchetypeId,
     required: &[ComponentKey],
+    excluded: &[ComponentKey],
     result: &mut impl FnMut(ArchetypeId, &'a Archetype),
 ) {
     let arch = archetypes.get(cur);
@@ -46,22 +76,31 @@ pub(crate) fn traverse_archetypes<'a>(
             // This matches
             result(cur, arch);
 
-            for &arch_id in arch.children.values() {
-                traverse_archetypes(archetypes, arch_id, required, result);
+            for (component, &arch_id) in &arch.children {
+                // All archetypes further down have the excluded component
+                if excluded.binary_search(component).is_ok() {
+                    continue;
+                }
+
+                traverse_archetypes(archetypes, arch_id, required, excluded, result);
             }
         }
         [head, tail @ ..] => {
             // Since the components in the trie are in order, a value greater than head means the
             // current component will never occur
             for (&component, &arch_id) in &arch.children {
+                if excluded.binary_search(&component).is_ok() {
+                    continue;
+                }
+
                 match component.cmp(head) {
                     cmp::Ordering::Less => {
                         // Not quite, keep looking
-                        traverse_archetypes(archetypes, arch_id, required, result);
+                        traverse_archetypes(archetypes, arch_id, required, excluded, result);
                     }
                     cmp::Ordering::Equal => {
                         // One more component has been found, continue to search for the remaining ones
-                        traverse_archetypes(archetypes, arch_id, tail, result);
+                        traverse_archetypes(archetypes, arch_id, tail, excluded, result);
                     }
                     cmp::Ordering::Greater => {
                         // We won't find anything of interest further down the tree
@@ -71,3 +110,65 @@ pub(crate) fn traverse_archetypes<'a>(
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use alloc::vec::Vec;
+
+    use crate::{Entity, World};
+
+    use super::*;
+
+    component! {
+        a: (),
+        b: (),
+        c: (),
+    }
+
+    #[test]
+    fn excluded() {
+        let mut world = World::new();
+
+        Entity::builder().set(a(), ()).spawn(&mut world);
+        Entity::builder()
+            .set(a(), ())
+            .set(b(), ())
+            .spawn(&mut world);
+        Entity::builder()
+            .set(a(), ())
+            .set(c(), ())
+            .spawn(&mut world);
+        Entity::builder()
+            .set(a(), ())
+            .set(b(), ())
+            .set(c(), ())
+            .spawn(&mut world);
+        Entity::builder().set(c(), ()).spawn(&mut world);
+
+        let mut searcher = ArchetypeSearcher::default();
+        searcher.add_required(a().key());
+        searcher.add_excluded(b().key());
+
+        let mut found = Vec::new();
+        searcher.find_archetypes(&world.archetypes, |_, arch| {
+            found.push(arch.components().keys().copied().collect::<Vec<_>>())
+        });
+
+        found.sort();
+        assert_eq!(
+            found,
+            [alloc::vec![a().key()], alloc::vec![a().key(), c().key()]]
+        );
+
+        let mut searcher = ArchetypeSearcher::default();
+        searcher.add_excluded(a().key());
+
+        let mut found = Vec::new();
+        searcher.find_archetypes(&world.archetypes, |_, arch| {
+            assert!(!arch.has(a().key()));
+            found.push(arch.components().keys().copied().collect::<Vec<_>>())
+        });
+
+        assert!(found.contains(&alloc::vec![c().key()]));
+    }
+}
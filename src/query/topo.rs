@@ -29,6 +29,7 @@ use super::{
 pub struct Topo {
     state: State,
     relation: Entity,
+    roots: Option<BTreeSet<Entity>>,
 }
 
 #[derive(Default, Debug, Clone)]
@@ -39,12 +40,19 @@ struct State {
 }
 
 impl State {
-    fn update<'w, Q: Fetch<'w>>(&mut self, relation: Entity, world: &World, fetch: &'w Q) {
+    fn update<'w, Q: Fetch<'w>>(
+        &mut self,
+        relation: Entity,
+        roots: Option<&BTreeSet<Entity>>,
+        world: &World,
+        fetch: &'w Q,
+    ) {
         self.clear();
         let mut searcher = ArchetypeSearcher::default();
         fetch.searcher(&mut searcher);
         // Maps each entity to all archetypes of its children
         let mut deps: BTreeMap<_, _> = BTreeMap::new();
+        let mut reachable_archetypes = BTreeMap::new();
 
         searcher.find_archetypes(&world.archetypes, |arch_id, arch| {
             if !fetch.filter_arch(FetchAccessData {
@@ -55,19 +63,25 @@ impl State {
                 return;
             }
 
+            if let Some(roots) = roots {
+                if !reachable(relation, roots, world, &mut reachable_archetypes, arch_id) {
+                    return;
+                }
+            }
+
             let idx = self.archetypes.len();
             self.archetypes.push(arch_id);
 
             let existing = self.archetypes_index.insert(arch_id, idx);
             debug_assert_eq!(existing, None, "duplicate archetype");
-            // Find dependencies
+            // Find dependencies. Targets which are no longer alive impose no dependency
             let arch_deps: Vec<_> = arch
                 .relations_like(relation)
-                .map(|(key, _)| {
+                .filter_map(|(key, _)| {
                     assert_eq!(key.id, relation);
                     let target = key.target.unwrap();
-                    let loc = world.location(target).unwrap();
-                    loc.arch_id
+                    let loc = world.location(target).ok()?;
+                    Some(loc.arch_id)
                 })
                 .collect();
 
@@ -116,14 +130,90 @@ impl State {
     }
 }
 
+/// Returns true if the entities of the archetype are descendants of any of `roots`
+fn reachable(
+    relation: Entity,
+    roots: &BTreeSet<Entity>,
+    world: &World,
+    visited: &mut BTreeMap<ArchetypeId, bool>,
+    arch_id: ArchetypeId,
+) -> bool {
+    let (result, _) = reachable_inner(
+        relation,
+        roots,
+        world,
+        visited,
+        &mut BTreeSet::new(),
+        arch_id,
+    );
+
+    // Nothing else is being evaluated, so the result is final
+    visited.insert(arch_id, result);
+    result
+}
+
+/// Returns whether the archetype is reachable, and whether a negative result depends on an
+/// archetype in `in_progress`, in which case it is not final and must not be stored.
+fn reachable_inner(
+    relation: Entity,
+    roots: &BTreeSet<Entity>,
+    world: &World,
+    visited: &mut BTreeMap<ArchetypeId, bool>,
+    in_progress: &mut BTreeSet<ArchetypeId>,
+    arch_id: ArchetypeId,
+) -> (bool, bool) {
+    if let Some(&v) = visited.get(&arch_id) {
+        return (v, false);
+    }
+
+    // Cycles are resolved by the archetype which started them
+    if !in_progress.insert(arch_id) {
+        return (false, true);
+    }
+
+    let mut pending = false;
+    let arch = world.archetypes.get(arch_id);
+    let result = arch.relations_like(relation).any(|(key, _)| {
+        let target = key.target.unwrap();
+        if roots.contains(&target) {
+            return true;
+        }
+
+        let Ok(loc) = world.location(target) else {
+            return false;
+        };
+
+        let (v, p) = reachable_inner(relation, roots, world, visited, in_progress, loc.arch_id);
+        pending |= p;
+        v
+    });
+
+    in_progress.remove(&arch_id);
+
+    if result || !pending {
+        visited.insert(arch_id, result);
+    }
+
+    (result, pending && !result)
+}
+
 impl Topo {
     /// Iterate a hierarchy in topological order from `root`
     pub fn new<T: ComponentValue>(relation: impl RelationExt<T>) -> Self {
         Self {
             relation: relation.id(),
             state: Default::default(),
+            roots: None,
         }
     }
+
+    /// Only visit the descendants of `roots`, ignoring unrelated hierarchies.
+    ///
+    /// The roots themselves are not visited, as their archetypes may contain unrelated entities.
+    pub fn roots(mut self, roots: impl IntoIterator<Item = Entity>) -> Self {
+        self.roots = Some(roots.into_iter().collect());
+        self
+    }
 }
 
 impl<'w, Q, F> QueryStrategy<'w, Q, F> for Topo
@@ -139,8 +229,12 @@ where
         dirty: bool,
     ) -> Self::Borrow {
         if dirty {
-            self.state
-                .update(self.relation, query_state.world, query_state.fetch);
+            self.state.update(
+                self.relation,
+                self.roots.as_ref(),
+                query_state.world,
+                query_state.fetch,
+            );
         }
 
         TopoBorrow {
@@ -152,7 +246,7 @@ where
 
     fn access(&self, world: &'w World, fetch: &'w Filtered<Q, F>, dst: &mut Vec<Access>) {
         let mut state = State::default();
-        state.update(self.relation, world, fetch);
+        state.update(self.relation, self.roots.as_ref(), world, fetch);
 
         state.archetypes.iter().for_each(|&arch_id| {
             let arch = world.archetypes.get(arch_id);
@@ -316,7 +410,7 @@ mod test {
 
         let fetch = name().with() & !component_info().with();
 
-        state.update(connected_to.id(), &world, &fetch);
+        state.update(connected_to.id(), None, &world, &fetch);
 
         let visited = state
             .order
@@ -408,4 +502,129 @@ mod test {
 
         assert_eq!(items, ["a", "d", "c", "f", "b", "g"]);
     }
+
+    #[test]
+    fn topo_dead_target() {
+        component! {
+            connected_to(parent): (),
+        }
+
+        let mut world = World::new();
+
+        let [a, b, c] = *('a'..='c')
+            .map(|i| {
+                Entity::builder()
+                    .set(name(), i.to_string())
+                    .spawn(&mut world)
+            })
+            .collect_vec()
+        else {
+            unreachable!()
+        };
+
+        world.set(c, connected_to(b), ()).unwrap();
+        world.set(b, connected_to(a), ()).unwrap();
+
+        world.despawn(a).unwrap();
+        // Leave a dangling relation to the despawned entity
+        world.set(b, connected_to(a), ()).unwrap();
+
+        let mut query = Query::new(name().cloned())
+            .with_strategy(Topo::new(connected_to))
+            .without(component_info());
+
+        let items = query.borrow(&world).iter().collect_vec();
+
+        assert_eq!(items, ["b", "c"]);
+    }
+
+    #[test]
+    fn topo_roots() {
+        component! {
+            child_of(parent): (),
+        }
+
+        let mut world = World::new();
+
+        let [a, b, c, d, e, f, g] = *('a'..='g')
+            .map(|i| {
+                Entity::builder()
+                    .set(name(), i.to_string())
+                    .spawn(&mut world)
+            })
+            .collect_vec()
+        else {
+            unreachable!()
+        };
+
+        //   a       d     g
+        //   |       |
+        //   b       e
+        //   |       |
+        //   c       f
+        world.set(b, child_of(a), ()).unwrap();
+        world.set(c, child_of(b), ()).unwrap();
+        world.set(e, child_of(d), ()).unwrap();
+        world.set(f, child_of(e), ()).unwrap();
+
+        let mut query = Query::new(name().cloned())
+            .with_strategy(Topo::new(child_of).roots([a]))
+            .without(component_info());
+
+        assert_eq!(query.borrow(&world).iter().collect_vec(), ["b", "c"]);
+
+        let mut query = Query::new(name().cloned())
+            .with_strategy(Topo::new(child_of).roots([e, g]))
+            .without(component_info());
+
+        assert_eq!(query.borrow(&world).iter().collect_vec(), ["f"]);
+
+        // Nodes which do not match the fetch still connect the hierarchy
+        world.remove(b, name()).unwrap();
+        let mut query = Query::new(name().cloned())
+            .with_strategy(Topo::new(child_of).roots([a]))
+            .without(component_info());
+
+        assert_eq!(query.borrow(&world).iter().collect_vec(), ["c"]);
+    }
+
+    #[test]
+    fn topo_roots_cycle() {
+        component! {
+            child_of(parent): (),
+            marker: (),
+        }
+
+        let mut world = World::new();
+
+        let b = world.spawn();
+        let root = Entity::builder()
+            .set(name(), "root".into())
+            .spawn(&mut world);
+
+        // `a` is reached from the root directly, and `b` only through the cycle with `a`
+        let a = Entity::builder()
+            .set(name(), "a".into())
+            .set(child_of(b), ())
+            .set(child_of(root), ())
+            .set(marker(), ())
+            .spawn(&mut world);
+
+        Entity::builder()
+            .set(name(), "b".into())
+            .set(child_of(a), ())
+            .set(marker(), ())
+            .append_to(&mut world, b)
+            .unwrap();
+
+        // Only the archetypes of `a` and `b` are visited, in that order
+        let mut query = Query::new(name().cloned())
+            .with_strategy(Topo::new(child_of).roots([root]))
+            .with(marker());
+
+        assert_eq!(
+            query.borrow(&world).iter().sorted().collect_vec(),
+            ["a", "b"]
+        );
+    }
 }
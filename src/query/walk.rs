@@ -80,6 +80,7 @@ where
         }
 
         self.change_tick = new_tick;
+        self.fetch.on_borrow();
 
         let query_state = QueryBorrowState {
             old_tick,
@@ -4,13 +4,15 @@ use core::{
     sync::atomic::AtomicU32,
 };
 
-use alloc::collections::btree_map::Range;
-use atomic_refcell::AtomicRef;
+use alloc::collections::{btree_map::Range, BTreeMap};
+use atomic_refcell::{AtomicRef, AtomicRefCell};
+use smallvec::SmallVec;
 
 use crate::{
-    archetype::{Archetype, RefMut, Slot},
-    component::{dummy, ComponentKey, ComponentValue},
+    archetype::{Archetype, RefMut, Slot, Storage},
+    component::{dummy, ComponentDesc, ComponentKey, ComponentValue},
     entity::EntityKind,
+    events::{EventData, EventSubscriber},
     fetch::{nth_relation, NthRelation},
     filter::{WithRelation, WithoutRelation},
     vtable::{ComponentVTable, UntypedVTable},
@@ -284,3 +286,152 @@ where
         ))
     }
 }
+
+/// Reverse index of a relation, mapping each target to the entities which have the relation to
+/// it.
+///
+/// Kept up to date by subscribing to the relation being added and removed.
+pub(crate) struct RelationIndex {
+    relation: Entity,
+    incoming: AtomicRefCell<BTreeMap<Entity, SmallVec<[Entity; 4]>>>,
+}
+
+impl RelationIndex {
+    pub(crate) fn new(relation: Entity) -> Self {
+        Self {
+            relation,
+            incoming: Default::default(),
+        }
+    }
+
+    /// Index the existing entities of `arch`
+    pub(crate) fn add_archetype(&self, arch: &Archetype) {
+        let mut incoming = self.incoming.borrow_mut();
+        for (key, _) in arch.relations_like(self.relation) {
+            incoming
+                .entry(key.target.unwrap())
+                .or_default()
+                .extend_from_slice(arch.entities());
+        }
+    }
+
+    pub(crate) fn get(&self, target: Entity) -> AtomicRef<'_, [Entity]> {
+        AtomicRef::map(self.incoming.borrow(), |v| {
+            v.get(&target).map(|v| &v[..]).unwrap_or(&[])
+        })
+    }
+}
+
+impl EventSubscriber for RelationIndex {
+    fn on_added(&self, _: &Storage, event: &EventData) {
+        let mut incoming = self.incoming.borrow_mut();
+        incoming
+            .entry(event.key.target.unwrap())
+            .or_default()
+            .extend_from_slice(event.ids);
+    }
+
+    fn on_modified(&self, _: &EventData) {}
+
+    fn on_removed(&self, _: &Storage, event: &EventData) {
+        let mut incoming = self.incoming.borrow_mut();
+        let target = event.key.target.unwrap();
+
+        if let Some(subjects) = incoming.get_mut(&target) {
+            subjects.retain(|v| !event.ids.contains(v));
+            if subjects.is_empty() {
+                incoming.remove(&target);
+            }
+        }
+    }
+
+    fn is_connected(&self) -> bool {
+        true
+    }
+
+    fn matches_component(&self, desc: ComponentDesc) -> bool {
+        desc.key().id == self.relation && desc.key().target.is_some()
+    }
+}
+
+/// A pair of a relation being added or removed.
+///
+/// See: [`World::observe_relations_changed`](crate::World::observe_relations_changed)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RelationEvent {
+    /// The relation from `subject` to `object` was added
+    Linked {
+        /// The entity which has the relation
+        subject: Entity,
+        /// The target of the relation
+        object: Entity,
+    },
+    /// The relation from `subject` to `object` was removed
+    Unlinked {
+        /// The entity which had the relation
+        subject: Entity,
+        /// The target of the relation
+        object: Entity,
+    },
+}
+
+impl RelationEvent {
+    /// Returns the entity which has, or had, the relation
+    pub fn subject(&self) -> Entity {
+        match *self {
+            Self::Linked { subject, .. } | Self::Unlinked { subject, .. } => subject,
+        }
+    }
+
+    /// Returns the target of the relation
+    pub fn object(&self) -> Entity {
+        match *self {
+            Self::Linked { object, .. } | Self::Unlinked { object, .. } => object,
+        }
+    }
+}
+
+/// Invokes a function for each pair of a relation being added or removed.
+pub(crate) struct RelationObserver<F> {
+    relation: Entity,
+    func: F,
+}
+
+impl<F> RelationObserver<F> {
+    pub(crate) fn new(relation: Entity, func: F) -> Self {
+        Self { relation, func }
+    }
+}
+
+impl<F> EventSubscriber for RelationObserver<F>
+where
+    F: 'static + Send + Sync + Fn(RelationEvent),
+{
+    fn on_added(&self, _: &Storage, event: &EventData) {
+        let object = event.key.target.unwrap();
+        for &subject in event.ids {
+            (self.func)(RelationEvent::Linked { subject, object })
+        }
+    }
+
+    fn on_modified(&self, _: &EventData) {}
+
+    fn on_removed(&self, _: &Storage, event: &EventData) {
+        let object = event.key.target.unwrap();
+        for &subject in event.ids {
+            (self.func)(RelationEvent::Unlinked { subject, object })
+        }
+    }
+
+    fn is_connected(&self) -> bool {
+        true
+    }
+
+    fn matches_arch(&self, arch: &Archetype) -> bool {
+        arch.relations_like(self.relation).next().is_some()
+    }
+
+    fn matches_component(&self, desc: ComponentDesc) -> bool {
+        desc.key().id == self.relation && desc.key().target.is_some()
+    }
+}
@@ -6,11 +6,46 @@ use anyhow::Context;
 use itertools::Itertools;
 
 use crate::{
-    system::{access_info, AccessInfo, IntoInput, SystemContext},
+    system::{
+        access_info, Access, AccessInfo, AccessKind, ErrorAction, IntoInput, Resources,
+        SystemContext, SystemResult,
+    },
     util::Verbatim,
     BoxedSystem, CommandBuffer, System, World,
 };
 
+/// Executes a system, consulting its error handler on failure
+fn execute_system(
+    system: &mut BoxedSystem,
+    ctx: &SystemContext<'_, '_, '_>,
+) -> anyhow::Result<SystemResult> {
+    let mut retries = 0;
+    loop {
+        // No other system records commands while this one does
+        let recorded = system.records_cmd().then(|| ctx.cmd().len());
+
+        match system.execute_flow(ctx) {
+            Ok(flow) => return Ok(flow),
+            Err(err) => match system.handle_error(&err) {
+                ErrorAction::Continue => return Ok(SystemResult::Continue),
+                ErrorAction::Retry if retries < system.max_retries() => {
+                    retries += 1;
+                    if let Some(len) = recorded {
+                        ctx.cmd_mut().truncate(len);
+                    }
+                }
+                ErrorAction::Retry => {
+                    return Err(err.context(alloc::format!(
+                        "System {:?} failed after {retries} retries",
+                        system.name()
+                    )))
+                }
+                ErrorAction::Abort => return Err(err),
+            },
+        }
+    }
+}
+
 fn flush_system() -> BoxedSystem {
     System::builder()
         .with_name("flush")
@@ -79,11 +114,163 @@ impl SystemInfo {
     }
 }
 
+/// Describes a system and the accesses it declares
+#[derive(Debug, Clone)]
+pub struct SystemDescription {
+    name: String,
+    access: Vec<Access>,
+}
+
+impl SystemDescription {
+    /// Returns the system name
+    pub fn name(&self) -> &str {
+        self.name.as_ref()
+    }
+
+    /// Returns the accesses declared by the system
+    pub fn access(&self) -> &[Access] {
+        &self.access
+    }
+
+    /// Returns true if the system borrows the whole world mutably, which prevents it from
+    /// running in parallel with any other system
+    pub fn is_exclusive(&self) -> bool {
+        self.access
+            .iter()
+            .any(|v| v.mutable && v.kind == AccessKind::World)
+    }
+}
+
+/// A property of a schedule which limits how its systems can be parallelized.
+///
+/// See [`Schedule::diagnostics`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleDiagnostic {
+    /// The system borrows the world mutably, which forces every system before it to complete
+    /// before it runs, and every system after it to wait for it.
+    Exclusive {
+        /// The name of the system
+        system: String,
+    },
+}
+
+impl core::fmt::Display for ScheduleDiagnostic {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ScheduleDiagnostic::Exclusive { system } => write!(
+                f,
+                "System {system:?} borrows the world mutably and can not run in parallel with any other system. \
+                 Consider replacing `&mut World` with queries, or deferring structural changes to a `CommandBuffer`"
+            ),
+        }
+    }
+}
+
+/// An access of a system in a schedule which conflicts with an access of another system.
+///
+/// See [`Schedule::would_conflict`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessConflict {
+    system: String,
+    name: String,
+    mutable: bool,
+    other_mutable: bool,
+}
+
+impl AccessConflict {
+    /// Returns the name of the system in the schedule
+    pub fn system(&self) -> &str {
+        &self.system
+    }
+
+    /// Returns a human friendly name of what is accessed, such as the component name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns true if the new system accesses it mutably
+    pub fn mutable(&self) -> bool {
+        self.mutable
+    }
+
+    /// Returns true if the system in the schedule accesses it mutably
+    pub fn other_mutable(&self) -> bool {
+        self.other_mutable
+    }
+}
+
+impl core::fmt::Display for AccessConflict {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let verb = |mutable| if mutable { "writes" } else { "reads" };
+
+        write!(
+            f,
+            "System {:?} {} `{}` which the new system {}",
+            self.system,
+            verb(self.other_mutable),
+            self.name,
+            verb(self.mutable)
+        )
+    }
+}
+
+/// Describes how a new system would be serialized with the systems of a schedule.
+///
+/// See [`Schedule::would_conflict`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictReport {
+    conflicts: Vec<AccessConflict>,
+}
+
+impl ConflictReport {
+    /// Returns the conflicting accesses, ordered by the position of the system in the schedule
+    pub fn conflicts(&self) -> &[AccessConflict] {
+        &self.conflicts
+    }
+
+    /// Returns the names of the conflicting systems
+    pub fn systems(&self) -> impl Iterator<Item = &str> {
+        self.conflicts.iter().map(|v| v.system()).dedup()
+    }
+}
+
+impl core::fmt::Display for ConflictReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, conflict) in self.conflicts.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            conflict.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns a human friendly name for what an access refers to
+fn access_name(kind: &AccessKind, world: &World) -> String {
+    match *kind {
+        AccessKind::Archetype { id, component } => world
+            .archetypes
+            .try_get(id)
+            .and_then(|arch| arch.component(component))
+            .map(|desc| desc.name().into())
+            .unwrap_or_else(|| alloc::format!("{component}")),
+        AccessKind::External(_) => "external".into(),
+        AccessKind::World => "world".into(),
+        AccessKind::CommandBuffer => "command buffer".into(),
+        AccessKind::Input(_) => "input".into(),
+        AccessKind::Resource(_) => "resource".into(),
+    }
+}
+
 /// A schedule of systems to execute with automatic parallelization.
 #[derive(Default)]
 pub struct Schedule {
     systems: Vec<Vec<BoxedSystem>>,
     cmd: CommandBuffer,
+    resources: Resources,
 
     archetype_gen: u32,
 }
@@ -156,8 +343,35 @@ impl Schedule {
         ScheduleBuilder::default()
     }
 
+    /// Inserts a resource which is available to all systems in the schedule, returning the previous
+    /// value of the same type.
+    ///
+    /// Systems access the resource through [`SystemBuilder::with_res`] and
+    /// [`SystemBuilder::with_res_mut`]. Systems mutably accessing the same resource are not
+    /// executed in parallel.
+    ///
+    /// [`SystemBuilder::with_res`]: crate::SystemBuilder::with_res
+    /// [`SystemBuilder::with_res_mut`]: crate::SystemBuilder::with_res_mut
+    pub fn insert_resource<T: Send + Sync + 'static>(&mut self, value: T) -> Option<T> {
+        self.resources.insert(value)
+    }
+
+    /// Returns the resources of the schedule
+    pub fn resources(&self) -> &Resources {
+        &self.resources
+    }
+
+    /// Returns the resources of the schedule mutably
+    pub fn resources_mut(&mut self) -> &mut Resources {
+        &mut self.resources
+    }
+
     /// Execute all systems in the schedule sequentially on the world.
     /// Returns the first error and aborts if the execution fails.
+    ///
+    /// A system returning [`SystemResult::Stop`] skips the remaining systems, including any
+    /// explicit [`flush`](Self::flush). The commands recorded up to and including the stopping
+    /// system are still applied before returning.
     pub fn execute_seq(&mut self, world: &mut World) -> anyhow::Result<()> {
         self.execute_seq_with(world, &mut ())
     }
@@ -174,6 +388,42 @@ impl Schedule {
     ///
     /// A dependency between two systems is given by a side effect, e.g; a component write, which
     /// is accessed by the seconds system through a read or other side effect.
+    ///
+    /// # Determinism
+    ///
+    /// Systems which conflict always execute in the order they were declared, which makes any
+    /// outcome which depends on their order deterministic. Notably, writing to the command buffer
+    /// is an exclusive access, which means commands are recorded, and consequently applied, in the
+    /// same order as [`Self::execute_seq`]. This includes the spawn order and thus the iteration
+    /// order of spawned entities.
+    ///
+    /// Outcomes which are not tied to any declared access, such as the change tick of
+    /// modifications made by non-conflicting systems in the same batch, or entities reserved
+    /// directly through a shared [`World`] reference, may differ between runs.
+    ///
+    /// # Load balancing
+    ///
+    /// The systems of a batch execute as tasks on the rayon thread pool. Systems which iterate
+    /// in parallel, such as through [`SystemBuilder::par_for_each`](crate::system::SystemBuilder::par_for_each)
+    /// or [`QueryBorrow::par_iter_batched`](crate::QueryBorrow::par_iter_batched), split their
+    /// batches into tasks on the same pool. Threads which finish their own systems steal the
+    /// remaining batches, which keeps all threads busy when a batch contains several small systems
+    /// and a single large one.
+    ///
+    /// The iteration of a system using e.g; `for_each` is not split.
+    ///
+    /// # Stopping
+    ///
+    /// A system returning [`SystemResult::Stop`] skips the batches after its own, and the commands
+    /// recorded so far are applied as in [`Self::execute_seq`].
+    ///
+    /// This is not the same set of systems as skipped by [`Self::execute_seq`]. Systems declared
+    /// after the stopping system which do not conflict with it may be placed in the same or an
+    /// earlier batch, in which case they still execute. Only the systems which have to execute
+    /// after the stopping system, as they conflict with it either directly or through other
+    /// systems declared after it, are guaranteed to be skipped. Systems declared before the
+    /// stopping system which do not conflict with it may likewise have been placed in a later
+    /// batch, and are skipped.
     pub fn execute_par(&mut self, world: &mut World) -> anyhow::Result<()> {
         self.execute_par_with(world, &mut ())
     }
@@ -184,29 +434,60 @@ impl Schedule {
             systems: alloc::vec![systems.into()],
             archetype_gen: 0,
             cmd: CommandBuffer::new(),
+            resources: Resources::new(),
         }
     }
 
     /// Append one schedule onto another
+    ///
+    /// Resources of `other` which do not exist in `self` are moved over.
     pub fn append(&mut self, other: Self) {
         self.archetype_gen = 0;
-        self.systems.extend(other.systems)
+        self.systems.extend(other.systems);
+        self.resources.merge(other.resources);
     }
 
     /// Add a new system to the schedule.
     /// Respects order.
     pub fn with_system(mut self, system: impl Into<BoxedSystem>) -> Self {
+        self.add_system(system);
+        self
+    }
+
+    /// Add a new system to the end of an existing schedule, such as for plugins loaded at
+    /// runtime.
+    ///
+    /// The system executes after all existing systems it conflicts with. The batches are
+    /// recomputed the next time the schedule is executed.
+    pub fn add_system(&mut self, system: impl Into<BoxedSystem>) {
         self.archetype_gen = 0;
-        let v = match self.systems.first_mut() {
-            Some(v) => v,
-            None => {
-                self.systems.push(Default::default());
-                &mut self.systems[0]
-            }
-        };
+        // The batches are flattened in order when recomputed, so the last batch keeps the system
+        // after all existing ones
+        match self.systems.last_mut() {
+            Some(v) => v.push(system.into()),
+            None => self.systems.push(alloc::vec![system.into()]),
+        }
+    }
 
-        v.push(system.into());
-        self
+    /// Removes the first system with the given name from the schedule, and returns it.
+    ///
+    /// The batches are recomputed the next time the schedule is executed.
+    pub fn remove_system(&mut self, name: &str) -> Option<BoxedSystem> {
+        let (batch, idx) = self
+            .systems
+            .iter()
+            .enumerate()
+            .find_map(|(batch, systems)| {
+                Some((batch, systems.iter().position(|v| v.name() == name)?))
+            })?;
+
+        let system = self.systems[batch].remove(idx);
+        if self.systems[batch].is_empty() {
+            self.systems.remove(batch);
+        }
+
+        self.archetype_gen = 0;
+        Some(system)
     }
 
     /// Applies the commands inside of the commandbuffer
@@ -214,6 +495,113 @@ impl Schedule {
         self.with_system(flush_system())
     }
 
+    /// Describes each system in the schedule and the accesses it declares for the given world.
+    ///
+    /// Contrary to [`Self::batch_info`] this does not compute the batches, and the systems are
+    /// returned in the order they are stored.
+    pub fn describe(&self, world: &World) -> Vec<SystemDescription> {
+        self.systems
+            .iter()
+            .flatten()
+            .map(|system| {
+                let mut access = Vec::new();
+                system.access(world, &mut access);
+                SystemDescription {
+                    name: system.name().into(),
+                    access,
+                }
+            })
+            .collect_vec()
+    }
+
+    /// Reports the systems which limit the parallelism of the schedule for the given world.
+    ///
+    /// The systems are ordered by their position in the schedule. Note that the command buffer
+    /// flush added by [`ScheduleBuilder::flush`] is exclusive by design.
+    ///
+    /// Ordering cycles can not occur, as a system only ever depends on conflicting systems
+    /// which were added before it.
+    pub fn diagnostics(&self, world: &World) -> Vec<ScheduleDiagnostic> {
+        self.describe(world)
+            .into_iter()
+            .filter(|v| v.is_exclusive())
+            .map(|v| ScheduleDiagnostic::Exclusive { system: v.name })
+            .collect_vec()
+    }
+
+    /// Checks whether a new system with the given accesses would conflict with any of the systems
+    /// in the schedule, and thereby be serialized with them when executing in parallel.
+    ///
+    /// Returns `None` if the system can run in parallel with every system of the schedule. This
+    /// allows e.g; a plugin loader to warn about a system which serializes with others at load
+    /// time.
+    ///
+    /// The accesses of a system can be acquired through [`BoxedSystem::access`].
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     transform: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// Entity::builder().set(transform(), 0.0).spawn(&mut world);
+    ///
+    /// let schedule = Schedule::builder()
+    ///     .with_system(
+    ///         System::builder()
+    ///             .with_name("renderer")
+    ///             .with_query(Query::new(transform().as_mut()))
+    ///             .for_each(|_| {}),
+    ///     )
+    ///     .build();
+    ///
+    /// let plugin = System::builder()
+    ///     .with_name("plugin")
+    ///     .with_query(Query::new(transform().as_mut()))
+    ///     .for_each(|v| *v += 1.0)
+    ///     .boxed();
+    ///
+    /// let mut access = Vec::new();
+    /// plugin.access(&world, &mut access);
+    ///
+    /// let report = schedule.would_conflict(&access, &world).unwrap();
+    /// assert_eq!(
+    ///     report.to_string(),
+    ///     "System \"renderer\" writes `transform` which the new system writes"
+    /// );
+    /// ```
+    pub fn would_conflict(&self, access: &[Access], world: &World) -> Option<ConflictReport> {
+        let mut conflicts = Vec::new();
+
+        for system in self.systems.iter().flatten() {
+            let mut other = Vec::new();
+            system.access(world, &mut other);
+
+            for dst in access {
+                for src in other.iter().filter(|v| !v.is_compatible_with(dst)) {
+                    let conflict = AccessConflict {
+                        system: system.name().into(),
+                        name: access_name(&dst.kind, world),
+                        mutable: dst.mutable,
+                        other_mutable: src.mutable,
+                    };
+
+                    // The same component is accessed once per archetype
+                    if !conflicts.contains(&conflict) {
+                        conflicts.push(conflict);
+                    }
+                }
+            }
+        }
+
+        if conflicts.is_empty() {
+            None
+        } else {
+            Some(ConflictReport { conflicts })
+        }
+    }
+
     /// Returns information about the current multithreaded batch partioning and system accesses.
     pub fn batch_info(&mut self, world: &World) -> BatchInfos {
         self.systems = Self::build_dependencies(mem::take(&mut self.systems), world);
@@ -250,14 +638,20 @@ impl Schedule {
         input: impl IntoInput<'a>,
     ) -> anyhow::Result<()> {
         profile_function!();
+        for system in self.systems.iter_mut().flatten() {
+            system.prepare_retry(world);
+        }
+
         let input = input.into_input();
-        let ctx = SystemContext::new(world, &mut self.cmd, &input);
+        let ctx = SystemContext::new(world, &mut self.cmd, &input).with_resources(&self.resources);
 
         #[cfg(feature = "tracing")]
         let _span = tracing::info_span!("execute_seq").entered();
 
         for system in self.systems.iter_mut().flatten() {
-            system.execute(&ctx)?
+            if execute_system(system, &ctx)? == SystemResult::Stop {
+                break;
+            }
         }
 
         self.cmd
@@ -285,15 +679,29 @@ impl Schedule {
             self.systems = Self::build_dependencies(mem::take(&mut self.systems), world);
         }
 
+        for system in self.systems.iter_mut().flatten() {
+            system.prepare_retry(world);
+        }
+
         let input = input.into_input();
-        let mut ctx = SystemContext::new(world, &mut self.cmd, &input);
+        let mut ctx =
+            SystemContext::new(world, &mut self.cmd, &input).with_resources(&self.resources);
 
         let mut batches = self.systems.iter_mut();
 
         for batch in &mut batches {
-            batch
+            let flow = batch
                 .par_iter_mut()
-                .try_for_each(|system| system.execute(&ctx))?;
+                .map(|system| execute_system(system, &ctx))
+                .try_reduce(
+                    || SystemResult::Continue,
+                    |a, b| Ok(if a == SystemResult::Stop { a } else { b }),
+                )?;
+
+            // The other systems of the batch may already be running, so the batch is completed
+            if flow == SystemResult::Stop {
+                break;
+            }
 
             // If the archetype generation changed the batches are invalidated
             //
@@ -314,7 +722,9 @@ impl Schedule {
         ctx: &mut SystemContext<'_, '_, '_>,
     ) -> anyhow::Result<()> {
         for system in batches.flatten() {
-            system.execute(ctx)?;
+            if execute_system(system, ctx)? == SystemResult::Stop {
+                break;
+            }
         }
 
         ctx.cmd
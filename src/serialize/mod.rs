@@ -246,4 +246,50 @@ mod test {
 
         test_eq(&world, &new_world);
     }
+
+    #[test]
+    fn serialize_deterministic() {
+        component! {
+            health: f32,
+            pos: (f32, f32),
+        }
+
+        let mut world = World::new();
+        let a = Entity::builder()
+            .set(name(), "a".into())
+            .set(health(), 5.0)
+            .spawn(&mut world);
+
+        let b = Entity::builder()
+            .set(name(), "b".into())
+            .set(pos(), (1.0, 2.0))
+            .spawn(&mut world);
+
+        // Same entities, but archetypes are created in the opposite order
+        let mut new_world = World::new();
+        Entity::builder()
+            .set(name(), "b".into())
+            .set(pos(), (1.0, 2.0))
+            .spawn_at(&mut new_world, b)
+            .unwrap();
+
+        Entity::builder()
+            .set(name(), "a".into())
+            .set(health(), 5.0)
+            .spawn_at(&mut new_world, a)
+            .unwrap();
+
+        let (serializer, _) = SerdeBuilder::new()
+            .with(name())
+            .with(health())
+            .with(pos())
+            .build();
+
+        for format in [SerializeFormat::ColumnMajor, SerializeFormat::RowMajor] {
+            assert_eq!(
+                serde_json::to_string(&serializer.serialize(&world, format.clone())).unwrap(),
+                serde_json::to_string(&serializer.serialize(&new_world, format)).unwrap()
+            );
+        }
+    }
 }
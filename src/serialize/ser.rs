@@ -131,7 +131,7 @@ impl SerializeContext {
         &'a self,
         world: &'a World,
     ) -> impl Iterator<Item = (ArchetypeId, &'a Archetype)> {
-        world.archetypes.iter().filter(|(_, arch)| {
+        world.archetype_iter_sorted().filter(|(_, arch)| {
             !arch.is_empty()
                 && arch
                     .components()
@@ -8,7 +8,7 @@ use crate::{
     CommandBuffer, World,
 };
 
-use super::{input::ExtractDyn, SystemAccess, SystemData};
+use super::{input::ExtractDyn, Resources, SystemAccess, SystemData};
 
 /// A resource that can be shared between systems
 /// The difference between this and an `Arc<Mutex<_>>` is that this will be
@@ -73,6 +73,7 @@ pub struct SystemContext<'w, 'b, 'input> {
     pub(crate) cmd: AtomicRefCell<&'w mut CommandBuffer>,
     /// External input
     input: &'b dyn ExtractDyn<'b, 'input>,
+    resources: Option<&'b Resources>,
 }
 
 impl<'a, 'b, 'input> SystemContext<'a, 'b, 'input> {
@@ -86,9 +87,16 @@ impl<'a, 'b, 'input> SystemContext<'a, 'b, 'input> {
             world: AtomicRefCell::new(world),
             cmd: AtomicRefCell::new(cmd),
             input,
+            resources: None,
         }
     }
 
+    /// Makes the resources available to the systems
+    pub fn with_resources(mut self, resources: &'b Resources) -> Self {
+        self.resources = Some(resources);
+        self
+    }
+
     /// Access the world
     #[inline]
     pub fn world(&self) -> AtomicRef<World> {
@@ -130,4 +138,16 @@ impl<'a, 'b, 'input> SystemContext<'a, 'b, 'input> {
         let cell = unsafe { self.input.extract_dyn(TypeId::of::<T>()) };
         cell.map(|v| AtomicRefMut::map(v.borrow_mut(), unsafe { |v| v.cast().as_mut() }))
     }
+
+    /// Access a schedule resource
+    #[inline]
+    pub fn resource<T: 'static>(&self) -> Option<AtomicRef<'_, T>> {
+        self.resources?.get()
+    }
+
+    /// Access a schedule resource mutably
+    #[inline]
+    pub fn resource_mut<T: 'static>(&self) -> Option<AtomicRefMut<'_, T>> {
+        self.resources?.get_mut()
+    }
 }
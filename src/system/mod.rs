@@ -1,5 +1,6 @@
 mod context;
 mod input;
+mod resources;
 mod traits;
 
 use crate::{
@@ -24,12 +25,13 @@ use core::{
 
 pub use context::*;
 pub use input::IntoInput;
+pub use resources::{Res, ResMut, Resources};
 pub use traits::{AsBorrowed, SystemAccess, SystemData, SystemFn};
 
 use self::traits::{WithCmd, WithCmdMut, WithInput, WithInputMut, WithWorld, WithWorldMut};
 
 #[cfg(feature = "rayon")]
-use rayon::prelude::{ParallelBridge, ParallelIterator};
+use rayon::prelude::ParallelIterator;
 
 /// A system builder which allows incrementally adding data to a system
 /// function.
@@ -111,8 +113,7 @@ where
     fn execute(&mut self, mut data: (QueryData<Q, F>,)) {
         let mut borrow = data.0.borrow();
         borrow
-            .iter_batched()
-            .par_bridge()
+            .par_iter_batched()
             .for_each(|v| v.for_each(&self.func));
     }
 }
@@ -249,6 +250,28 @@ impl<Args> SystemBuilder<Args> {
         self.with(WithInputMut::<T>(PhantomData))
     }
 
+    /// Access a resource of the schedule
+    ///
+    /// See: [`Schedule::insert_resource`](crate::Schedule::insert_resource)
+    pub fn with_res<T>(self) -> SystemBuilder<Args::PushRight>
+    where
+        T: 'static,
+        Args: TuplePush<Res<T>>,
+    {
+        self.with(Res::<T>(PhantomData))
+    }
+
+    /// Access a resource of the schedule mutably
+    ///
+    /// See: [`Schedule::insert_resource`](crate::Schedule::insert_resource)
+    pub fn with_res_mut<T>(self) -> SystemBuilder<Args::PushRight>
+    where
+        T: 'static,
+        Args: TuplePush<ResMut<T>>,
+    {
+        self.with(ResMut::<T>(PhantomData))
+    }
+
     /// Set the systems name
     pub fn with_name(mut self, name: impl Into<String>) -> Self {
         self.name = Some(name.into());
@@ -307,17 +330,18 @@ pub struct System<F, Args, Ret> {
 pub trait DynSystem {
     fn name(&self) -> &str;
     fn describe(&self, f: &mut Formatter<'_>) -> fmt::Result;
-    fn execute(&mut self, ctx: &SystemContext<'_, '_, '_>) -> anyhow::Result<()>;
+    fn execute(&mut self, ctx: &SystemContext<'_, '_, '_>) -> anyhow::Result<SystemResult>;
     fn access(&self, world: &World, dst: &mut Vec<Access>);
 }
 
-impl<F, Args, Err> DynSystem for System<F, Args, Result<(), Err>>
+impl<F, Args, T, Err> DynSystem for System<F, Args, Result<T, Err>>
 where
     Args: for<'x> SystemData<'x>,
-    F: for<'x> SystemFn<'x, <Args as SystemData<'x>>::Value, Result<(), Err>>,
+    F: for<'x> SystemFn<'x, <Args as SystemData<'x>>::Value, Result<T, Err>>,
+    T: Into<SystemResult>,
     Err: Into<anyhow::Error>,
 {
-    fn execute(&mut self, ctx: &SystemContext<'_, '_, '_>) -> anyhow::Result<()> {
+    fn execute(&mut self, ctx: &SystemContext<'_, '_, '_>) -> anyhow::Result<SystemResult> {
         profile_function!(self.name());
 
         #[cfg(feature = "tracing")]
@@ -325,12 +349,12 @@ where
 
         let data = self.data.acquire(ctx);
 
-        let res: anyhow::Result<()> = self.func.execute(data).map_err(Into::into);
-        if let Err(err) = res {
-            return Err(err.context(format!("Failed to execute system: {:?}", self)));
+        match self.func.execute(data) {
+            Ok(v) => Ok(v.into()),
+            Err(err) => Err(err
+                .into()
+                .context(format!("Failed to execute system: {:?}", self))),
         }
-
-        Ok(())
     }
 
     fn describe(&self, f: &mut fmt::Formatter<'_>) -> core::fmt::Result {
@@ -338,7 +362,7 @@ where
         f.write_str(&self.name)?;
         self.data.describe(f)?;
         f.write_str(" -> ")?;
-        f.write_str(&tynm::type_name::<core::result::Result<(), Err>>())?;
+        f.write_str(&tynm::type_name::<core::result::Result<T, Err>>())?;
 
         Ok(())
     }
@@ -357,7 +381,7 @@ where
     Args: for<'x> SystemData<'x>,
     F: for<'x> SystemFn<'x, <Args as SystemData<'x>>::Value, ()>,
 {
-    fn execute(&mut self, ctx: &SystemContext<'_, '_, '_>) -> anyhow::Result<()> {
+    fn execute(&mut self, ctx: &SystemContext<'_, '_, '_>) -> anyhow::Result<SystemResult> {
         profile_function!(self.name());
 
         #[cfg(feature = "tracing")]
@@ -373,7 +397,7 @@ where
             self.func.execute(data);
         }
 
-        Ok(())
+        Ok(SystemResult::Continue)
     }
 
     fn describe(&self, f: &mut fmt::Formatter<'_>) -> core::fmt::Result {
@@ -393,6 +417,42 @@ where
     }
 }
 
+impl<F, Args> DynSystem for System<F, Args, SystemResult>
+where
+    Args: for<'x> SystemData<'x>,
+    F: for<'x> SystemFn<'x, <Args as SystemData<'x>>::Value, SystemResult>,
+{
+    fn execute(&mut self, ctx: &SystemContext<'_, '_, '_>) -> anyhow::Result<SystemResult> {
+        profile_function!(self.name());
+
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("system", name = self.name).entered();
+
+        let data = {
+            profile_scope!("acquire_data");
+            self.data.acquire(ctx)
+        };
+
+        profile_scope!("exec");
+        Ok(self.func.execute(data))
+    }
+
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("fn ")?;
+        f.write_str(&self.name)?;
+        self.data.describe(f)?;
+        f.write_str(" -> SystemResult")
+    }
+
+    fn access(&self, world: &World, dst: &mut Vec<Access>) {
+        self.data.access(world, dst)
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
 impl<F, Args, Ret> fmt::Debug for System<F, Args, Ret>
 where
     Self: DynSystem,
@@ -422,6 +482,22 @@ impl<F, Args, Ret> System<F, Args, Ret> {
     {
         BoxedSystem::new(self)
     }
+
+    /// Convert to a type erased system which handles its errors using `handler`.
+    ///
+    /// See: [`BoxedSystem::on_error`]
+    pub fn on_error(
+        self,
+        handler: impl FnMut(&anyhow::Error) -> ErrorAction + Send + Sync + 'static,
+    ) -> BoxedSystem
+    where
+        Ret: Send + Sync + 'static,
+        Args: Send + Sync + 'static,
+        F: Send + Sync + 'static,
+        Self: DynSystem,
+    {
+        self.boxed().on_error(handler)
+    }
 }
 
 impl System<(), (), ()> {
@@ -486,6 +562,8 @@ pub enum AccessKind {
     CommandBuffer,
     /// Data supplied by user in the execution context
     Input(TypeId),
+    /// A resource of the schedule
+    Resource(TypeId),
 }
 
 impl AccessKind {
@@ -539,6 +617,7 @@ pub struct AccessInfo {
     cmd: Option<bool>,
     external: Vec<TypeId>,
     input: Vec<(TypeId, bool)>,
+    resources: Vec<(TypeId, bool)>,
 }
 
 #[derive(Hash, Debug, Clone, PartialEq, Eq)]
@@ -576,6 +655,9 @@ pub(crate) fn access_info(accesses: &[Access], world: &World) -> AccessInfo {
             AccessKind::Input(ty) => {
                 result.input.push((ty, access.mutable));
             }
+            AccessKind::Resource(ty) => {
+                result.resources.push((ty, access.mutable));
+            }
             AccessKind::World => match result.world {
                 Some(true) => result.world = Some(true),
                 _ => result.world = Some(access.mutable),
@@ -597,9 +679,59 @@ impl Access {
     }
 }
 
+/// Decides how a schedule proceeds when a system fails.
+///
+/// See: [`BoxedSystem::on_error`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorAction {
+    /// Ignore the error and continue with the next system
+    Continue,
+    /// Execute the system again.
+    ///
+    /// Commands recorded by the failed execution are discarded. The handler is consulted again if
+    /// the system keeps failing, until the retry limit of the system is reached, after which the
+    /// schedule is aborted with the last error.
+    ///
+    /// See: [`BoxedSystem::with_max_retries`]
+    Retry,
+    /// Abort the schedule and return the error
+    Abort,
+}
+
+/// Decides whether a schedule proceeds after a system has executed.
+///
+/// Systems may return this, or a `Result` of it, to stop the rest of the schedule from executing
+/// this time around, such as when the world was paused by a state transition.
+///
+/// See: [`Schedule::execute_seq`](crate::Schedule::execute_seq)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SystemResult {
+    /// Continue with the next system
+    #[default]
+    Continue,
+    /// Skip the remaining systems of the schedule
+    Stop,
+}
+
+impl From<()> for SystemResult {
+    fn from(_: ()) -> Self {
+        Self::Continue
+    }
+}
+
+type ErrorHandler = Box<dyn FnMut(&anyhow::Error) -> ErrorAction + Send + Sync>;
+
+/// The number of times a failing system is executed again by default before giving up
+const DEFAULT_MAX_RETRIES: usize = 8;
+
 /// A type erased system
 pub struct BoxedSystem {
     inner: Box<dyn DynSystem + Send + Sync>,
+    on_error: Option<ErrorHandler>,
+    max_retries: usize,
+    /// True if the system records into the command buffer, which is known once the system has
+    /// been prepared for retrying
+    records_cmd: Option<bool>,
 }
 
 impl core::fmt::Debug for BoxedSystem {
@@ -623,11 +755,81 @@ impl BoxedSystem {
     {
         Self {
             inner: Box::new(system),
+            on_error: None,
+            max_retries: DEFAULT_MAX_RETRIES,
+            records_cmd: None,
         }
     }
 
+    /// Handle errors returned by the system when executed in a schedule.
+    ///
+    /// By default, an error aborts the schedule.
+    pub fn on_error(
+        mut self,
+        handler: impl FnMut(&anyhow::Error) -> ErrorAction + Send + Sync + 'static,
+    ) -> Self {
+        self.on_error = Some(Box::new(handler));
+        self
+    }
+
+    /// Set the number of times the system is executed again when the error handler returns
+    /// [`ErrorAction::Retry`], before the schedule is aborted.
+    ///
+    /// Defaults to 8.
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Returns the number of times the system is executed again before giving up
+    pub(crate) fn max_retries(&self) -> usize {
+        self.max_retries
+    }
+
+    /// Consults the error handler for an error returned by the system
+    pub(crate) fn handle_error(&mut self, err: &anyhow::Error) -> ErrorAction {
+        match &mut self.on_error {
+            Some(handler) => handler(err),
+            None => ErrorAction::Abort,
+        }
+    }
+
+    /// Determines whether the system records commands, so that the commands of a failed
+    /// execution can be discarded before retrying.
+    ///
+    /// This must be done while no system is executing, as the world is not otherwise accessible.
+    pub(crate) fn prepare_retry(&mut self, world: &World) {
+        if self.on_error.is_none() || self.records_cmd.is_some() {
+            return;
+        }
+
+        let mut access = Vec::new();
+        self.inner.access(world, &mut access);
+
+        self.records_cmd = Some(
+            access
+                .iter()
+                .any(|v| v.kind == AccessKind::CommandBuffer && v.mutable),
+        );
+    }
+
+    /// Returns true if the system records commands. Only known once [`Self::prepare_retry`] has
+    /// been called.
+    pub(crate) fn records_cmd(&self) -> bool {
+        self.records_cmd.unwrap_or_default()
+    }
+
     /// Execute the system with the provided context
     pub fn execute<'a>(&'a mut self, ctx: &'a SystemContext<'_, '_, '_>) -> anyhow::Result<()> {
+        self.inner.execute(ctx)?;
+        Ok(())
+    }
+
+    /// Execute the system with the provided context, returning whether the schedule should proceed
+    pub(crate) fn execute_flow(
+        &mut self,
+        ctx: &SystemContext<'_, '_, '_>,
+    ) -> anyhow::Result<SystemResult> {
         self.inner.execute(ctx)
     }
 
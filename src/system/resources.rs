@@ -0,0 +1,143 @@
+use core::{
+    any::{Any, TypeId},
+    fmt::{self, Formatter},
+    marker::PhantomData,
+};
+
+use alloc::{boxed::Box, collections::BTreeMap, vec::Vec};
+use atomic_refcell::{AtomicRef, AtomicRefCell, AtomicRefMut};
+
+use crate::World;
+
+use super::{Access, AccessKind, SystemAccess, SystemContext, SystemData};
+
+/// A type map of values shared by all systems in a [`Schedule`](crate::Schedule).
+///
+/// Systems access the values through [`Res`] and [`ResMut`], which are taken into account when
+/// parallelizing the schedule, so that two systems mutably accessing the same resource are never
+/// executed at the same time.
+#[derive(Default)]
+pub struct Resources {
+    values: BTreeMap<TypeId, AtomicRefCell<Box<dyn Any + Send + Sync>>>,
+}
+
+impl Resources {
+    /// Creates a new empty resource map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a resource, returning the previous value of the same type
+    pub fn insert<T: Send + Sync + 'static>(&mut self, value: T) -> Option<T> {
+        self.values
+            .insert(TypeId::of::<T>(), AtomicRefCell::new(Box::new(value)))
+            .map(|v| *v.into_inner().downcast().unwrap())
+    }
+
+    /// Removes a resource
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.values
+            .remove(&TypeId::of::<T>())
+            .map(|v| *v.into_inner().downcast().unwrap())
+    }
+
+    /// Returns true if the resource exists
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Borrow a resource
+    ///
+    /// Panics if the resource is already borrowed mutably
+    pub fn get<T: 'static>(&self) -> Option<AtomicRef<'_, T>> {
+        let cell = self.values.get(&TypeId::of::<T>())?;
+        Some(AtomicRef::map(cell.borrow(), |v| v.downcast_ref().unwrap()))
+    }
+
+    /// Borrow a resource mutably
+    ///
+    /// Panics if the resource is already borrowed
+    pub fn get_mut<T: 'static>(&self) -> Option<AtomicRefMut<'_, T>> {
+        let cell = self.values.get(&TypeId::of::<T>())?;
+        Some(AtomicRefMut::map(cell.borrow_mut(), |v| {
+            v.downcast_mut().unwrap()
+        }))
+    }
+
+    /// Moves the resources of `other` which do not exist in `self`
+    pub(crate) fn merge(&mut self, other: Self) {
+        for (key, value) in other.values {
+            self.values.entry(key).or_insert(value);
+        }
+    }
+}
+
+impl fmt::Debug for Resources {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Resources")
+            .field("len", &self.values.len())
+            .finish()
+    }
+}
+
+/// Access a schedule resource
+///
+/// See: [`SystemBuilder::with_res`](crate::SystemBuilder::with_res)
+pub struct Res<T>(pub(crate) PhantomData<T>);
+
+impl<'a, T: 'static> SystemData<'a> for Res<T> {
+    type Value = AtomicRef<'a, T>;
+
+    fn acquire(&'a mut self, ctx: &'a SystemContext<'_, '_, '_>) -> Self::Value {
+        match ctx.resource() {
+            Some(v) => v,
+            None => panic!("Resources do not contain `{}`", tynm::type_name::<T>()),
+        }
+    }
+
+    fn describe(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("Res<")?;
+        f.write_str(&tynm::type_name::<T>())?;
+        f.write_str(">")
+    }
+}
+
+impl<T: 'static> SystemAccess for Res<T> {
+    fn access(&self, _: &World, dst: &mut Vec<Access>) {
+        dst.push(Access {
+            kind: AccessKind::Resource(TypeId::of::<T>()),
+            mutable: false,
+        });
+    }
+}
+
+/// Access a schedule resource mutably
+///
+/// See: [`SystemBuilder::with_res_mut`](crate::SystemBuilder::with_res_mut)
+pub struct ResMut<T>(pub(crate) PhantomData<T>);
+
+impl<'a, T: 'static> SystemData<'a> for ResMut<T> {
+    type Value = AtomicRefMut<'a, T>;
+
+    fn acquire(&'a mut self, ctx: &'a SystemContext<'_, '_, '_>) -> Self::Value {
+        match ctx.resource_mut() {
+            Some(v) => v,
+            None => panic!("Resources do not contain `{}`", tynm::type_name::<T>()),
+        }
+    }
+
+    fn describe(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("ResMut<")?;
+        f.write_str(&tynm::type_name::<T>())?;
+        f.write_str(">")
+    }
+}
+
+impl<T: 'static> SystemAccess for ResMut<T> {
+    fn access(&self, _: &World, dst: &mut Vec<Access>) {
+        dst.push(Access {
+            kind: AccessKind::Resource(TypeId::of::<T>()),
+            mutable: true,
+        });
+    }
+}
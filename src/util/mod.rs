@@ -3,6 +3,8 @@
 
 use core::marker::PhantomData;
 
+use atomic_refcell::{AtomicRefCell, AtomicRefMut};
+
 use crate::filter::All;
 
 /// Allows pushing onto a tuple
@@ -181,6 +183,39 @@ impl<'a, T> PtrMut<'a, T> {
     }
 }
 
+/// A reference to the state of a fetch, which is shared by its prepared fetches and borrowed
+/// while they are in use.
+///
+/// The reference is neither `Send` nor `Sync`, such that prepared fetches of different archetypes
+/// can not borrow the state from different threads at the same time.
+pub(crate) struct LocalRef<'a, T> {
+    cell: &'a AtomicRefCell<T>,
+    _marker: PhantomData<*const ()>,
+}
+
+impl<'a, T> Clone for LocalRef<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for LocalRef<'a, T> {}
+
+impl<'a, T> LocalRef<'a, T> {
+    #[inline]
+    pub(crate) fn new(cell: &'a AtomicRefCell<T>) -> Self {
+        Self {
+            cell,
+            _marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn borrow_mut(&self) -> AtomicRefMut<'a, T> {
+        self.cell.borrow_mut()
+    }
+}
+
 #[derive(PartialEq, Eq, Clone)]
 pub(crate) struct Verbatim(pub alloc::string::String);
 impl core::fmt::Debug for Verbatim {
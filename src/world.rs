@@ -1,4 +1,10 @@
-use alloc::{collections::BTreeMap, sync::Arc, vec::Vec};
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+    sync::Arc,
+    vec::Vec,
+};
 use core::{
     fmt,
     fmt::Formatter,
@@ -12,23 +18,33 @@ use atomic_refcell::{AtomicRef, BorrowError, BorrowMutError};
 use itertools::Itertools;
 
 use crate::{
-    archetype::{Archetype, ArchetypeId, ArchetypeInfo, Slot},
+    archetype::{
+        Archetype, ArchetypeId, ArchetypeInfo, FragmentationInfo, LazyRefMut, Slice, Slot,
+    },
     archetypes::Archetypes,
-    buffer::ComponentBuffer,
+    buffer::{Bundle, ComponentBuffer},
     component::{dummy, ComponentDesc, ComponentKey, ComponentValue},
     components::{self, component_info, is_static, name},
-    entity::{entity_ids, Entity, EntityIndex, EntityKind, EntityLocation, EntityStore},
+    diff::WorldDiff,
+    entity::{
+        entity_ids, Entity, EntityIndex, EntityKind, EntityLocation, EntityStore, RecyclePolicy,
+    },
     entity_ref::{EntityRef, EntityRefMut},
     entry::{Entry, OccupiedEntry, VacantEntry},
     error::{MissingComponent, Result},
-    events::EventSubscriber,
+    events::{ChangeTracker, EventKind, EventSubscriber, Observer},
+    fetch::Mutable,
     filter::StaticFilter,
     format::{EntitiesFormatter, HierarchyFormatter, WorldFormatter},
-    relation::{Relation, RelationExt},
+    metadata::{
+        self, add_required, add_required_batch, combine, history, HistoryBuffer, HistoryStore,
+    },
+    relation::{Relation, RelationEvent, RelationExt, RelationIndex, RelationObserver},
     writer::{
-        self, EntityWriter, FnWriter, Replace, ReplaceDyn, SingleComponentWriter, WriteDedup,
+        self, CombineDyn, EntityWriter, FnWriter, Replace, ReplaceDyn, SingleComponentWriter,
+        WriteDedup,
     },
-    BatchSpawn, Component, ComponentVTable, Error, Fetch, Query, RefMut,
+    BatchSpawn, Component, ComponentVTable, EntityBuilder, Error, Fetch, FetchItem, Query, RefMut,
 };
 
 #[derive(Debug, Default)]
@@ -37,9 +53,12 @@ struct EntityStores {
 }
 
 impl EntityStores {
-    fn new() -> Self {
+    fn with_capacity(capacity: usize) -> Self {
         Self {
-            inner: BTreeMap::from([(EntityKind::empty(), EntityStore::new(EntityKind::empty()))]),
+            inner: BTreeMap::from([(
+                EntityKind::empty(),
+                EntityStore::with_capacity(EntityKind::empty(), capacity),
+            )]),
         }
     }
 
@@ -71,6 +90,16 @@ pub(crate) fn update_entity_loc(
     *ns.get_mut(id).expect("Entity is not valid") = loc;
 }
 
+/// Dirty flags kept up to date from the changes of a component.
+///
+/// See [`World::track_changes`].
+struct DirtyFlags {
+    tracker: Arc<ChangeTracker>,
+    dirty: Component<bool>,
+    /// Entities which currently have the flag set
+    flagged: Vec<Entity>,
+}
+
 /// The main entry point of the ECS
 ///
 /// Holds the entities and components of the ECS.
@@ -85,6 +114,9 @@ pub struct World {
     entities: EntityStores,
     pub(crate) archetypes: Archetypes,
     change_tick: AtomicU32,
+    relation_indices: BTreeMap<Entity, Arc<RelationIndex>>,
+    histories: BTreeMap<ComponentKey, Box<dyn HistoryStore>>,
+    dirty_flags: Vec<DirtyFlags>,
 
     has_reserved: AtomicBool,
 }
@@ -92,10 +124,25 @@ pub struct World {
 impl World {
     /// Creates a new empty world
     pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates a new empty world with space preallocated for at least `entities` entities.
+    ///
+    /// This avoids reallocating the entity ids and the root archetype when a large number of
+    /// entities are spawned up front, such as when loading a scene. Use
+    /// [`Self::reserve_archetype`] to preallocate the component storage of the spawned entities.
+    pub fn with_capacity(entities: usize) -> Self {
+        let mut archetypes = Archetypes::new();
+        archetypes.get_mut(archetypes.root).reserve(entities);
+
         Self {
-            entities: EntityStores::new(),
-            archetypes: Archetypes::new(),
+            entities: EntityStores::with_capacity(entities),
+            archetypes,
             change_tick: AtomicU32::new(0b11),
+            relation_indices: BTreeMap::new(),
+            histories: BTreeMap::new(),
+            dirty_flags: Vec::new(),
             has_reserved: AtomicBool::new(false),
         }
     }
@@ -148,6 +195,7 @@ impl World {
         profile_function!();
         self.flush_reserved();
 
+        add_required_batch(chunk);
         for component in chunk.components() {
             self.init_component(component);
         }
@@ -179,6 +227,133 @@ impl World {
         ids
     }
 
+    /// Efficiently spawn many entities with the same components at once, and invoke `func` for
+    /// each spawned row.
+    ///
+    /// The closure receives the index of the row along with the ids of the whole batch, which
+    /// allows setting up references between the spawned entities, such as relations.
+    ///
+    /// Returns the ids of the spawned entities in row order.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     position: f32,
+    ///     next(id): (),
+    /// }
+    ///
+    /// let mut world = World::new();
+    ///
+    /// let mut batch = BatchSpawn::new(3);
+    /// batch.set(position(), [0.0, 1.0, 2.0]).unwrap();
+    ///
+    /// let ids = world.spawn_batch_with(&mut batch, |world, idx, ids| {
+    ///     if let Some(&target) = ids.get(idx + 1) {
+    ///         world.set(ids[idx], next(target), ()).unwrap();
+    ///     }
+    /// });
+    ///
+    /// assert!(world.has(ids[0], next(ids[1])));
+    /// assert!(world.has(ids[1], next(ids[2])));
+    /// assert_eq!(*world.get(ids[2], position()).unwrap(), 2.0);
+    /// ```
+    pub fn spawn_batch_with(
+        &mut self,
+        chunk: &mut BatchSpawn,
+        mut func: impl FnMut(&mut World, usize, &[Entity]),
+    ) -> Vec<Entity> {
+        let ids = self.spawn_batch(chunk);
+
+        for idx in 0..ids.len() {
+            func(self, idx, &ids);
+        }
+
+        ids
+    }
+
+    /// Spawns the entities of several builders at once, returning their ids in the same order as
+    /// `builders`.
+    ///
+    /// The builders are grouped by their component set, such that the archetype of each group is
+    /// only looked up once. Prefer [`Self::spawn_batch`] when all entities share the same
+    /// components.
+    ///
+    /// Children of the builders are spawned after all the builders themselves.
+    pub fn extend(&mut self, builders: impl IntoIterator<Item = EntityBuilder>) -> Vec<Entity> {
+        profile_function!();
+        let mut builders = builders.into_iter().collect_vec();
+
+        let mut groups: BTreeMap<Vec<ComponentKey>, Vec<usize>> = BTreeMap::new();
+        for (idx, builder) in builders.iter_mut().enumerate() {
+            let buffer = builder.buffer_mut();
+            add_required(buffer, |_| false);
+            for &component in buffer.components() {
+                self.init_component(component);
+            }
+
+            groups
+                .entry(buffer.components().map(|v| v.key()).collect())
+                .or_default()
+                .push(idx);
+        }
+
+        let change_tick = self.advance_change_tick();
+        let mut ids = alloc::vec![None; builders.len()];
+
+        for indices in groups.into_values() {
+            let buffer = builders[indices[0]].buffer_mut();
+            let recorded = Self::history_components(buffer);
+            let (arch_id, arch) = self.archetypes.find_create(buffer.components().copied());
+            arch.reserve(indices.len());
+
+            for idx in indices {
+                let (id, _, arch) = self.spawn_inner(arch_id, EntityKind::empty());
+
+                for (desc, src) in builders[idx].buffer_mut().drain() {
+                    unsafe { arch.push(desc.key, src, change_tick) }
+                }
+
+                for &desc in &recorded {
+                    self.record_history(id, desc);
+                }
+
+                ids[idx] = Some(id);
+            }
+        }
+
+        let ids = ids.into_iter().flatten().collect_vec();
+        for (builder, &id) in builders.iter_mut().zip(&ids) {
+            builder.spawn_children(self, id);
+        }
+
+        ids
+    }
+
+    /// Preallocates storage for at least `additional` more entities in the archetype with exactly
+    /// the given components, creating it if necessary.
+    ///
+    /// This avoids repeated reallocations when spawning a large number of entities with the same
+    /// components one at a time, such as through [`Self::extend`] or an
+    /// [`EntityBuilder`].
+    ///
+    /// **Note**: [`Self::spawn_batch`] into an empty archetype takes over the storage of the
+    /// batch, and already allocates only once.
+    pub fn reserve_archetype(
+        &mut self,
+        components: impl IntoIterator<Item = ComponentDesc>,
+        additional: usize,
+    ) -> ArchetypeId {
+        let components: BTreeMap<_, _> = components.into_iter().map(|v| (v.key(), v)).collect();
+        for &component in components.values() {
+            self.init_component(component);
+        }
+
+        let (arch_id, arch) = self.archetypes.find_create(components.into_values());
+        arch.reserve(additional);
+
+        arch_id
+    }
+
     // Check if the entity is reserved after flush
     fn is_reserved(&self, id: Entity) -> bool {
         self.location(id)
@@ -222,10 +397,12 @@ impl World {
     ) -> Result<(Entity, EntityLocation)> {
         let change_tick = self.advance_change_tick();
 
+        add_required(buffer, |_| false);
         for &component in buffer.components() {
             self.init_component(component);
         }
 
+        let recorded = Self::history_components(buffer);
         let (arch_id, _) = self.archetypes.find_create(buffer.components().copied());
         let (loc, arch) = self.spawn_at_inner(id, arch_id)?;
 
@@ -233,6 +410,10 @@ impl World {
             unsafe { arch.push(desc.key(), src, change_tick) }
         }
 
+        for desc in recorded {
+            self.record_history(id, desc);
+        }
+
         Ok((id, loc))
     }
 
@@ -240,11 +421,13 @@ impl World {
     ///
     /// For increased ergonomics, prefer [crate::EntityBuilder]
     pub(crate) fn spawn_with(&mut self, buffer: &mut ComponentBuffer) -> Entity {
+        add_required(buffer, |_| false);
         for component in buffer.components() {
             self.init_component(*component);
         }
 
         let change_tick = self.advance_change_tick();
+        let recorded = Self::history_components(buffer);
         let (arch_id, _) = self.archetypes.find_create(buffer.components().copied());
 
         let (id, _, arch) = self.spawn_inner(arch_id, EntityKind::empty());
@@ -255,6 +438,10 @@ impl World {
             }
         }
 
+        for desc in recorded {
+            self.record_history(id, desc);
+        }
+
         id
     }
 
@@ -285,6 +472,8 @@ impl World {
             arch_id: self.archetypes.root,
         };
 
+        self.clear_history(id);
+
         Ok(())
     }
 
@@ -293,6 +482,42 @@ impl World {
         self.archetypes.prune_all()
     }
 
+    /// Compacts the archetypes of the world and returns metrics describing its fragmentation.
+    ///
+    /// Empty archetypes are pruned, as with [`Self::prune_archetypes`], and the unused capacity of
+    /// the remaining archetypes' storages is released.
+    ///
+    /// Entities are stored densely within each archetype, so there are no holes to defragment.
+    /// Archetypes with different component sets can however not be merged, which means a world
+    /// where component churn has spread entities across many small archetypes remains so. Use
+    /// the returned [`FragmentationInfo`] to diagnose such cases, e.g; by a low
+    /// [`entities_per_archetype`](FragmentationInfo::entities_per_archetype).
+    pub fn compact(&mut self) -> FragmentationInfo {
+        let mut info = FragmentationInfo {
+            pruned: self.archetypes.prune_all(),
+            ..Default::default()
+        };
+
+        for (_, arch) in self.archetypes.iter_mut() {
+            info.released += arch.shrink_to_fit();
+
+            if arch.is_empty() {
+                continue;
+            }
+
+            info.smallest = if info.archetypes == 0 {
+                arch.len()
+            } else {
+                info.smallest.min(arch.len())
+            };
+
+            info.archetypes += 1;
+            info.entities += arch.len();
+        }
+
+        info
+    }
+
     pub(crate) fn retain_entity_components(
         &mut self,
         id: Entity,
@@ -301,8 +526,10 @@ impl World {
     ) -> EntityLocation {
         let src = self.archetypes.get(loc.arch_id);
 
-        let dst_components: SmallVec<[ComponentDesc; 8]> =
-            src.components_desc().filter(|v| f(v.key())).collect();
+        let (dst_components, removed): (
+            SmallVec<[ComponentDesc; 8]>,
+            SmallVec<[ComponentDesc; 8]>,
+        ) = src.components_desc().partition(|v| f(v.key()));
 
         let (dst_id, _) = self.archetypes.find_create(dst_components);
 
@@ -326,6 +553,11 @@ impl World {
         };
 
         *self.location_mut(id).expect("Entity is not valid") = loc;
+
+        for desc in removed {
+            self.forget_history(id, desc.key());
+        }
+
         loc
     }
 
@@ -355,6 +587,9 @@ impl World {
 
     /// Despawn an entity.
     /// Any relations to other entities will be removed.
+    ///
+    /// Fails with [`Error::NoSuchEntity`] if the entity does not exist, such as when it was already
+    /// despawned, in which case the world is left unchanged.
     pub fn despawn(&mut self, id: Entity) -> Result<()> {
         profile_function!();
         self.flush_reserved();
@@ -385,11 +620,60 @@ impl World {
         }
 
         // self.archetypes.prune_arch(arch);
-        self.entities.init(id.kind()).despawn(id)?;
+        self.free_entity(id)?;
+
         self.detach(id);
         Ok(())
     }
 
+    /// Releases the id of an entity which has been removed from its archetype, along with the
+    /// history of its components
+    fn free_entity(&mut self, id: Entity) -> Result<()> {
+        self.entities.init(id.kind()).despawn(id)?;
+        self.clear_history(id);
+
+        Ok(())
+    }
+
+    /// Despawns an entity if `predicate` returns true, and returns whether it was despawned.
+    ///
+    /// The predicate is given read access to the entity, which combines checking and despawning
+    /// into a single step. Returns false if the entity does not exist.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     health: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let id = Entity::builder().set(health(), 10.0).spawn(&mut world);
+    ///
+    /// let is_dead = |entity: EntityRef| entity.get(health()).is_ok_and(|v| *v <= 0.0);
+    ///
+    /// assert!(!world.despawn_if(id, is_dead));
+    ///
+    /// *world.get_mut(id, health()).unwrap() -= 15.0;
+    /// assert!(world.despawn_if(id, is_dead));
+    /// assert!(!world.is_alive(id));
+    ///
+    /// assert!(!world.despawn_if(id, |_| true));
+    /// ```
+    pub fn despawn_if(&mut self, id: Entity, predicate: impl FnOnce(EntityRef) -> bool) -> bool {
+        profile_function!();
+        self.flush_reserved();
+        let Ok(entity) = self.entity(id) else {
+            return false;
+        };
+
+        if !predicate(entity) {
+            return false;
+        }
+
+        self.despawn(id).expect("Invalid entity id");
+        true
+    }
+
     /// Despawns all entities which matches the filter
     pub fn despawn_many<F>(&mut self, filter: F)
     where
@@ -405,6 +689,95 @@ impl World {
         }
     }
 
+    /// Executes a closure for each item of the query, and then despawns the matched entities.
+    ///
+    /// This is useful for consuming one-shot entities, such as events.
+    ///
+    /// Archetypes where all entities are matched are cleared at once rather than despawning the
+    /// entities one by one.
+    pub fn drain<Q, F>(
+        &mut self,
+        fetch: Q,
+        filter: F,
+        mut func: impl FnMut(<Q as FetchItem<'_>>::Item),
+    ) where
+        Q: for<'x> Fetch<'x>,
+        F: for<'x> Fetch<'x>,
+    {
+        profile_function!();
+        self.flush_reserved();
+        let mut query = Query::new((entity_ids(), fetch)).filter(filter);
+        let mut ids = Vec::new();
+        for (id, item) in &mut query.borrow(self) {
+            func(item);
+            ids.push(id);
+        }
+
+        self.despawn_batch(&ids);
+    }
+
+    /// Despawns all entities matching `filter` for which `predicate` returns false.
+    ///
+    /// This is analogous to [`Vec::retain`], and archetypes where all entities are despawned are
+    /// cleared at once.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     position: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let ids = [-5.0, 1.0, 8.0]
+    ///     .map(|v| Entity::builder().set(position(), v).spawn(&mut world));
+    ///
+    /// world.retain(position().with(), |entity| {
+    ///     entity.get(position()).is_ok_and(|v| v.abs() < 4.0)
+    /// });
+    ///
+    /// assert_eq!(ids.map(|id| world.is_alive(id)), [false, true, false]);
+    /// ```
+    pub fn retain<F>(&mut self, filter: F, mut predicate: impl FnMut(EntityRef) -> bool)
+    where
+        F: for<'x> Fetch<'x>,
+    {
+        profile_function!();
+        self.flush_reserved();
+        let mut query = Query::new(entity_ids()).filter(filter);
+        let mut ids = query.borrow(self).iter().collect_vec();
+
+        ids.retain(|&id| !predicate(self.entity(id).expect("Invalid entity id")));
+
+        self.despawn_batch(&ids);
+    }
+
+    /// Despawns the entities, which must be contiguous per archetype
+    fn despawn_batch(&mut self, mut ids: &[Entity]) {
+        while let Some(&id) = ids.first() {
+            let arch_id = self.location(id).expect("Invalid entity id").arch_id;
+            let count = ids
+                .iter()
+                .take_while(|&&id| self.location(id).ok().map(|v| v.arch_id) == Some(arch_id))
+                .count();
+
+            let (batch, rest) = ids.split_at(count);
+            ids = rest;
+
+            let arch = self.archetypes.get_mut(arch_id);
+            if arch.len() == batch.len() {
+                arch.clear();
+                for &id in batch {
+                    self.free_entity(id).expect("Invalid entity id");
+                    self.detach(id);
+                }
+            } else {
+                for &id in batch {
+                    self.despawn(id).expect("Invalid entity id");
+                }
+            }
+        }
+    }
+
     /// Despawns an entity and all connected entities through the supplied
     /// relation
     pub fn despawn_recursive<T: ComponentValue>(
@@ -443,11 +816,12 @@ impl World {
 
             for &arch_id in &archetypes {
                 let arch = self.archetypes.get(arch_id);
+                let len = arch.len();
                 stack.extend(arch.entities());
-                for &id in arch.entities() {
-                    self.entities.init(id.kind()).despawn(id).unwrap();
-                }
                 self.archetypes.despawn(arch_id).clear();
+                for &id in &stack[stack.len() - len..] {
+                    self.free_entity(id).unwrap();
+                }
             }
         }
 
@@ -487,6 +861,61 @@ impl World {
         }
     }
 
+    /// Removes a component from all entities in the world.
+    ///
+    /// The entities of each archetype are moved at once, which is considerably faster than
+    /// removing the component from each entity individually.
+    ///
+    /// Returns the number of entities the component was removed from.
+    pub fn remove_all<T: ComponentValue>(&mut self, component: Component<T>) -> usize {
+        profile_function!();
+        self.flush_reserved();
+
+        let key = component.key();
+        let archetypes = self
+            .archetypes
+            .index
+            .find(key)
+            .into_iter()
+            .flat_map(|v| v.keys().copied())
+            .collect_vec();
+
+        let mut count = 0;
+        for src_id in archetypes {
+            let src = self.archetypes.get(src_id);
+            if src.is_empty() {
+                continue;
+            }
+
+            let dst_id = match src.incoming(key) {
+                Some(dst) => dst,
+                None => {
+                    let components = src.components_desc().filter(|v| v.key != key).collect_vec();
+                    let (dst_id, _) = self.archetypes.find_create(components);
+                    dst_id
+                }
+            };
+
+            let (src, dst) = self.archetypes.get_disjoint(src_id, dst_id).unwrap();
+            src.add_incoming(key, dst_id);
+            dst.add_outgoing(key, src_id);
+
+            let moved = src.move_all(dst);
+            count += moved.len();
+
+            for (id, slot) in moved {
+                *self.location_mut(id).expect("Entity id was not valid") = EntityLocation {
+                    slot,
+                    arch_id: dst_id,
+                }
+            }
+        }
+
+        self.histories.remove(&key);
+
+        count
+    }
+
     /// Updates a component in place
     pub fn update<T: ComponentValue, U>(
         &self,
@@ -510,7 +939,10 @@ impl World {
             }))
     }
 
-    /// Updates a component in place
+    /// Updates a component in place.
+    ///
+    /// Does not trigger a modification event if the value is the same, but rather a
+    /// [`EventKind::Touched`] event
     pub fn update_dedup<T: ComponentValue + PartialEq>(
         &self,
         id: Entity,
@@ -533,8 +965,70 @@ impl World {
             }))
     }
 
+    /// Updates a component in place for every entity which has it, in parallel.
+    ///
+    /// The column of each archetype is split across the threads of the rayon thread pool. This is
+    /// a shorthand for the common case of mutating each entity independently, such as integrating
+    /// positions, without constructing a query or schedule.
+    ///
+    /// All visited entities are marked as modified.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     timer: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let ids = (0..100)
+    ///     .map(|i| Entity::builder().set(timer(), i as f32).spawn(&mut world))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// world.par_update(timer(), |_, v| *v *= 0.5);
+    ///
+    /// assert_eq!(*world.get(ids[10], timer()).unwrap(), 5.0);
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_update<T: ComponentValue>(
+        &mut self,
+        component: Component<T>,
+        func: impl Fn(Entity, &mut T) + Send + Sync,
+    ) {
+        use rayon::prelude::{
+            IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator,
+        };
+
+        profile_function!();
+        let change_tick = self.advance_change_tick();
+
+        for (_, arch) in self.archetypes.iter() {
+            let Some(cell) = arch.cell(component.key()) else {
+                continue;
+            };
+
+            if arch.is_empty() {
+                continue;
+            }
+
+            let mut data = cell.data.borrow_mut();
+
+            data.storage
+                .downcast_mut::<T>()
+                .par_iter_mut()
+                .zip(arch.entities())
+                .for_each(|(value, &id)| func(id, value));
+
+            data.set_modified(arch.entities(), arch.slots(), change_tick);
+        }
+    }
+
     /// Set the value of a component.
     /// If the component does not exist it will be added.
+    ///
+    /// Returns the previous value of the component, if any.
+    ///
+    /// **Note**: for components with [`Combine`](crate::metadata::Combine) the value is combined
+    /// into the existing value rather than replacing it, and as such `None` is always returned.
     #[inline]
     pub fn set<T: ComponentValue>(
         &mut self,
@@ -542,22 +1036,66 @@ impl World {
         component: Component<T>,
         value: T,
     ) -> Result<Option<T>> {
-        Ok(self
+        if let Some(info) = component.desc().meta_ref().get(combine()) {
+            let combine = info.combine;
+            let mut value = mem::ManuallyDrop::new(value);
+            let ptr = &mut *value as *mut T as *mut u8;
+
+            let res = self.set_with_writer(
+                id,
+                SingleComponentWriter::new(
+                    component.desc(),
+                    CombineDyn {
+                        value: ptr,
+                        combine,
+                    },
+                ),
+            );
+
+            if let Err(err) = res {
+                // The value is only moved out by the writer
+                unsafe { mem::ManuallyDrop::drop(&mut value) };
+                return Err(err);
+            }
+
+            return Ok(None);
+        }
+
+        let old = self
             .set_with_writer(
                 id,
                 SingleComponentWriter::new(component.desc(), Replace::new(value)),
             )?
             .1
-            .left())
+            .left();
+
+        Ok(old)
     }
 
     /// Add the components stored in a component buffer to an entity
     pub fn set_with(&mut self, id: Entity, buffer: &mut ComponentBuffer) -> Result<()> {
         self.set_with_writer(id, writer::Buffered::new(buffer))?;
+        Ok(())
+    }
 
+    /// Replaces all components of an entity with the components stored in a component buffer.
+    ///
+    /// Unlike [`Self::clear`] followed by [`Self::set_with`], the entity is moved to its new
+    /// archetype at most once.
+    pub(crate) fn replace_with(&mut self, id: Entity, buffer: &mut ComponentBuffer) -> Result<()> {
+        self.set_with_writer(id, writer::Replaced::new(buffer))?;
         Ok(())
     }
 
+    /// Add all components of the bundle to an entity.
+    ///
+    /// The destination archetype is only resolved once for the whole bundle.
+    pub fn insert_bundle(&mut self, id: Entity, bundle: impl Bundle) -> Result<()> {
+        let mut buffer = ComponentBuffer::new();
+        bundle.write(&mut buffer);
+        self.set_with(id, &mut buffer)
+    }
+
     #[inline]
     pub(crate) fn set_dyn(
         &mut self,
@@ -565,8 +1103,18 @@ impl World {
         desc: ComponentDesc,
         value: *mut u8,
     ) -> Result<EntityLocation> {
-        let (loc, _) =
-            self.set_with_writer(id, SingleComponentWriter::new(desc, ReplaceDyn { value }))?;
+        let (loc, _) = match desc.meta_ref().get(combine()) {
+            Some(info) => {
+                let combine = info.combine;
+                self.set_with_writer(
+                    id,
+                    SingleComponentWriter::new(desc, CombineDyn { value, combine }),
+                )?
+            }
+            None => {
+                self.set_with_writer(id, SingleComponentWriter::new(desc, ReplaceDyn { value }))?
+            }
+        };
 
         Ok(loc)
     }
@@ -585,6 +1133,76 @@ impl World {
         Ok(writer.write(self, id, src_loc, change_tick))
     }
 
+    pub(crate) fn history_components(buffer: &ComponentBuffer) -> SmallVec<[ComponentDesc; 4]> {
+        buffer
+            .components()
+            .filter(|v| v.meta_ref().has(history()))
+            .copied()
+            .collect()
+    }
+
+    /// Records the current value of a component which has [`History`](crate::metadata::History)
+    pub(crate) fn record_history(&mut self, id: Entity, desc: ComponentDesc) {
+        let Some(info) = desc.meta_ref().get(history()) else {
+            return;
+        };
+
+        let Ok(loc) = self.location(id) else {
+            return;
+        };
+
+        let Some(cell) = self.archetypes.get(loc.arch_id).cell(desc.key()) else {
+            return;
+        };
+
+        let data = cell.data.borrow();
+        let value = data
+            .storage
+            .at(loc.slot)
+            .expect("Storage is smaller than archetype");
+
+        let store = self
+            .histories
+            .entry(desc.key())
+            .or_insert_with(|| info.create());
+
+        unsafe { info.record(&mut **store, id, value) }
+    }
+
+    /// Removes the history of a component which was removed from an entity
+    pub(crate) fn forget_history(&mut self, id: Entity, key: ComponentKey) {
+        if let Some(store) = self.histories.get_mut(&key) {
+            store.remove(id);
+        }
+    }
+
+    /// Removes the history of all components of an entity
+    fn clear_history(&mut self, id: Entity) {
+        for store in self.histories.values_mut() {
+            store.remove(id);
+        }
+    }
+
+    /// Returns the last values set for the component of an entity, oldest first.
+    ///
+    /// The history is only kept for components with the [`History`](crate::metadata::History)
+    /// metadata. No values are returned if none have been recorded.
+    pub fn component_history<T: ComponentValue>(
+        &self,
+        id: Entity,
+        component: Component<T>,
+    ) -> impl ExactSizeIterator<Item = &T> + DoubleEndedIterator {
+        let Some(store) = self.histories.get(&component.key()) else {
+            return Default::default();
+        };
+
+        store
+            .as_any()
+            .downcast_ref::<HistoryBuffer<T>>()
+            .expect("Mismatched history type")
+            .get(id)
+    }
+
     #[inline]
     pub(crate) fn remove_dyn(&mut self, id: Entity, component: ComponentDesc) -> Result<()> {
         unsafe {
@@ -655,11 +1273,37 @@ impl World {
         };
 
         *self.location_mut(id).expect("Entity is not valid") = loc;
+        self.forget_history(id, desc.key());
 
         Ok(loc)
     }
 
-    /// Remove a component from the entity
+    /// Remove a component from the entity, returning its value.
+    ///
+    /// The value is moved out of the archetype storage rather than dropped, which allows taking
+    /// ownership of values such as resource handles stored as components.
+    ///
+    /// Fails with [`Error::NoSuchEntity`] if the entity does not exist, and with
+    /// [`Error::MissingComponent`] if the entity lacks the component.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// # use std::sync::Arc;
+    /// component! {
+    ///     handle: Arc<String>,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let texture = Arc::new("texture".to_string());
+    ///
+    /// let id = Entity::builder()
+    ///     .set(handle(), texture.clone())
+    ///     .spawn(&mut world);
+    ///
+    /// let value = world.remove(id, handle()).unwrap();
+    /// assert!(Arc::ptr_eq(&value, &texture));
+    /// assert_eq!(Arc::strong_count(&texture), 2);
+    /// ```
     #[inline]
     pub fn remove<T: ComponentValue>(&mut self, id: Entity, component: Component<T>) -> Result<T> {
         let mut res: MaybeUninit<T> = MaybeUninit::uninit();
@@ -673,10 +1317,66 @@ impl World {
         Ok(res)
     }
 
-    /// Randomly access an entity's component.
-    pub fn get<T: ComponentValue>(
-        &self,
-        id: Entity,
+    /// Moves a component from one entity to another, without cloning the value.
+    ///
+    /// The component is removed from `from` and set on `to` as if by [`World::set`], replacing
+    /// any existing value. Removal and insertion events are emitted as usual.
+    ///
+    /// Fails with [`Error::NoSuchEntity`] if either entity does not exist, and with
+    /// [`Error::MissingComponent`] if `from` lacks the component, in which case neither entity
+    /// is modified. Moving a component to the same entity does nothing.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     weapon: String,
+    /// }
+    ///
+    /// let mut world = World::new();
+    ///
+    /// let player = Entity::builder().spawn(&mut world);
+    /// let enemy = Entity::builder()
+    ///     .set(weapon(), "sword".into())
+    ///     .spawn(&mut world);
+    ///
+    /// world.move_component(enemy, player, weapon()).unwrap();
+    ///
+    /// assert!(!world.has(enemy, weapon()));
+    /// assert_eq!(world.get(player, weapon()).as_deref(), Ok(&"sword".into()));
+    /// ```
+    pub fn move_component<T: ComponentValue>(
+        &mut self,
+        from: Entity,
+        to: Entity,
+        component: Component<T>,
+    ) -> Result<()> {
+        self.init_location(to)?;
+
+        if from == to {
+            let loc = self.location(from)?;
+            if !self.archetypes.get(loc.arch_id).has(component.key()) {
+                return Err(Error::MissingComponent(MissingComponent {
+                    id: from,
+                    desc: component.desc(),
+                }));
+            }
+
+            return Ok(());
+        }
+
+        let value = self.remove(from, component)?;
+        self.set(to, component, value)?;
+
+        Ok(())
+    }
+
+    /// Randomly access an entity's component.
+    ///
+    /// Fails with [`Error::NoSuchEntity`] if the entity does not exist, and with
+    /// [`Error::MissingComponent`] if the entity exists but lacks the component.
+    pub fn get<T: ComponentValue>(
+        &self,
+        id: Entity,
         component: Component<T>,
     ) -> Result<AtomicRef<T>> {
         let loc = self.location(id)?;
@@ -689,6 +1389,60 @@ impl World {
         })
     }
 
+    /// Shorthand to copy and not use a borrowing reference
+    pub fn get_copy<T: ComponentValue + Copy>(
+        &self,
+        id: Entity,
+        component: Component<T>,
+    ) -> Result<T> {
+        self.get(id, component).map(|v| *v)
+    }
+
+    /// Shorthand to clone and not use a borrowing reference
+    pub fn get_cloned<T: ComponentValue + Clone>(
+        &self,
+        id: Entity,
+        component: Component<T>,
+    ) -> Result<T> {
+        self.get(id, component).map(|v| v.clone())
+    }
+
+    /// Access a component by a previously retrieved [`World::location`], without resolving the
+    /// entity id.
+    ///
+    /// This is useful for tight loops over a stable set of entities, where the locations can be
+    /// collected once.
+    ///
+    /// Returns `None` if the archetype no longer exists, the slot is out of bounds, or the
+    /// archetype lacks the component.
+    ///
+    /// **Note**: a location is invalidated when the entity moves between archetypes, or when
+    /// another entity in the same archetype is removed, as entities are swapped into the freed
+    /// slot. In that case the component of a *different* entity may be returned.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     health: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let id = Entity::builder().set(health(), 50.0).spawn(&mut world);
+    ///
+    /// let loc = world.location(id).unwrap();
+    /// assert_eq!(world.get_by_location(loc, health()).as_deref(), Some(&50.0));
+    /// ```
+    #[inline]
+    pub fn get_by_location<T: ComponentValue>(
+        &self,
+        loc: EntityLocation,
+        component: Component<T>,
+    ) -> Option<AtomicRef<'_, T>> {
+        self.archetypes
+            .try_get(loc.arch_id)?
+            .get(loc.slot, component)
+    }
+
     #[inline]
     pub(crate) fn get_at<T: ComponentValue>(
         &self,
@@ -713,6 +1467,9 @@ impl World {
     }
 
     /// Randomly access an entity's component.
+    ///
+    /// Fails with [`Error::NoSuchEntity`] if the entity does not exist, and with
+    /// [`Error::MissingComponent`] if the entity exists but lacks the component.
     pub fn get_mut<T: ComponentValue>(
         &self,
         id: Entity,
@@ -728,6 +1485,213 @@ impl World {
         })
     }
 
+    /// Randomly access an entity's component mutably, without triggering a change until written
+    /// to.
+    ///
+    /// In contrast to [`Self::get_mut`], which records a change on any mutable dereference, such
+    /// as implicitly calling a method taking `&mut self`, the returned guard only records a change
+    /// when explicitly calling [`LazyRefMut::write`]. This is the random access equivalent of
+    /// [`Component::maybe_mut`].
+    ///
+    /// The component is borrowed for the duration of each `read` or `write` rather than for the
+    /// lifetime of the guard.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     health: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let id = Entity::builder().set(health(), 100.0).spawn(&mut world);
+    ///
+    /// let mut query = Query::new(entity_ids()).filter(health().modified());
+    /// assert_eq!(query.collect_vec(&world), [id]);
+    ///
+    /// let guard = world.get_mut_lazy(id, health()).unwrap();
+    /// if *guard.read() < 50.0 {
+    ///     *guard.write() = 50.0;
+    /// }
+    ///
+    /// assert!(query.collect_vec(&world).is_empty());
+    ///
+    /// *guard.write() -= 60.0;
+    /// assert_eq!(query.collect_vec(&world), [id]);
+    /// ```
+    pub fn get_mut_lazy<T: ComponentValue>(
+        &self,
+        id: Entity,
+        component: Component<T>,
+    ) -> Result<LazyRefMut<'_, T>> {
+        let loc = self.location(id)?;
+
+        let cell = self
+            .archetypes
+            .get(loc.arch_id)
+            .cell(component.key())
+            .ok_or_else(|| {
+                Error::MissingComponent(MissingComponent {
+                    id,
+                    desc: component.desc(),
+                })
+            })?;
+
+        Ok(LazyRefMut::new(self, cell, id, loc.slot))
+    }
+
+    /// Borrow several components of an entity at once.
+    ///
+    /// Components are borrowed mutably using [`Component::as_mut`], and shared otherwise. This is
+    /// the single entity equivalent of a query.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     position: f32,
+    ///     velocity: f32,
+    ///     mass: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let id = Entity::builder()
+    ///     .set(position(), 1.0)
+    ///     .set(velocity(), 2.0)
+    ///     .set(mass(), 4.0)
+    ///     .spawn(&mut world);
+    ///
+    /// {
+    ///     let (mut pos, mut vel, mass) = world
+    ///         .get_many_mut(id, (position().as_mut(), velocity().as_mut(), mass()))
+    ///         .unwrap();
+    ///
+    ///     *vel += 1.0 / *mass;
+    ///     *pos += *vel;
+    /// }
+    ///
+    /// assert_eq!(world.get_copy(id, position()), Ok(3.25));
+    /// ```
+    ///
+    /// # Errors
+    /// - [`Error::NoSuchEntity`] if the entity does not exist
+    /// - [`Error::MissingComponent`] if the entity does not have a component
+    /// - [`Error::AliasedBorrow`] if the same component is borrowed mutably and borrowed again
+    ///
+    /// # Panics
+    /// If a mutably borrowed component is already borrowed elsewhere.
+    pub fn get_many_mut<'a, C: ComponentRefs<'a>>(
+        &'a self,
+        id: Entity,
+        components: C,
+    ) -> Result<C::Output> {
+        let mut borrows = Vec::new();
+        components.borrows(id, &mut borrows);
+
+        // Validate all components before borrowing, as a mutable borrow records a modification
+        self.validate_borrows(&borrows)?;
+
+        let loc = self.location(id)?;
+        let arch = self.archetypes.get(loc.arch_id);
+        components.borrow(id, arch, loc.slot, self.advance_change_tick())
+    }
+
+    /// Checks that every entity has the borrowed component, and that no component of an entity
+    /// is borrowed mutably more than once
+    fn validate_borrows(&self, borrows: &[(Entity, ComponentDesc, bool)]) -> Result<()> {
+        for (i, &(id, desc, mutable)) in borrows.iter().enumerate() {
+            let loc = self.location(id)?;
+            if !self.archetypes.get(loc.arch_id).has(desc.key()) {
+                return Err(MissingComponent { id, desc }.into());
+            }
+
+            if is_aliased(&borrows[..i], id, desc, mutable) {
+                return Err(Error::AliasedBorrow(id, desc));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Borrow components of several entities at once, such as for atomically moving a value
+    /// from one entity to another.
+    ///
+    /// Accepts tuples of an entity paired with a component, which is borrowed mutably using
+    /// [`Component::as_mut`], and shared otherwise.
+    ///
+    /// All entities and components are validated before anything is borrowed, so either all
+    /// borrows are acquired or an error is returned and the world is left untouched. This avoids
+    /// leaving the world partially modified, as can happen when a sequence of
+    /// [`Self::get_mut`] fails midway.
+    ///
+    /// A modification is recorded for each mutably borrowed component.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     gold: u32,
+    ///     items: Vec<&'static str>,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let buyer = Entity::builder()
+    ///     .set(gold(), 100)
+    ///     .set(items(), vec![])
+    ///     .spawn(&mut world);
+    /// let seller = Entity::builder()
+    ///     .set(gold(), 0)
+    ///     .set(items(), vec!["sword"])
+    ///     .spawn(&mut world);
+    ///
+    /// let (buyer_gold, buyer_items, seller_gold, seller_items) = world
+    ///     .get_mut_many_components((
+    ///         (buyer, gold().as_mut()),
+    ///         (buyer, items().as_mut()),
+    ///         (seller, gold().as_mut()),
+    ///         (seller, items().as_mut()),
+    ///     ))
+    ///     .unwrap();
+    ///
+    /// *buyer_gold -= 30;
+    /// *seller_gold += 30;
+    /// buyer_items.extend(seller_items.drain(..));
+    ///
+    /// assert_eq!(world.get_copy(seller, gold()), Ok(30));
+    /// assert_eq!(world.get_cloned(buyer, items()), Ok(vec!["sword"]));
+    /// ```
+    ///
+    /// # Errors
+    /// - [`Error::NoSuchEntity`] if an entity does not exist
+    /// - [`Error::MissingComponent`] if an entity does not have the component
+    /// - [`Error::AliasedBorrow`] if the same component of an entity is borrowed mutably and
+    ///   borrowed again
+    pub fn get_mut_many_components<'a, C: EntityComponentRefs<'a>>(
+        &'a mut self,
+        components: C,
+    ) -> Result<C::Output> {
+        let mut borrows = Vec::new();
+        components.borrows(&mut borrows);
+
+        self.validate_borrows(&borrows)?;
+
+        let tick = self.advance_change_tick();
+        for &(id, desc, mutable) in &borrows {
+            if !mutable {
+                continue;
+            }
+
+            let loc = self.location(id).unwrap();
+            self.archetypes
+                .get_mut(loc.arch_id)
+                .cell_mut(desc.key())
+                .unwrap()
+                .data
+                .get_mut()
+                .set_modified(&[id], Slice::single(loc.slot), tick);
+        }
+
+        // Safety: all components exist, and no component of an entity is aliased
+        Ok(unsafe { components.borrow(self) })
+    }
+
     /// Randomly access an entity's component.
     pub(crate) fn get_mut_at<T: ComponentValue>(
         &self,
@@ -767,6 +1731,48 @@ impl World {
         }
     }
 
+    /// Returns the targets of all relations of the specified kind on `subject`.
+    ///
+    /// This is cheaper than [`EntityRef::relations`] when only the connectivity is needed, as the
+    /// relation values are not borrowed.
+    ///
+    /// Returns an empty list if the entity does not exist.
+    pub fn relation_targets<T: ComponentValue>(
+        &self,
+        subject: Entity,
+        relation: impl RelationExt<T>,
+    ) -> SmallVec<[Entity; 4]> {
+        let Ok(loc) = self.location(subject) else {
+            return SmallVec::new();
+        };
+
+        self.archetypes
+            .get(loc.arch_id)
+            .relations_like(relation.id())
+            .map(|(key, _)| key.target.unwrap())
+            .collect()
+    }
+
+    /// Sets how the indices of despawned entities of the given kind are reused.
+    ///
+    /// Each kind of entity, such as [`EntityKind::empty`] for regular entities, has its own
+    /// indices and policy. The policy applies to entities despawned after it is set.
+    ///
+    /// ```rust
+    /// # use flax::{*, entity::{EntityKind, RecyclePolicy}};
+    /// let mut world = World::new();
+    /// world.set_recycle_policy(EntityKind::empty(), RecyclePolicy::NeverReuse);
+    ///
+    /// let a = world.spawn();
+    /// world.despawn(a).unwrap();
+    ///
+    /// let b = world.spawn();
+    /// assert!(b.index() > a.index());
+    /// ```
+    pub fn set_recycle_policy(&mut self, kind: EntityKind, policy: RecyclePolicy) {
+        self.entities.init(kind).set_policy(policy)
+    }
+
     /// Returns true if the entity is still alive.
     ///
     /// **Note**: false is returned static entities which are not yet present in the world, for example, before
@@ -785,7 +1791,11 @@ impl World {
     ///
     /// *Note*: Fails for static entities which are not yet spawned into the world, which happens
     /// when a component is first added.
-    pub(crate) fn location(&self, id: Entity) -> Result<EntityLocation> {
+    ///
+    /// See [`EntityLocation`] for when the returned location is invalidated.
+    ///
+    /// See: [`World::get_by_location`]
+    pub fn location(&self, id: Entity) -> Result<EntityLocation> {
         match self.entities.get(id.kind()).and_then(|v| v.get(id)) {
             Some(&loc) => Ok(loc),
             None => Err(Error::NoSuchEntity(id)),
@@ -839,6 +1849,7 @@ impl World {
         ids: &'a [Entity],
         chunk: &mut BatchSpawn,
     ) -> Result<&'a [Entity]> {
+        add_required_batch(chunk);
         for component in chunk.components() {
             self.init_component(component);
         }
@@ -902,6 +1913,45 @@ impl World {
         Ok(ids)
     }
 
+    /// Eagerly registers a component in the world.
+    ///
+    /// Components are otherwise registered lazily when first used, such as when first set on an
+    /// entity. Registering does nothing if the component is already registered.
+    ///
+    /// The ids of components declared through [`component!`](macro@crate::component) are acquired from
+    /// a process-wide counter the first time each component is accessed. Registering the
+    /// components in a fixed order at startup, before they are used elsewhere, therefore makes both
+    /// the ids and the order of the [`ComponentKey`]s reproducible across runs, independent of
+    /// spawn order. This is useful for stable serialization and networking.
+    ///
+    /// See: [`Self::register_all`]
+    pub fn register_component<T: ComponentValue>(&mut self, component: Component<T>) {
+        self.init_component(component.desc())
+    }
+
+    /// Eagerly registers multiple components in the world, in the given order.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     position: (f32, f32),
+    ///     velocity: (f32, f32),
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// world.register_all([position().desc(), velocity().desc()]);
+    ///
+    /// assert!(position().key() < velocity().key());
+    /// assert!(world.is_alive(velocity().id()));
+    /// ```
+    ///
+    /// See: [`Self::register_component`]
+    pub fn register_all(&mut self, components: impl IntoIterator<Item = ComponentDesc>) {
+        for desc in components {
+            self.init_component(desc)
+        }
+    }
+
     /// Spawn a new component of type `T` which can be attached to an entity.
     ///
     /// The given name does not need to be unique.
@@ -972,6 +2022,156 @@ impl World {
         (self.change_tick.fetch_or(1, Ordering::Relaxed) >> 1) + 1
     }
 
+    /// Executes `f` while deferring modification events to subscribers until it returns.
+    ///
+    /// The modified slots of each component are coalesced, such that subscribers receive one
+    /// [`EventSubscriber::on_modified`] call per contiguous range of modified entities rather than
+    /// one per modification. This reduces the notification overhead of mass edits, such as
+    /// deserialization.
+    ///
+    /// Change detection through queries, such as [`FetchExt::modified`], is not affected, and
+    /// neither are events for added or removed components.
+    ///
+    /// Nested batches are merged into the outermost one.
+    ///
+    /// [`FetchExt::modified`]: crate::FetchExt::modified
+    pub fn with_change_batch(&mut self, f: impl FnOnce(&mut World)) {
+        if self.archetypes.defer_modified {
+            f(self);
+            return;
+        }
+
+        self.archetypes.defer_modified = true;
+        for (_, arch) in self.archetypes.iter_mut() {
+            arch.defer_modified();
+        }
+
+        f(self);
+
+        self.archetypes.defer_modified = false;
+        for (_, arch) in self.archetypes.iter_mut() {
+            arch.flush_modified();
+        }
+    }
+
+    /// Explicitly advances the change tick and returns the new one.
+    ///
+    /// The change tick is otherwise advanced implicitly when components are added, removed, or
+    /// mutably accessed, though only if the current tick has been observed since the last
+    /// increment. This means several modifications may share the same tick.
+    ///
+    /// In contrast, this always increments the tick, which allows marking a clean boundary,
+    /// such as a frame. Any change made after this call is guaranteed to be newer than all changes
+    /// made before it.
+    ///
+    /// Subscribers are notified through [`EventSubscriber::on_tick`].
+    pub fn tick(&mut self) -> u32 {
+        let v = self.change_tick.get_mut();
+        // Set the read bit to force an increment
+        *v = (*v | 1) + 1;
+        let tick = (*v >> 1) + 1;
+
+        for subscriber in self.archetypes.subscribers() {
+            subscriber.on_tick(tick);
+        }
+
+        self.update_dirty_flags();
+
+        tick
+    }
+
+    /// Keeps `dirty` of each entity set to whether `component` was added or modified during the
+    /// previous frame, as delimited by [`Self::tick`].
+    ///
+    /// The flags are updated when the world is ticked, which allows iterating the entities which
+    /// changed during the last frame through a regular query, rather than subscribing to events.
+    ///
+    /// Only entities which have the `dirty` component are flagged, and the component is not
+    /// added automatically. Use [`Requires`](crate::metadata::Requires) to insert it along with
+    /// `component`.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     position: (f32, f32),
+    ///     position_changed: bool,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// world.track_changes(position(), position_changed());
+    ///
+    /// let id = Entity::builder()
+    ///     .set(position(), (0.0, 0.0))
+    ///     .set(position_changed(), false)
+    ///     .spawn(&mut world);
+    ///
+    /// world.tick();
+    /// assert_eq!(world.get_copy(id, position_changed()), Ok(true));
+    ///
+    /// world.tick();
+    /// assert_eq!(world.get_copy(id, position_changed()), Ok(false));
+    ///
+    /// *world.get_mut(id, position()).unwrap() = (1.0, 0.0);
+    /// world.tick();
+    /// assert_eq!(world.get_copy(id, position_changed()), Ok(true));
+    /// ```
+    pub fn track_changes<T: ComponentValue>(
+        &mut self,
+        component: Component<T>,
+        dirty: Component<bool>,
+    ) {
+        self.init_component(dirty.desc());
+
+        let tracker = Arc::new(ChangeTracker::new(component.key()));
+        self.archetypes.add_subscriber(tracker.clone());
+
+        self.dirty_flags.push(DirtyFlags {
+            tracker,
+            dirty,
+            flagged: Vec::new(),
+        });
+    }
+
+    fn update_dirty_flags(&mut self) {
+        let mut dirty_flags = mem::take(&mut self.dirty_flags);
+
+        for flags in &mut dirty_flags {
+            let changed = flags.tracker.take();
+
+            for id in flags.flagged.drain(..) {
+                if changed.contains(&id) {
+                    continue;
+                }
+
+                self.write_dirty_flag(id, flags.dirty, false);
+            }
+
+            for id in changed {
+                if self.write_dirty_flag(id, flags.dirty, true) {
+                    flags.flagged.push(id);
+                }
+            }
+        }
+
+        self.dirty_flags = dirty_flags;
+    }
+
+    /// Sets the dirty flag of an entity, returning false if the entity does not have it.
+    ///
+    /// The flag is only written if it differs, so that it is not marked as modified each tick.
+    fn write_dirty_flag(&self, id: Entity, dirty: Component<bool>, value: bool) -> bool {
+        match self.get_copy(id, dirty) {
+            Ok(v) => {
+                if v != value {
+                    *self.get_mut(id, dirty).expect("Entity has the flag") = value;
+                }
+
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
     /// Increases the change tick and returns the new one
     pub(crate) fn advance_change_tick(&self) -> u32 {
         let v = self
@@ -1033,6 +2233,186 @@ impl World {
         self.archetypes.iter().map(|(k, v)| (k, v.desc())).collect()
     }
 
+    /// Returns the number of entities which have each component, summed across all archetypes.
+    ///
+    /// The components are ordered by their key, and components which are not held by any entity
+    /// are omitted. This includes the builtin components of the component entities themselves,
+    /// such as [`name`].
+    pub fn component_usage(&self) -> Vec<(ComponentDesc, usize)> {
+        let mut usage = BTreeMap::new();
+        for (_, arch) in self.archetypes.iter() {
+            if arch.is_empty() {
+                continue;
+            }
+
+            for cell in arch.cells() {
+                let desc = cell.desc();
+                usage.entry(desc.key()).or_insert((desc, 0)).1 += arch.len();
+            }
+        }
+
+        usage.into_values().collect_vec()
+    }
+
+    /// Verifies that the relation and target of every pair component held by an entity are still
+    /// alive.
+    ///
+    /// Despawning an entity removes all pairs which refer to it, so this always succeeds unless a
+    /// despawn path failed to clean up its relations. This is intended to be called in tests or
+    /// debug builds to catch such bugs early, as dangling pairs cause e.g. [`Topo`](crate::Topo)
+    /// to panic.
+    ///
+    /// Returns the dangling pairs, if any.
+    pub fn validate_relations(&self) -> core::result::Result<(), Vec<ComponentKey>> {
+        let mut dangling = BTreeSet::new();
+        for (_, arch) in self.archetypes.iter() {
+            // Empty archetypes do not hold any pairs
+            if arch.is_empty() {
+                continue;
+            }
+
+            for &key in arch.components().keys() {
+                let Some(target) = key.target else {
+                    continue;
+                };
+
+                if !self.is_alive(key.id) || !self.is_alive(target) {
+                    dangling.insert(key);
+                }
+            }
+        }
+
+        if dangling.is_empty() {
+            Ok(())
+        } else {
+            Err(dangling.into_iter().collect_vec())
+        }
+    }
+
+    /// Returns the archetype graph in the Graphviz dot format.
+    ///
+    /// Each node is an archetype labeled by its components and number of entities, and each edge
+    /// is labeled by the component which is added to move an entity to the other archetype.
+    ///
+    /// This is useful for finding the cause of a large number of archetypes, such as a component
+    /// being frequently added and removed in combination with other components.
+    pub fn archetype_graph_dot(&self) -> String {
+        use core::fmt::Write;
+
+        fn label(desc: ComponentDesc) -> String {
+            let name = desc.name().replace('"', "\\\"");
+            match desc.key().target {
+                Some(target) => alloc::format!("{name}({target})"),
+                None => name,
+            }
+        }
+
+        let mut s = String::new();
+        s.push_str("digraph archetypes {\n    node [shape=box];\n");
+
+        for (arch_id, arch) in self.archetype_iter_sorted() {
+            let components = arch.components_desc().map(label).join(", ");
+            writeln!(
+                s,
+                "    \"{arch_id}\" [label=\"{{{components}}}\\n{} entities\"];",
+                arch.len()
+            )
+            .unwrap();
+        }
+
+        for (arch_id, arch) in self.archetype_iter_sorted() {
+            for (&key, &dst_id) in &arch.outgoing {
+                let dst = self.archetypes.get(dst_id);
+                let Some(desc) = dst.components_desc().find(|v| v.key() == key) else {
+                    continue;
+                };
+
+                writeln!(
+                    s,
+                    "    \"{arch_id}\" -> \"{dst_id}\" [label=\"{}\"];",
+                    label(desc)
+                )
+                .unwrap();
+            }
+        }
+
+        s.push_str("}\n");
+        s
+    }
+
+    /// Iterate all archetypes ordered by their component keys.
+    ///
+    /// In contrast to the creation order of archetypes, which depends on the order entities were
+    /// spawned and modified, this order only depends on the components of each archetype. This
+    /// allows logically identical worlds to be visited, and serialized, in the same order.
+    pub fn archetype_iter_sorted(&self) -> impl Iterator<Item = (ArchetypeId, &Archetype)> {
+        let mut archetypes = self.archetypes.iter().collect_vec();
+        archetypes.sort_by(|(_, a), (_, b)| a.components().keys().cmp(b.components().keys()));
+        archetypes.into_iter()
+    }
+
+    /// Returns the slices of entities in the archetype for which the component was modified or
+    /// added after `since_tick`.
+    ///
+    /// Modifications are only tracked once they are observed, which means this starts tracking
+    /// modifications of the component in the archetype if it was not already. Additions are always
+    /// tracked.
+    ///
+    /// Returns an empty iterator if the archetype does not have the component.
+    ///
+    /// # Panics
+    /// If the archetype does not exist.
+    pub fn component_changes(
+        &self,
+        arch_id: ArchetypeId,
+        key: ComponentKey,
+        since_tick: u32,
+    ) -> impl Iterator<Item = Slice> {
+        let arch = self.archetypes.get(arch_id);
+
+        let slices = match arch.changes(key) {
+            Some(changes) => changes.modified_since(since_tick).collect_vec(),
+            None => Vec::new(),
+        };
+
+        slices.into_iter()
+    }
+
+    /// Visits each value of a component in the archetype without knowing its concrete type.
+    ///
+    /// The closure receives the entity, a pointer to the component value, and the component
+    /// description. The pointer is valid for reads of the type described by the component for
+    /// the duration of the call, and can be used together with the component's metadata, such as
+    /// [`Debuggable`](crate::Debuggable), to operate on arbitrary components.
+    ///
+    /// Does nothing if the archetype does not have the component.
+    ///
+    /// # Panics
+    /// If the archetype does not exist, or if the component is borrowed mutably.
+    pub fn visit_column(
+        &self,
+        arch_id: ArchetypeId,
+        key: ComponentKey,
+        mut f: impl FnMut(Entity, *const u8, &ComponentDesc),
+    ) {
+        let arch = self.archetypes.get(arch_id);
+
+        let Some(cell) = arch.cell(key) else {
+            return;
+        };
+
+        let data = cell.data.borrow();
+        let desc = data.storage.desc();
+
+        for (slot, &id) in arch.entities().iter().enumerate() {
+            let ptr = data
+                .storage
+                .at(slot)
+                .expect("Storage is smaller than archetype");
+            f(id, ptr, &desc);
+        }
+    }
+
     /// Attempt to find an alive entity given the id
     pub fn reconstruct(&self, index: EntityIndex, kind: EntityKind) -> Option<Entity> {
         let ns = self.entities.get(kind)?;
@@ -1054,7 +2434,97 @@ impl World {
         Some(Component::from_raw_parts(id, desc.vtable))
     }
 
+    /// Returns the description of a component, such as its name and type, given only its key.
+    ///
+    /// This is useful when the typed component is not available, such as for keys received
+    /// through events.
+    ///
+    /// Returns `None` if the component has not yet been used in this world.
+    pub fn component_info(&self, key: ComponentKey) -> Option<ComponentDesc> {
+        let desc = *self.get(key.id, component_info()).ok()?;
+
+        Some(ComponentDesc {
+            key,
+            vtable: desc.vtable,
+        })
+    }
+
+    /// Returns true if the component has the given metadata, such as
+    /// [`Debuggable`](crate::Debuggable), given only its key.
+    ///
+    /// The metadata of a component are themselves components on the component entity. This allows
+    /// generic tooling, such as inspectors or serializers, to discover what each component supports.
+    ///
+    /// Relations share the metadata of the relation, regardless of the target.
+    ///
+    /// Returns `false` if the component has not yet been used in this world.
+    ///
+    /// ```rust
+    /// # use flax::{*, metadata::debuggable};
+    /// component! {
+    ///     health: f32 => [Debuggable],
+    ///     secret: String,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// Entity::builder()
+    ///     .set(health(), 50.0)
+    ///     .set(secret(), "hunter2".into())
+    ///     .spawn(&mut world);
+    ///
+    /// let keys = [health().key(), secret().key()];
+    /// let debuggable = keys
+    ///     .into_iter()
+    ///     .filter(|&key| world.component_has_meta(key, debuggable()))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(debuggable, [health().key()]);
+    /// ```
+    pub fn component_has_meta<T: ComponentValue>(
+        &self,
+        key: ComponentKey,
+        meta: Component<T>,
+    ) -> bool {
+        self.has(key.id, meta)
+    }
+
+    /// Returns the value of a metadata of a component given only its key.
+    ///
+    /// See: [`Self::component_has_meta`]
+    pub fn component_meta<T: ComponentValue>(
+        &self,
+        key: ComponentKey,
+        meta: Component<T>,
+    ) -> Result<AtomicRef<'_, T>> {
+        self.get(key.id, meta)
+    }
+
     /// Access, insert, and remove all components of an entity
+    ///
+    /// The entity is looked up once, which makes this the preferred way of performing several
+    /// modifications to the same entity.
+    ///
+    /// ```rust
+    /// # use flax::{*, components::{name, child_of}};
+    /// component! {
+    ///     health: f32,
+    ///     armor: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let parent = world.spawn();
+    /// let id = Entity::builder().set(health(), 50.0).spawn(&mut world);
+    ///
+    /// let mut entity = world.entity_mut(id).unwrap();
+    /// entity.set(name(), "player".into());
+    /// entity.set(child_of(parent), ());
+    /// *entity.get_mut(health()).unwrap() += 25.0;
+    /// entity.set(armor(), 10.0);
+    /// assert_eq!(entity.remove(armor()), Ok(10.0));
+    ///
+    /// assert_eq!(entity.relations(child_of).map(|v| v.0).collect::<Vec<_>>(), [parent]);
+    /// assert_eq!(world.get_copy(id, health()), Ok(75.0));
+    /// ```
     pub fn entity_mut(&mut self, id: Entity) -> Result<EntityRefMut> {
         let loc = self.init_location(id)?;
         Ok(EntityRefMut {
@@ -1079,6 +2549,66 @@ impl World {
         })
     }
 
+    /// Returns a builder with a copy of the components of an existing entity, which can be
+    /// modified before spawning.
+    ///
+    /// This allows using a live entity as a template, such as for spawning variants of a prefab.
+    ///
+    /// Only components and relations with the [`Cloneable`](crate::Cloneable) metadata are
+    /// copied. The entity itself is left untouched.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     health: f32 => [Cloneable],
+    ///     speed: f32 => [Cloneable],
+    ///     target: Entity,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let player = world.spawn();
+    /// let goblin = Entity::builder()
+    ///     .set(health(), 50.0)
+    ///     .set(speed(), 2.0)
+    ///     .set(target(), player)
+    ///     .spawn(&mut world);
+    ///
+    /// let fast_goblin = world
+    ///     .entity_builder_from(goblin)
+    ///     .unwrap()
+    ///     .set(speed(), 4.0)
+    ///     .spawn(&mut world);
+    ///
+    /// assert_eq!(world.get_copy(fast_goblin, health()), Ok(50.0));
+    /// assert_eq!(world.get_copy(fast_goblin, speed()), Ok(4.0));
+    /// assert!(!world.has(fast_goblin, target()));
+    ///
+    /// assert_eq!(world.get_copy(goblin, speed()), Ok(2.0));
+    /// ```
+    pub fn entity_builder_from(&self, id: Entity) -> Result<EntityBuilder> {
+        let loc = self.location(id)?;
+        let arch = self.archetypes.get(loc.arch_id);
+
+        let mut builder = EntityBuilder::new();
+        for desc in arch.components_desc() {
+            let Some(cloneable) = desc.meta_ref().get(metadata::cloneable()) else {
+                continue;
+            };
+
+            let data = arch.cell(desc.key()).unwrap().data.borrow();
+            // Safety: the metadata belongs to `desc`
+            unsafe {
+                (cloneable.clone)(
+                    data.storage.at(loc.slot).unwrap(),
+                    desc,
+                    builder.buffer_mut(),
+                )
+            }
+        }
+
+        Ok(builder)
+    }
+
     /// Returns an entry for a given component of an entity allowing for
     /// in-place manipulation, insertion or removal.
     ///
@@ -1092,7 +2622,10 @@ impl World {
         let arch = self.archetypes.get(loc.arch_id);
         if arch.has(component.key()) {
             return Ok(Entry::Occupied(OccupiedEntry {
-                borrow: self.get_mut(id, component).unwrap(),
+                world: self,
+                id,
+                loc,
+                component,
             }));
         } else {
             return Ok(Entry::Vacant(VacantEntry {
@@ -1113,18 +2646,277 @@ impl World {
         self.archetypes.add_subscriber(Arc::new(subscriber))
     }
 
+    /// Invokes `func` with the entity and value each time `component` is added to an entity,
+    /// including when the entity is spawned.
+    ///
+    /// This is a shorthand for the common case of reacting to a single component, such as
+    /// registering a physics body when a collider is added, without going through
+    /// [`Self::subscribe`] and a channel.
+    ///
+    /// # Reentrancy
+    ///
+    /// The observer is invoked *during* the archetype migration which adds the component, while the
+    /// world is mutably borrowed. It can therefore not access the world, and further changes
+    /// should be deferred, e.g; by recording them into a [`CommandBuffer`](crate::CommandBuffer)
+    /// behind a lock or by sending them over a channel, and applying them once the operation has
+    /// completed.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// # use std::sync::{Arc, Mutex};
+    /// component! {
+    ///     collider: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    ///
+    /// let bodies = Arc::new(Mutex::new(Vec::new()));
+    /// world.observe_added(collider(), {
+    ///     let bodies = bodies.clone();
+    ///     move |id, &radius| bodies.lock().unwrap().push((id, radius))
+    /// });
+    ///
+    /// let id = Entity::builder().set(collider(), 0.5).spawn(&mut world);
+    /// assert_eq!(*bodies.lock().unwrap(), [(id, 0.5)]);
+    /// ```
+    pub fn observe_added<T, F>(&mut self, component: Component<T>, func: F)
+    where
+        T: ComponentValue,
+        F: 'static + Send + Sync + Fn(Entity, &T),
+    {
+        self.subscribe(Observer::new(component, EventKind::Added, func))
+    }
+
+    /// Invokes `func` with the entity and value each time `component` is removed from an entity,
+    /// including when the entity is despawned.
+    ///
+    /// The value is observed before it is dropped or returned.
+    ///
+    /// The same reentrancy rules as for [`Self::observe_added`] apply.
+    pub fn observe_removed<T, F>(&mut self, component: Component<T>, func: F)
+    where
+        T: ComponentValue,
+        F: 'static + Send + Sync + Fn(Entity, &T),
+    {
+        self.subscribe(Observer::new(component, EventKind::Removed, func))
+    }
+
+    /// Invokes `func` each time a pair of `relation` is added to or removed from an entity, for
+    /// any target.
+    ///
+    /// This includes the entity being spawned or despawned, and the relation being removed when
+    /// its target is despawned. This is useful for invalidating data derived from the graph formed
+    /// by the relation, such as a dependency graph.
+    ///
+    /// The same reentrancy rules as for [`Self::observe_added`] apply.
+    ///
+    /// ```rust
+    /// # use flax::{*, relation::RelationEvent};
+    /// # use std::sync::{Arc, Mutex};
+    /// component! {
+    ///     depends_on(id): (),
+    /// }
+    ///
+    /// let mut world = World::new();
+    ///
+    /// let events = Arc::new(Mutex::new(Vec::new()));
+    /// world.observe_relations_changed(depends_on, {
+    ///     let events = events.clone();
+    ///     move |event| events.lock().unwrap().push(event)
+    /// });
+    ///
+    /// let input = world.spawn();
+    /// let output = Entity::builder()
+    ///     .set(depends_on(input), ())
+    ///     .spawn(&mut world);
+    ///
+    /// world.remove(output, depends_on(input)).unwrap();
+    ///
+    /// assert_eq!(
+    ///     *events.lock().unwrap(),
+    ///     [
+    ///         RelationEvent::Linked { subject: output, object: input },
+    ///         RelationEvent::Unlinked { subject: output, object: input },
+    ///     ]
+    /// );
+    /// ```
+    pub fn observe_relations_changed<T, F>(&mut self, relation: impl RelationExt<T>, func: F)
+    where
+        T: ComponentValue,
+        F: 'static + Send + Sync + Fn(RelationEvent),
+    {
+        self.subscribe(RelationObserver::new(relation.id(), func))
+    }
+
+    /// Maintain a reverse index of `relation`, allowing the entities which have the relation to a
+    /// target to be retrieved without searching the archetypes.
+    ///
+    /// See [`Self::incoming`].
+    ///
+    /// The index is kept up to date as the relation is added and removed, which trades memory and
+    /// a cost for each change of the relation for faster reverse traversal.
+    ///
+    /// Does nothing if the index is already enabled.
+    pub fn enable_relation_index<T: ComponentValue>(&mut self, relation: impl RelationExt<T>) {
+        let relation = relation.id();
+        if self.relation_indices.contains_key(&relation) {
+            return;
+        }
+
+        let index = Arc::new(RelationIndex::new(relation));
+        for (_, arch) in self.archetypes.iter() {
+            index.add_archetype(arch);
+        }
+
+        self.archetypes.add_subscriber(index.clone());
+        self.relation_indices.insert(relation, index);
+    }
+
+    /// Returns all entities which have `relation` to `target`.
+    ///
+    /// Returns `None` if the index for the relation is not enabled using
+    /// [`Self::enable_relation_index`].
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     child_of(parent): (),
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// world.enable_relation_index(child_of);
+    ///
+    /// let parent = world.spawn();
+    /// let child = Entity::builder()
+    ///     .set(child_of(parent), ())
+    ///     .spawn(&mut world);
+    ///
+    /// assert_eq!(&*world.incoming(child_of, parent).unwrap(), [child]);
+    /// ```
+    pub fn incoming<T: ComponentValue>(
+        &self,
+        relation: impl RelationExt<T>,
+        target: Entity,
+    ) -> Option<AtomicRef<'_, [Entity]>> {
+        let index = self.relation_indices.get(&relation.id())?;
+        Some(index.get(target))
+    }
+
+    /// Returns the difference which transforms `self` into `other` when applied.
+    ///
+    /// Only components with the [`Diffable`](crate::Diffable) metadata are compared, and entities
+    /// are matched by id. Entities without any diffable components, such as runtime-only state,
+    /// are left untouched. See [`WorldDiff`] for details.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     position: f32 => [Diffable],
+    ///     velocity: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let id = Entity::builder()
+    ///     .set(position(), 1.0)
+    ///     .set(velocity(), 2.0)
+    ///     .spawn(&mut world);
+    ///
+    /// // E.g; the same scene, reloaded from disk
+    /// let mut reloaded = World::new();
+    /// reloaded.spawn_at(id).unwrap();
+    /// reloaded.set(id, position(), 5.0).unwrap();
+    ///
+    /// let diff = world.diff(&reloaded);
+    /// world.apply_diff(diff).unwrap();
+    ///
+    /// assert_eq!(world.get_copy(id, position()), Ok(5.0));
+    /// // Runtime state is kept
+    /// assert_eq!(world.get_copy(id, velocity()), Ok(2.0));
+    /// ```
+    pub fn diff(&self, other: &World) -> WorldDiff {
+        WorldDiff::new(self, other)
+    }
+
+    /// Applies a difference computed through [`Self::diff`].
+    ///
+    /// Entities are despawned, then spawned, and lastly have their components changed and
+    /// removed.
+    pub fn apply_diff(&mut self, diff: WorldDiff) -> Result<()> {
+        diff.apply(self)
+    }
+
+    /// Applies the commands recorded in a [`CommandBuffer`](crate::CommandBuffer) to the world,
+    /// leaving the buffer empty and ready for reuse.
+    ///
+    /// This is the manual counterpart to the flush performed by a [`Schedule`](crate::Schedule)
+    /// between batches, and is used when recording commands outside of a schedule, such as in
+    /// tests or custom game loops.
+    ///
+    /// The commands are applied in the order they were recorded, with deferred functions executing
+    /// in their place among the other commands. Relations targeting an entity despawned by the
+    /// buffer are removed once all commands are applied.
+    ///
+    /// If a command fails the error is returned and the remaining commands are discarded.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// component! {
+    ///     health: f32,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let id = world.spawn();
+    ///
+    /// let mut cmd = CommandBuffer::new();
+    /// cmd.set(id, health(), 100.0).defer(move |world| {
+    ///     *world.get_mut(id, health())? -= 20.0;
+    ///     Ok(())
+    /// });
+    ///
+    /// world.flush_commands(&mut cmd).unwrap();
+    ///
+    /// assert_eq!(world.get_copy(id, health()), Ok(80.0));
+    ///
+    /// // The buffer is empty, and nothing is applied twice
+    /// world.flush_commands(&mut cmd).unwrap();
+    /// assert_eq!(world.get_copy(id, health()), Ok(80.0));
+    /// ```
+    pub fn flush_commands(&mut self, cmd: &mut crate::CommandBuffer) -> anyhow::Result<()> {
+        cmd.apply(self)
+    }
+
     /// Merges `other` into `self`.
     ///
     /// Colliding entities will be migrated to a new entity id. Static entities will not be
     /// migrated but rather appended to existing ones. This is so that e.g; a resource entity gets
     /// the union of the worlds.
     ///
-    /// Returns a map of all the entities which were remapped.
+    /// Returns a map of all the entities which were remapped. Entities which are not in the map
+    /// keep their id. Use [`MigratedEntities::get`] to fix up any ids from `other` which are held
+    /// outside of the world, such as scripting handles or a selection.
     ///
     /// `other` will be left empty
     ///
     /// **Note**: The data from `other` will all be marked as *added*
     /// as change events do not carry over.
+    ///
+    /// ```rust
+    /// # use flax::*;
+    /// let mut world = World::new();
+    /// let existing = world.spawn();
+    ///
+    /// let mut other = World::new();
+    /// let selected = other.spawn();
+    /// // Both worlds assigned the same id
+    /// assert_eq!(selected, existing);
+    ///
+    /// let migrated = world.merge_with(&mut other);
+    /// let selected = migrated.get(selected);
+    ///
+    /// assert_ne!(selected, existing);
+    /// assert!(world.is_alive(selected));
+    /// assert_eq!(migrated.into_ids().len(), 1);
+    /// ```
     pub fn merge_with(&mut self, other: &mut World) -> MigratedEntities {
         let mut archetypes = mem::replace(&mut other.archetypes, Archetypes::new());
         let mut entities = mem::take(&mut other.entities);
@@ -1308,6 +3100,11 @@ impl MigratedEntities {
     pub fn ids(&self) -> &BTreeMap<Entity, Entity> {
         &self.ids
     }
+
+    /// Consumes the migrated ids, mapping each remapped source id to its new id
+    pub fn into_ids(self) -> BTreeMap<Entity, Entity> {
+        self.ids
+    }
 }
 
 impl Default for World {
@@ -1341,6 +3138,191 @@ impl<'a> Iterator for ReservedEntityIter<'a> {
     }
 }
 
+/// A set of components which can be borrowed from a single entity at once.
+///
+/// Implemented for [`Component`], [`Mutable`], and tuples thereof.
+///
+/// See: [`World::get_many_mut`]
+pub trait ComponentRefs<'a> {
+    /// The borrowed components
+    type Output;
+
+    /// Collects the component and mutability of each borrow of the entity
+    #[doc(hidden)]
+    fn borrows(&self, id: Entity, dst: &mut Vec<(Entity, ComponentDesc, bool)>);
+
+    /// Borrow the components of the entity at `slot` in `arch`
+    #[doc(hidden)]
+    fn borrow(self, id: Entity, arch: &'a Archetype, slot: Slot, tick: u32)
+        -> Result<Self::Output>;
+}
+
+impl<'a, T: ComponentValue> ComponentRefs<'a> for Component<T> {
+    type Output = AtomicRef<'a, T>;
+
+    fn borrows(&self, id: Entity, dst: &mut Vec<(Entity, ComponentDesc, bool)>) {
+        dst.push((id, self.desc(), false))
+    }
+
+    fn borrow(self, id: Entity, arch: &'a Archetype, slot: Slot, _: u32) -> Result<Self::Output> {
+        arch.get(slot, self).ok_or_else(|| {
+            Error::MissingComponent(MissingComponent {
+                id,
+                desc: self.desc(),
+            })
+        })
+    }
+}
+
+impl<'a, T: ComponentValue> ComponentRefs<'a> for Mutable<T> {
+    type Output = RefMut<'a, T>;
+
+    fn borrows(&self, id: Entity, dst: &mut Vec<(Entity, ComponentDesc, bool)>) {
+        dst.push((id, self.0.desc(), true))
+    }
+
+    fn borrow(
+        self,
+        id: Entity,
+        arch: &'a Archetype,
+        slot: Slot,
+        tick: u32,
+    ) -> Result<Self::Output> {
+        arch.get_mut(slot, self.0, tick).ok_or_else(|| {
+            Error::MissingComponent(MissingComponent {
+                id,
+                desc: self.0.desc(),
+            })
+        })
+    }
+}
+
+/// Returns true if borrowing the component of `id` conflicts with any of `borrows`, which is the
+/// case when the same component of the same entity is borrowed mutably by either
+fn is_aliased(
+    borrows: &[(Entity, ComponentDesc, bool)],
+    id: Entity,
+    desc: ComponentDesc,
+    mutable: bool,
+) -> bool {
+    borrows.iter().any(|&(other, other_desc, other_mutable)| {
+        other == id && other_desc.key() == desc.key() && (mutable || other_mutable)
+    })
+}
+
+/// A set of components of several entities which can be borrowed at once.
+///
+/// Implemented for an [`Entity`] paired with a [`Component`] or [`Mutable`], and tuples thereof.
+///
+/// See: [`World::get_mut_many_components`]
+pub trait EntityComponentRefs<'a> {
+    /// The borrowed components
+    type Output;
+
+    /// Collects the entity, component, and mutability of each borrow
+    #[doc(hidden)]
+    fn borrows(&self, dst: &mut Vec<(Entity, ComponentDesc, bool)>);
+
+    /// Borrow the components
+    ///
+    /// # Safety
+    /// The entities must have the components, and a mutably borrowed component of an entity
+    /// must not be borrowed elsewhere.
+    #[doc(hidden)]
+    unsafe fn borrow(self, world: &'a World) -> Self::Output;
+}
+
+/// Returns a pointer to the value of a component of an entity without borrowing the storage
+///
+/// # Safety
+/// The entity must have the component
+unsafe fn component_ptr<T: ComponentValue>(
+    world: &World,
+    id: Entity,
+    component: Component<T>,
+) -> *mut T {
+    let loc = world.location(id).unwrap();
+    let cell = world
+        .archetypes
+        .get(loc.arch_id)
+        .cell(component.key())
+        .unwrap();
+
+    (*cell.data.as_ptr())
+        .storage
+        .at(loc.slot)
+        .unwrap()
+        .cast::<T>()
+        .cast_mut()
+}
+
+impl<'a, T: ComponentValue> EntityComponentRefs<'a> for (Entity, Component<T>) {
+    type Output = &'a T;
+
+    fn borrows(&self, dst: &mut Vec<(Entity, ComponentDesc, bool)>) {
+        dst.push((self.0, self.1.desc(), false))
+    }
+
+    unsafe fn borrow(self, world: &'a World) -> Self::Output {
+        &*component_ptr(world, self.0, self.1)
+    }
+}
+
+impl<'a, T: ComponentValue> EntityComponentRefs<'a> for (Entity, Mutable<T>) {
+    type Output = &'a mut T;
+
+    fn borrows(&self, dst: &mut Vec<(Entity, ComponentDesc, bool)>) {
+        dst.push((self.0, self.1 .0.desc(), true))
+    }
+
+    unsafe fn borrow(self, world: &'a World) -> Self::Output {
+        &mut *component_ptr(world, self.0, self.1 .0)
+    }
+}
+
+macro_rules! tuple_impl {
+    ($($idx: tt => $ty: ident),*) => {
+        impl<'a, $($ty: ComponentRefs<'a>,)*> ComponentRefs<'a> for ($($ty,)*) {
+            type Output = ($($ty::Output,)*);
+
+            fn borrows(&self, id: Entity, dst: &mut Vec<(Entity, ComponentDesc, bool)>) {
+                $(self.$idx.borrows(id, dst);)*
+            }
+
+            fn borrow(
+                self,
+                id: Entity,
+                arch: &'a Archetype,
+                slot: Slot,
+                tick: u32,
+            ) -> Result<Self::Output> {
+                Ok(($(self.$idx.borrow(id, arch, slot, tick)?,)*))
+            }
+        }
+
+        impl<'a, $($ty: EntityComponentRefs<'a>,)*> EntityComponentRefs<'a> for ($($ty,)*) {
+            type Output = ($($ty::Output,)*);
+
+            fn borrows(&self, dst: &mut Vec<(Entity, ComponentDesc, bool)>) {
+                $(self.$idx.borrows(dst);)*
+            }
+
+            unsafe fn borrow(self, world: &'a World) -> Self::Output {
+                ($(self.$idx.borrow(world),)*)
+            }
+        }
+    };
+}
+
+tuple_impl! { 0 => A }
+tuple_impl! { 0 => A, 1 => B }
+tuple_impl! { 0 => A, 1 => B, 2 => C }
+tuple_impl! { 0 => A, 1 => B, 2 => C, 3 => D }
+tuple_impl! { 0 => A, 1 => B, 2 => C, 3 => D, 4 => E }
+tuple_impl! { 0 => A, 1 => B, 2 => C, 3 => D, 4 => E, 5 => F }
+tuple_impl! { 0 => A, 1 => B, 2 => C, 3 => D, 4 => E, 5 => F, 6 => G }
+tuple_impl! { 0 => A, 1 => B, 2 => C, 3 => D, 4 => E, 5 => F, 6 => G, 7 => H }
+
 #[cfg(test)]
 mod tests {
 
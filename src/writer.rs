@@ -8,7 +8,7 @@ use crate::{
     buffer::ComponentBuffer,
     component::{ComponentDesc, ComponentValue},
     entity::EntityLocation,
-    metadata::exclusive,
+    metadata::{add_required, collect_required, combine, exclusive, required},
     world::update_entity_loc,
     Entity, World,
 };
@@ -16,6 +16,9 @@ use crate::{
 /// Describes a modification to the components of an entity within the context of an archetype
 pub(crate) trait ComponentUpdater {
     type Updated;
+    /// Whether an existing value is replaced, and thus recorded in the history of the component
+    const WRITES: bool = true;
+
     /// Performs write operations against the target entity
     /// # Safety
     ///
@@ -109,10 +112,27 @@ unsafe impl<W: ComponentUpdater + ComponentPusher> EntityWriter for SingleCompon
                     .update(cell.data.get_mut(), src_loc.slot, id, tick)
             };
 
+            if W::WRITES {
+                world.record_history(id, self.desc);
+            }
+
             return (src_loc, Either::Left(res));
         }
 
-        let (src, dst, dst_id) = if let Some(&dst_id) = arch.outgoing.get(&key) {
+        // The components required by the inserted component are added in the same move
+        let mut required = if self.desc.meta_ref().has(required()) {
+            collect_required(self.desc, |key| arch.has(key))
+        } else {
+            ComponentBuffer::new()
+        };
+
+        let cached = arch
+            .outgoing
+            .get(&key)
+            .filter(|_| required.is_empty())
+            .copied();
+
+        let (src, dst, dst_id) = if let Some(dst_id) = cached {
             let (src, dst) = world
                 .archetypes
                 .get_disjoint(src_loc.arch_id, dst_id)
@@ -128,11 +148,14 @@ unsafe impl<W: ComponentUpdater + ComponentPusher> EntityWriter for SingleCompon
 
             let (components, superset) = find_archetype_components(
                 arch.cells().iter().map(|v| v.desc()),
-                [self.desc],
+                core::iter::once(self.desc).chain(required.components().copied()),
                 exclusive,
             );
 
             world.init_component(self.desc);
+            for &desc in required.components() {
+                world.init_component(desc);
+            }
             let (dst_id, _) = world.archetypes.find_create(components.iter().copied());
 
             // Add a quick edge to refer to later
@@ -142,7 +165,7 @@ unsafe impl<W: ComponentUpdater + ComponentPusher> EntityWriter for SingleCompon
                 .get_disjoint(src_loc.arch_id, dst_id)
                 .unwrap();
 
-            if superset && src_loc.arch_id != reserved_id {
+            if superset && required.is_empty() && src_loc.arch_id != reserved_id {
                 src.add_outgoing(key, dst_id);
                 dst.add_incoming(key, src_loc.arch_id);
             }
@@ -163,6 +186,11 @@ unsafe impl<W: ComponentUpdater + ComponentPusher> EntityWriter for SingleCompon
             self.writer.push(data, id, tick)
         };
 
+        let recorded = World::history_components(&required);
+        for (desc, value) in required.drain() {
+            unsafe { dst.push(desc.key, value, tick) }
+        }
+
         let dst_loc = EntityLocation {
             arch_id: dst_id,
             slot: dst_slot,
@@ -170,6 +198,9 @@ unsafe impl<W: ComponentUpdater + ComponentPusher> EntityWriter for SingleCompon
 
         update_entity_loc(world, id, dst_loc, swapped);
 
+        world.record_history(id, self.desc);
+        record_history(world, id, &recorded);
+
         (dst_loc, Either::Right(pushed))
     }
 }
@@ -217,6 +248,7 @@ pub(crate) struct Missing<T: ComponentValue> {
 
 impl<T: ComponentValue> ComponentUpdater for Missing<T> {
     type Updated = ();
+    const WRITES: bool = false;
 
     unsafe fn update(self, _: &mut CellData, _: Slot, _: Entity, _: u32) {}
 }
@@ -255,6 +287,8 @@ impl<T: ComponentValue + PartialEq> ComponentUpdater for WriteDedup<T> {
             *current = self.value;
 
             data.set_modified(&[id], Slice::single(slot), tick);
+        } else {
+            data.set_touched(&[id], Slice::single(slot));
         }
     }
 }
@@ -288,6 +322,7 @@ impl ComponentUpdater for WriteDedupDyn {
 
             if (self.cmp)(self.value, dst) {
                 desc.drop(self.value);
+                data.set_touched(&[id], Slice::single(slot));
                 return;
             }
 
@@ -343,12 +378,44 @@ impl ComponentPusher for ReplaceDyn {
     }
 }
 
+/// Combines the value into the current value using the component's
+/// [`Combine`](crate::metadata::Combine) function, or inserts it if missing
+pub(crate) struct CombineDyn {
+    pub(crate) value: *mut u8,
+    pub(crate) combine: unsafe fn(*mut u8, *mut u8),
+}
+
+impl ComponentUpdater for CombineDyn {
+    type Updated = ();
+
+    unsafe fn update(self, data: &mut CellData, slot: Slot, id: Entity, tick: u32) {
+        unsafe {
+            let dst = data.storage.at_mut(slot).unwrap();
+            (self.combine)(dst, self.value);
+        }
+
+        data.set_modified(&[id], Slice::single(slot), tick);
+    }
+}
+
+impl ComponentPusher for CombineDyn {
+    type Pushed = ();
+
+    unsafe fn push(self, data: &mut CellData, id: Entity, tick: u32) {
+        let slot = data.storage.len();
+        data.storage.extend(self.value, 1);
+
+        data.set_added(&[id], Slice::single(slot), tick);
+    }
+}
+
 pub(crate) struct MissingDyn {
     pub(crate) value: *mut u8,
 }
 
 impl ComponentUpdater for MissingDyn {
     type Updated = ();
+    const WRITES: bool = false;
 
     unsafe fn update(self, data: &mut CellData, _: Slot, _: Entity, _: u32) {
         let desc = data.storage.desc();
@@ -389,6 +456,7 @@ unsafe impl<'b> EntityWriter for Buffered<'b> {
         src_loc: EntityLocation,
         tick: u32,
     ) -> (EntityLocation, ()) {
+        let mut recorded = World::history_components(self.buffer);
         let mut exclusive_relations = Vec::new();
 
         let arch = world.archetypes.get_mut(src_loc.arch_id);
@@ -401,8 +469,12 @@ unsafe impl<'b> EntityWriter for Buffered<'b> {
                     let data = cell.data.get_mut();
 
                     let dst = data.storage.at_mut(src_loc.slot).unwrap();
-                    desc.drop(dst);
-                    ptr::copy_nonoverlapping(src, dst, desc.size());
+                    if let Some(info) = desc.meta_ref().get(combine()) {
+                        (info.combine)(dst, src);
+                    } else {
+                        desc.drop(dst);
+                        ptr::copy_nonoverlapping(src, dst, desc.size());
+                    }
 
                     data.set_modified(&[id], Slice::single(src_loc.slot), tick);
                     false
@@ -424,9 +496,18 @@ unsafe impl<'b> EntityWriter for Buffered<'b> {
         }
 
         if self.buffer.is_empty() {
+            record_history(world, id, &recorded);
             return (src_loc, ());
         }
 
+        // Components which are only now added bring along their required components
+        add_required(self.buffer, |key| arch.has(key));
+        for desc in World::history_components(self.buffer) {
+            if !recorded.contains(&desc) {
+                recorded.push(desc);
+            }
+        }
+
         // Add the existing components, making sure new exclusive relations are favored
         let (components, _) = find_archetype_components(
             arch.cells().iter().map(|v| v.desc()),
@@ -462,10 +543,108 @@ unsafe impl<'b> EntityWriter for Buffered<'b> {
         update_entity_loc(world, id, dst_loc, swapped);
         // world.archetypes.prune_arch(src_loc.arch_id);
 
+        record_history(world, id, &recorded);
+
+        (dst_loc, ())
+    }
+}
+
+/// Replaces all components of an entity with the components of the buffer, such that the entity
+/// is moved at most once.
+pub(crate) struct Replaced<'b> {
+    pub(crate) buffer: &'b mut ComponentBuffer,
+}
+
+impl<'b> Replaced<'b> {
+    pub(crate) fn new(buffer: &'b mut ComponentBuffer) -> Self {
+        Self { buffer }
+    }
+}
+
+unsafe impl<'b> EntityWriter for Replaced<'b> {
+    type Output = ();
+
+    fn write(
+        self,
+        world: &mut World,
+        id: Entity,
+        src_loc: EntityLocation,
+        tick: u32,
+    ) -> (EntityLocation, ()) {
+        add_required(self.buffer, |_| false);
+
+        for &desc in self.buffer.components() {
+            world.init_component(desc);
+        }
+
+        let components = self
+            .buffer
+            .components()
+            .copied()
+            .sorted_unstable()
+            .collect_vec();
+        let (dst_id, _) = world.archetypes.find_create(components);
+
+        // The entity already has exactly the components of the buffer
+        if dst_id == src_loc.arch_id {
+            return Buffered::new(self.buffer).write(world, id, src_loc, tick);
+        }
+
+        let recorded = World::history_components(self.buffer);
+        let removed = world
+            .archetypes
+            .get(src_loc.arch_id)
+            .components_desc()
+            .filter(|v| !self.buffer.has_key(v.key()))
+            .collect_vec();
+
+        let (src, dst) = world
+            .archetypes
+            .get_disjoint(src_loc.arch_id, dst_id)
+            .unwrap();
+
+        let (dst_slot, swapped) = unsafe { src.move_to(dst, src_loc.slot, |c, ptr| c.drop(ptr)) };
+
+        for (desc, value) in self.buffer.drain() {
+            unsafe {
+                if src.has(desc.key) {
+                    // The existing value was moved along with the entity
+                    let data = dst.cell_mut(desc.key).unwrap().data.get_mut();
+                    let dst = data.storage.at_mut(dst_slot).unwrap();
+                    desc.drop(dst);
+                    ptr::copy_nonoverlapping(value, dst, desc.size());
+
+                    data.set_modified(&[id], Slice::single(dst_slot), tick);
+                } else {
+                    dst.push(desc.key, value, tick);
+                }
+            }
+        }
+
+        let dst_loc = EntityLocation {
+            arch_id: dst_id,
+            slot: dst_slot,
+        };
+
+        update_entity_loc(world, id, dst_loc, swapped);
+
+        for desc in removed {
+            world.forget_history(id, desc.key());
+        }
+
+        record_history(world, id, &recorded);
+
         (dst_loc, ())
     }
 }
 
+/// Records the values of the components which have [`History`](crate::metadata::History)
+fn record_history(world: &mut World, id: Entity, components: &[ComponentDesc]) {
+    for &desc in components {
+        world.record_history(id, desc);
+    }
+}
+
 fn find_archetype_components(
     current_components: impl IntoIterator<Item = ComponentDesc>,
     new_components: impl IntoIterator<Item = ComponentDesc>,
@@ -160,3 +160,63 @@ fn access() {
         ]
     );
 }
+
+#[test]
+fn entity_ref_access() {
+    use flax::*;
+    component! {
+        health: f32,
+        armor: f32,
+        mana: f32,
+    }
+
+    let mut world = World::new();
+
+    let knight = Entity::builder()
+        .set(name(), "knight".into())
+        .set(health(), 100.0)
+        .set(armor(), 0.5)
+        .spawn(&mut world);
+
+    let mage = Entity::builder()
+        .set(name(), "mage".into())
+        .set(mana(), 50.0)
+        .spawn(&mut world);
+
+    let damage = System::builder()
+        .with_name("damage")
+        .with_query(Query::new(health().as_mut().with_entity_ref()))
+        .for_each(|(entity, health)| *health -= 20.0 * entity.get_copy(armor()).unwrap_or(1.0))
+        .boxed();
+
+    let names = System::builder()
+        .with_name("names")
+        .with_query(Query::new(name()))
+        .for_each(|name| eprintln!("{name}"))
+        .boxed();
+
+    let regen = System::builder()
+        .with_name("regen")
+        .with_query(Query::new(mana().as_mut()))
+        .for_each(|mana| *mana += 1.0)
+        .boxed();
+
+    let repair = System::builder()
+        .with_name("repair")
+        .with_query(Query::new(armor().as_mut()))
+        .for_each(|armor| *armor = 0.5)
+        .boxed();
+
+    let mut schedule = Schedule::from([damage, names, regen, repair]);
+
+    // The entity reference reads `armor`
+    assert_eq!(
+        schedule.batch_info(&world).to_names(),
+        [&["damage", "names", "regen"][..], &["repair"]]
+    );
+
+    schedule.execute_seq(&mut world).unwrap();
+
+    assert_eq!(world.get(knight, health()).as_deref(), Ok(&90.0));
+    assert_eq!(world.get(mage, mana()).as_deref(), Ok(&51.0));
+}
@@ -31,3 +31,117 @@ fn add_remove() {
     assert_eq!(q.borrow(&world).count(), 0);
     assert_eq!(Query::new(a()).borrow(&world).count(), COUNT);
 }
+
+#[test]
+#[cfg(feature = "flume")]
+fn remove_all() {
+    use std::sync::Arc;
+
+    use flax::events::{EventKind, EventSubscriber};
+    use itertools::Itertools;
+
+    component! {
+        a: f32,
+        b: f32,
+        shared: Arc<()>,
+    }
+
+    let mut world = World::new();
+
+    let (tx, rx) = flume::unbounded();
+    world.subscribe(
+        tx.filter_components([shared().key()])
+            .filter(|kind, _| kind == EventKind::Removed),
+    );
+
+    let value = Arc::new(());
+
+    let ids = (0..10)
+        .map(|i| {
+            let mut builder = Entity::builder();
+            builder.set(a(), i as f32).set(shared(), value.clone());
+            if i % 2 == 0 {
+                builder.set(b(), 0.0);
+            }
+            builder.spawn(&mut world)
+        })
+        .collect_vec();
+
+    let other = Entity::builder().set(a(), 5.0).spawn(&mut world);
+
+    assert_eq!(Arc::strong_count(&value), 11);
+    assert_eq!(world.remove_all(shared()), 10);
+    assert_eq!(Arc::strong_count(&value), 1);
+
+    assert_eq!(
+        rx.drain().map(|v| v.id).sorted().collect_vec(),
+        ids.iter().copied().sorted().collect_vec()
+    );
+
+    assert_eq!(Query::new(shared()).borrow(&world).count(), 0);
+    assert_eq!(Query::new(b()).borrow(&world).count(), 5);
+    assert_eq!(Query::new(a()).borrow(&world).count(), 11);
+
+    for (i, &id) in ids.iter().enumerate() {
+        assert_eq!(world.get_copy(id, a()), Ok(i as f32));
+        assert!(!world.has(id, shared()));
+    }
+    assert!(world.has(other, a()));
+
+    assert_eq!(world.remove_all(shared()), 0);
+}
+
+#[test]
+fn move_component() {
+    use flax::error::MissingComponent;
+
+    component! {
+        weapon: String,
+        health: f32,
+    }
+
+    let mut world = World::new();
+
+    let player = Entity::builder().set(health(), 100.0).spawn(&mut world);
+    let enemy = Entity::builder()
+        .set(health(), 50.0)
+        .set(weapon(), "sword".into())
+        .spawn(&mut world);
+
+    let mut added = Query::new(entity_ids()).filter(weapon().added());
+    assert_eq!(added.borrow(&world).iter().collect::<Vec<_>>(), [enemy]);
+
+    world.move_component(enemy, player, weapon()).unwrap();
+
+    assert!(!world.has(enemy, weapon()));
+    assert_eq!(world.get(player, weapon()).as_deref(), Ok(&"sword".into()));
+    assert_eq!(added.borrow(&world).iter().collect::<Vec<_>>(), [player]);
+
+    // Moving to the same entity does nothing
+    world.move_component(player, player, weapon()).unwrap();
+    assert_eq!(world.get(player, weapon()).as_deref(), Ok(&"sword".into()));
+    assert_eq!(
+        world.move_component(enemy, enemy, weapon()),
+        Err(Error::MissingComponent(MissingComponent {
+            id: enemy,
+            desc: weapon().desc()
+        }))
+    );
+
+    // Neither entity is modified on failure
+    assert_eq!(
+        world.move_component(enemy, player, weapon()),
+        Err(Error::MissingComponent(MissingComponent {
+            id: enemy,
+            desc: weapon().desc()
+        }))
+    );
+    assert!(world.has(player, weapon()));
+
+    world.despawn(enemy).unwrap();
+    assert_eq!(
+        world.move_component(player, enemy, weapon()),
+        Err(Error::NoSuchEntity(enemy))
+    );
+    assert!(world.has(player, weapon()));
+}
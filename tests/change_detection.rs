@@ -275,3 +275,394 @@ fn added_opt_and() {
     assert_eq!(query.borrow(&world).iter().collect_vec(), [(&5, &2)]);
     assert_eq!(query.borrow(&world).iter().collect_vec(), []);
 }
+
+#[test]
+fn explicit_tick() {
+    use core::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    use flax::{
+        archetype::Storage,
+        events::{EventData, EventSubscriber},
+    };
+
+    component! {
+        a: i32,
+    }
+
+    struct TickSubscriber(Arc<AtomicU32>);
+
+    impl EventSubscriber for TickSubscriber {
+        fn on_added(&self, _: &Storage, _: &EventData) {}
+        fn on_modified(&self, _: &EventData) {}
+        fn on_removed(&self, _: &Storage, _: &EventData) {}
+
+        fn is_connected(&self) -> bool {
+            true
+        }
+
+        fn on_tick(&self, tick: u32) {
+            self.0.store(tick, Ordering::Relaxed);
+        }
+    }
+
+    let mut world = World::new();
+    let last_tick = Arc::new(AtomicU32::new(0));
+    world.subscribe(TickSubscriber(last_tick.clone()).filter_components([a().key()]));
+
+    let id = Entity::builder().set(a(), 1).spawn(&mut world);
+
+    let mut query = Query::new(a()).filter(a().modified());
+    assert_eq!(query.borrow(&world).iter().copied().collect_vec(), [1]);
+
+    // The tick is incremented even if not observed
+    let tick = world.tick();
+    assert_eq!(world.tick(), tick + 1);
+    assert_eq!(last_tick.load(Ordering::Relaxed), tick + 1);
+    assert_eq!(world.change_tick(), tick + 1);
+
+    assert!(query.borrow(&world).iter().next().is_none());
+
+    *world.get_mut(id, a()).unwrap() = 2;
+    assert_eq!(query.borrow(&world).iter().copied().collect_vec(), [2]);
+}
+
+#[test]
+fn component_changes() {
+    use flax::archetype::Slice;
+
+    component! {
+        a: i32,
+    }
+
+    let mut world = World::new();
+
+    let ids = (0..8)
+        .map(|i| Entity::builder().set(a(), i).spawn(&mut world))
+        .collect_vec();
+
+    let (arch_id, _) = world
+        .archetype_iter_sorted()
+        .find(|(_, arch)| arch.has(a().key()))
+        .unwrap();
+
+    // All entities were added
+    assert_eq!(
+        world.component_changes(arch_id, a().key(), 0).collect_vec(),
+        [Slice::new(0, 8)]
+    );
+
+    let tick = world.change_tick();
+
+    *world.get_mut(ids[2], a()).unwrap() = -2;
+    *world.get_mut(ids[3], a()).unwrap() = -3;
+    *world.get_mut(ids[6], a()).unwrap() = -6;
+
+    assert_eq!(
+        world
+            .component_changes(arch_id, a().key(), tick)
+            .collect_vec(),
+        [Slice::new(2, 4), Slice::new(6, 7)]
+    );
+
+    assert_eq!(
+        world
+            .component_changes(arch_id, name().key(), 0)
+            .collect_vec(),
+        []
+    );
+}
+
+#[test]
+fn entity_ref_get_mut_silent() {
+    component! {
+        a: i32,
+    }
+
+    let mut world = World::new();
+
+    let id = Entity::builder().set(a(), 5).spawn(&mut world);
+
+    let mut query = Query::new(entity_ids()).filter(a().modified());
+    assert_eq!(query.collect_vec(&world), [id]);
+
+    {
+        let entity = world.entity(id).unwrap();
+        *entity.get_mut_silent(a()).unwrap() = 7;
+    }
+
+    assert_eq!(query.collect_vec(&world), []);
+    assert_eq!(world.get_copy(id, a()), Ok(7));
+
+    *world.entity_mut(id).unwrap().get_mut_silent(a()).unwrap() = 9;
+    assert_eq!(query.collect_vec(&world), []);
+
+    *world.entity(id).unwrap().get_mut(a()).unwrap() = 10;
+    assert_eq!(query.collect_vec(&world), [id]);
+
+    assert!(world.entity(id).unwrap().get_mut_silent(name()).is_err());
+}
+
+#[test]
+fn track_changes() {
+    component! {
+        position: f32,
+        position_changed: bool,
+    }
+
+    let mut world = World::new();
+    world.track_changes(position(), position_changed());
+
+    let ids = (0..4)
+        .map(|i| {
+            Entity::builder()
+                .set(position(), i as f32)
+                .set(position_changed(), false)
+                .spawn(&mut world)
+        })
+        .collect_vec();
+
+    // Not flagged
+    let untracked = Entity::builder().set(position(), 5.0).spawn(&mut world);
+
+    let mut changed = Query::new(entity_ids()).filter(position_changed().eq(true));
+
+    assert_eq!(changed.collect_vec(&world), []);
+
+    world.tick();
+    assert_eq!(changed.collect_vec(&world), ids);
+    assert!(!world.has(untracked, position_changed()));
+
+    world.tick();
+    assert_eq!(changed.collect_vec(&world), []);
+
+    *world.get_mut(ids[2], position()).unwrap() *= 2.0;
+    *world.get_mut(ids[3], position()).unwrap() *= 2.0;
+    world.set(ids[0], position(), -1.0).unwrap();
+
+    // Not visible until the frame ends
+    assert_eq!(changed.collect_vec(&world), []);
+
+    world.tick();
+    assert_eq!(changed.collect_vec(&world), [ids[0], ids[2], ids[3]]);
+
+    world.set(ids[2], position(), 0.0).unwrap();
+    world.despawn(ids[3]).unwrap();
+
+    world.tick();
+    assert_eq!(changed.collect_vec(&world), [ids[2]]);
+
+    world.tick();
+    assert_eq!(changed.collect_vec(&world), []);
+}
+
+#[test]
+fn track_changes_unchanged_flags() {
+    component! {
+        position: f32,
+        position_changed: bool,
+    }
+
+    let mut world = World::new();
+    world.track_changes(position(), position_changed());
+
+    let id = Entity::builder()
+        .set(position(), 0.0)
+        .set(position_changed(), false)
+        .spawn(&mut world);
+
+    world.tick();
+    world.tick();
+
+    // The flag is only written when it changes
+    let mut flag_modified = Query::new(entity_ids()).filter(position_changed().modified());
+    assert_eq!(flag_modified.collect_vec(&world), [id]);
+
+    world.tick();
+    assert_eq!(flag_modified.collect_vec(&world), []);
+
+    // Modified in consecutive frames, so the flag stays set
+    *world.get_mut(id, position()).unwrap() = 1.0;
+    world.tick();
+    assert_eq!(flag_modified.collect_vec(&world), [id]);
+
+    *world.get_mut(id, position()).unwrap() = 2.0;
+    world.tick();
+    assert_eq!(world.get_copy(id, position_changed()), Ok(true));
+    assert_eq!(flag_modified.collect_vec(&world), []);
+}
+
+#[test]
+fn change_batch() {
+    use std::sync::{Arc, Mutex};
+
+    use flax::{
+        archetype::Storage,
+        events::{EventData, EventSubscriber},
+    };
+
+    component! {
+        a: i32,
+        b: (),
+    }
+
+    #[derive(Default, Clone)]
+    struct Modified(Arc<Mutex<Vec<Vec<Entity>>>>);
+
+    impl EventSubscriber for Modified {
+        fn on_added(&self, _: &Storage, _: &EventData) {}
+        fn on_modified(&self, event: &EventData) {
+            self.0.lock().unwrap().push(event.ids.to_vec());
+        }
+        fn on_removed(&self, _: &Storage, _: &EventData) {}
+
+        fn is_connected(&self) -> bool {
+            true
+        }
+    }
+
+    let mut world = World::new();
+    let modified = Modified::default();
+    world.subscribe(modified.clone().filter_components([a().key()]));
+
+    let ids = (0..64)
+        .map(|i| Entity::builder().set(a(), i).spawn(&mut world))
+        .collect_vec();
+
+    let mut query = Query::new(entity_ids()).filter(a().modified());
+    assert_eq!(query.borrow(&world).count(), 64);
+
+    world.with_change_batch(|world| {
+        for &id in &ids {
+            *world.get_mut(id, a()).unwrap() += 1;
+        }
+
+        world.despawn(ids[10]).unwrap();
+        // Moved into an archetype created during the batch
+        world.set(ids[20], b(), ()).unwrap();
+
+        world.with_change_batch(|world| {
+            *world.get_mut(ids[30], a()).unwrap() += 1;
+        });
+
+        assert!(modified.0.lock().unwrap().is_empty());
+        assert_eq!(query.borrow(world).iter().count(), 63);
+    });
+
+    let events = modified.0.lock().unwrap().clone();
+    // One event for each archetype
+    assert_eq!(events.len(), 2);
+
+    let expected = ids
+        .iter()
+        .copied()
+        .filter(|&id| id != ids[10])
+        .sorted()
+        .collect_vec();
+
+    assert_eq!(events.concat().into_iter().sorted().collect_vec(), expected);
+
+    // Events are no longer deferred
+    *world.get_mut(ids[0], a()).unwrap() += 1;
+    assert_eq!(modified.0.lock().unwrap().len(), 3);
+}
+
+#[test]
+fn change_slices() {
+    use flax::archetype::{ChangeKind, Slice};
+
+    component! {
+        a: i32,
+    }
+
+    let mut world = World::new();
+
+    let ids = (0..8)
+        .map(|i| Entity::builder().set(a(), i).spawn(&mut world))
+        .collect_vec();
+
+    let (_, arch) = world
+        .archetype_iter_sorted()
+        .find(|(_, arch)| arch.has(a().key()))
+        .unwrap();
+
+    // Observing the modifications starts tracking them
+    assert_eq!(
+        arch.changes(a().key())
+            .unwrap()
+            .modified_since(0)
+            .collect_vec(),
+        [Slice::new(0, 8)]
+    );
+
+    let tick = world.change_tick();
+
+    *world.get_mut(ids[1], a()).unwrap() += 1;
+    *world.get_mut(ids[2], a()).unwrap() += 1;
+    *world.get_mut(ids[3], a()).unwrap() += 1;
+    *world.get_mut(ids[6], a()).unwrap() += 1;
+
+    let (_, arch) = world
+        .archetype_iter_sorted()
+        .find(|(_, arch)| arch.has(a().key()))
+        .unwrap();
+
+    let changes = arch.changes(a().key()).unwrap();
+
+    assert_eq!(
+        changes.modified_since(tick).collect_vec(),
+        [Slice::new(1, 4), Slice::new(6, 7)]
+    );
+
+    // Adjacent slices are merged across ticks
+    assert_eq!(changes.modified_since(0).collect_vec(), [Slice::new(0, 8)]);
+
+    assert_eq!(changes.inserted_since(0).collect_vec(), [Slice::new(0, 8)]);
+    assert_eq!(changes.inserted_since(tick).collect_vec(), []);
+
+    let overlapping = changes
+        .get(ChangeKind::Modified)
+        .overlapping(Slice::new(3, 7))
+        .map(|v| v.slice)
+        .collect_vec();
+
+    assert_eq!(
+        overlapping,
+        [Slice::new(1, 4), Slice::new(4, 6), Slice::new(6, 7)]
+    );
+
+    assert!(arch.changes(name().key()).is_none());
+}
+
+#[test]
+fn get_mut_lazy() {
+    component! {
+        a: i32,
+    }
+
+    let mut world = World::new();
+
+    let id = Entity::builder().set(a(), 5).spawn(&mut world);
+
+    let mut query = Query::new(entity_ids()).filter(a().modified());
+    assert_eq!(query.collect_vec(&world), [id]);
+
+    let guard = world.get_mut_lazy(id, a()).unwrap();
+    assert_eq!(guard.id(), id);
+    assert_eq!(*guard.read(), 5);
+    assert_eq!(query.collect_vec(&world), []);
+
+    // The change is recorded when written to, not when the guard is created
+    *guard.write() += 1;
+    assert_eq!(query.collect_vec(&world), [id]);
+    assert_eq!(*world.get(id, a()).unwrap(), 6);
+
+    // Only borrowing for writing does not record a change
+    assert_eq!(*guard.write(), 6);
+    assert_eq!(query.collect_vec(&world), []);
+
+    assert!(world.get_mut_lazy(id, name()).is_err());
+
+    world.despawn(id).unwrap();
+    assert!(world.get_mut_lazy(id, a()).is_err());
+}
@@ -145,3 +145,145 @@ fn commandbuffer() {
 
     assert_eq!(soldiers.len(), 99);
 }
+
+#[test]
+fn commandbuffer_despawn_relations() {
+    let mut world = World::new();
+
+    let parent = world.spawn();
+    let child = Entity::builder()
+        .set(name(), "child".into())
+        .set(child_of(parent), ())
+        .spawn(&mut world);
+
+    let other = world.spawn();
+
+    let mut cmd = CommandBuffer::new();
+
+    // Relations added after the despawn are swept on apply
+    cmd.despawn(parent).set(other, child_of(parent), ()).spawn(
+        Entity::builder()
+            .set(child_of(parent), ())
+            .set(health(), 1.0),
+    );
+
+    cmd.apply(&mut world).unwrap();
+
+    assert!(!world.is_alive(parent));
+    assert!(!world.has(child, child_of(parent)));
+    assert!(!world.has(other, child_of(parent)));
+    assert_eq!(
+        Query::new(entity_ids())
+            .with_relation(child_of)
+            .borrow(&world)
+            .count(),
+        0
+    );
+    assert_eq!(Query::new(health()).borrow(&world).count(), 1);
+}
+
+#[test]
+fn double_despawn() {
+    let mut world = World::new();
+
+    let ids = (0..4)
+        .map(|i| Entity::builder().set(health(), i as f32).spawn(&mut world))
+        .collect_vec();
+
+    world.despawn(ids[0]).unwrap();
+    assert_eq!(world.despawn(ids[0]), Err(Error::NoSuchEntity(ids[0])));
+
+    // Two systems queueing a despawn of the same entity
+    let mut cmd = CommandBuffer::new();
+    cmd.despawn(ids[1]);
+    cmd.set(ids[2], health(), 10.0);
+    cmd.despawn(ids[1]);
+    cmd.despawn(ids[0]);
+
+    cmd.apply(&mut world).unwrap();
+
+    assert!(!world.is_alive(ids[1]));
+    assert_eq!(world.get_copy(ids[2], health()), Ok(10.0));
+    assert_eq!(world.get_copy(ids[3], health()), Ok(3.0));
+
+    assert_eq!(
+        Query::new(entity_ids()).collect_sorted_vec(&world),
+        [ids[2], ids[3]]
+    );
+
+    // The freed slots are reused without aliasing the remaining entities
+    let id = Entity::builder().set(health(), 5.0).spawn(&mut world);
+    assert!(!ids.contains(&id));
+    assert_eq!(world.get_copy(id, health()), Ok(5.0));
+    assert_eq!(world.get_copy(ids[3], health()), Ok(3.0));
+}
+
+#[test]
+fn for_each_with() {
+    let mut world = World::new();
+
+    let ids = (0..10)
+        .map(|i| {
+            Entity::builder()
+                .set(health(), i as f32 * 10.0)
+                .spawn(&mut world)
+        })
+        .collect_vec();
+
+    let mut cmd = CommandBuffer::new();
+    let mut query = Query::new((entity_ids(), health()));
+
+    query
+        .borrow(&world)
+        .for_each_with(&mut cmd, |cmd, (id, &health)| {
+            if health < 50.0 {
+                cmd.despawn(id);
+            } else {
+                cmd.spawn(Entity::builder().set(name(), format!("child of {id}")));
+            }
+        });
+
+    cmd.apply(&mut world).unwrap();
+
+    assert_eq!(
+        ids.iter().filter(|&&id| world.is_alive(id)).collect_vec(),
+        ids[5..].iter().collect_vec()
+    );
+
+    assert_eq!(Query::new(name()).borrow(&world).count(), 5);
+}
+
+#[test]
+fn flush_commands() {
+    let mut world = World::new();
+
+    let mut cmd = CommandBuffer::new();
+    let parent = world.spawn();
+    let shared_value = Arc::new("Foo".to_string());
+
+    cmd.spawn(Entity::builder().set(name(), "spawned".into()))
+        .set(parent, shared(), shared_value.clone())
+        .defer(move |world| {
+            // Applied in order, so the previous command has been applied
+            assert!(world.has(parent, shared()));
+            world.set(parent, health(), 50.0)?;
+            Ok(())
+        })
+        .despawn(parent);
+
+    world.flush_commands(&mut cmd).unwrap();
+
+    assert!(!world.is_alive(parent));
+    assert_eq!(Arc::strong_count(&shared_value), 1);
+    assert_eq!(
+        Query::new(name().cloned()).collect_vec(&world),
+        ["spawned".to_string()]
+    );
+
+    // The buffer is cleared and can be reused
+    world.flush_commands(&mut cmd).unwrap();
+    assert_eq!(Query::new(name()).borrow(&world).count(), 1);
+
+    cmd.despawn(parent).set(parent, health(), 1.0);
+    assert!(world.flush_commands(&mut cmd).is_err());
+}
@@ -1,5 +1,6 @@
 use flax::{
     buffer::ComponentBuffer,
+    components::name,
     error::MissingComponent,
     metadata::Metadata,
     vtable::{ComponentVTable, LazyComponentBuffer},
@@ -40,3 +41,614 @@ fn custom_component() {
         })),
     );
 }
+
+#[test]
+fn component_info() {
+    component! {
+        health: f32,
+        child_of(parent): (),
+    }
+
+    let mut world = World::new();
+
+    assert_eq!(world.component_info(health().key()), None);
+
+    let parent = world.spawn();
+    let id = Entity::builder()
+        .set(health(), 100.0)
+        .set(child_of(parent), ())
+        .spawn(&mut world);
+
+    let desc = world.component_info(health().key()).unwrap();
+    assert_eq!(desc, health().desc());
+    assert_eq!(desc.name(), "health");
+
+    // Relations are described by their target specific key
+    let key = child_of(parent).key();
+    let desc = world.component_info(key).unwrap();
+    assert_eq!(desc.key(), key);
+    assert_eq!(desc.name(), "child_of");
+
+    assert!(world.has(id, health()));
+}
+
+#[test]
+fn visit_column() {
+    component! {
+        health: i32,
+    }
+
+    let mut world = World::new();
+
+    let ids = (0..4)
+        .map(|i| Entity::builder().set(health(), i * 10).spawn(&mut world))
+        .collect::<Vec<_>>();
+
+    let (arch_id, _) = world
+        .archetype_iter_sorted()
+        .find(|(_, arch)| arch.has(health().key()))
+        .unwrap();
+
+    let mut visited = Vec::new();
+    world.visit_column(arch_id, health().key(), |id, ptr, desc| {
+        assert_eq!(desc.key(), health().key());
+        assert_eq!(desc.name(), "health");
+        // Safety: the component is an i32
+        visited.push((id, unsafe { *ptr.cast::<i32>() }));
+    });
+
+    assert_eq!(
+        visited,
+        ids.iter().copied().zip([0, 10, 20, 30]).collect::<Vec<_>>()
+    );
+
+    // Not present in the archetype
+    world.visit_column(arch_id, name().key(), |_, _, _| unreachable!());
+}
+
+#[test]
+fn archetype_graph_dot() {
+    component! {
+        health: f32,
+        position: Vec2,
+    }
+
+    let mut world = World::new();
+
+    let a = Entity::builder().set(health(), 100.0).spawn(&mut world);
+    Entity::builder().set(health(), 50.0).spawn(&mut world);
+    world.set(a, position(), vec2(1.0, 2.0)).unwrap();
+
+    let dot = world.archetype_graph_dot();
+
+    assert!(dot.starts_with("digraph archetypes {"));
+    assert!(dot.trim_end().ends_with('}'));
+    assert!(dot.contains("{health}\\n1 entities"));
+    assert!(dot.contains("{health, position}\\n1 entities"));
+    assert!(dot.contains("[label=\"position\"]"));
+    assert!(dot.contains("->"));
+}
+
+#[test]
+fn required_components() {
+    use flax::metadata::{RequiredComponents, Requires};
+
+    struct Motion;
+    impl RequiredComponents for Motion {
+        fn write(buffer: &mut ComponentBuffer) {
+            buffer.set(previous_position(), Vec2::ZERO);
+            buffer.set(velocity(), Vec2::ZERO);
+        }
+    }
+
+    struct Inertia;
+    impl RequiredComponents for Inertia {
+        fn write(buffer: &mut ComponentBuffer) {
+            buffer.set(mass(), 1.0);
+        }
+    }
+
+    component! {
+        position: Vec2 => [Requires<Motion>],
+        previous_position: Vec2,
+        velocity: Vec2 => [Requires<Inertia>],
+        mass: f32,
+    }
+
+    let mut world = World::new();
+
+    let a = Entity::builder()
+        .set(position(), vec2(1.0, 2.0))
+        .set(velocity(), vec2(0.0, 1.0))
+        .spawn(&mut world);
+
+    assert_eq!(world.get_copy(a, previous_position()), Ok(Vec2::ZERO));
+    assert_eq!(world.get_copy(a, velocity()), Ok(vec2(0.0, 1.0)));
+    assert_eq!(world.get_copy(a, mass()), Ok(1.0));
+
+    let b = Entity::builder().set(mass(), 5.0).spawn(&mut world);
+    let archetypes = world.archetype_info().len();
+
+    world.set(b, position(), vec2(3.0, 4.0)).unwrap();
+    assert_eq!(world.get_copy(b, previous_position()), Ok(Vec2::ZERO));
+    assert_eq!(world.get_copy(b, velocity()), Ok(Vec2::ZERO));
+    assert_eq!(world.get_copy(b, mass()), Ok(5.0));
+
+    // All components were added in one move, into the existing archetype of `a`
+    assert_eq!(world.archetype_info().len(), archetypes);
+
+    // Updating an existing component does not add the requirements again
+    world.remove(b, previous_position()).unwrap();
+    world.set(b, position(), vec2(5.0, 6.0)).unwrap();
+    assert!(!world.has(b, previous_position()));
+
+    let c = world.spawn();
+    let mut cmd = CommandBuffer::new();
+    cmd.set(c, velocity(), vec2(1.0, 0.0));
+    cmd.apply(&mut world).unwrap();
+
+    assert_eq!(world.get_copy(c, velocity()), Ok(vec2(1.0, 0.0)));
+    assert_eq!(world.get_copy(c, mass()), Ok(1.0));
+    assert!(!world.has(c, position()));
+
+    // The requirements are resolved by every way of inserting a component
+    let d = world.spawn();
+    assert!(world
+        .entity_mut(d)
+        .unwrap()
+        .set_missing(velocity(), Vec2::ONE));
+    assert_eq!(world.get_copy(d, mass()), Ok(1.0));
+
+    let e = world.spawn();
+    world
+        .entity_mut(e)
+        .unwrap()
+        .set_dedup(velocity(), Vec2::ONE);
+    assert_eq!(world.get_copy(e, mass()), Ok(1.0));
+
+    let f = world.spawn();
+    world
+        .entity_mut(f)
+        .unwrap()
+        .set_opt(velocity(), Some(Vec2::ONE));
+    assert_eq!(world.get_copy(f, mass()), Ok(1.0));
+
+    let g = world.spawn();
+    world.entry(g, velocity()).unwrap().or_insert(Vec2::ONE);
+    assert_eq!(world.get_copy(g, mass()), Ok(1.0));
+
+    let h = world.spawn();
+    let mut cmd = CommandBuffer::new();
+    cmd.set_missing(h, velocity(), Vec2::ONE);
+    cmd.apply(&mut world).unwrap();
+    assert_eq!(world.get_copy(h, mass()), Ok(1.0));
+}
+
+#[test]
+fn required_components_batch() {
+    use flax::metadata::{RequiredComponents, Requires};
+
+    struct Motion;
+    impl RequiredComponents for Motion {
+        fn write(buffer: &mut ComponentBuffer) {
+            buffer.set(previous_position(), Vec2::ZERO);
+            buffer.set(velocity(), Vec2::ZERO);
+        }
+    }
+
+    struct Inertia;
+    impl RequiredComponents for Inertia {
+        fn write(buffer: &mut ComponentBuffer) {
+            buffer.set(mass(), 1.0);
+        }
+    }
+
+    component! {
+        position: Vec2 => [Requires<Motion>],
+        previous_position: Vec2,
+        velocity: Vec2 => [Requires<Inertia>],
+        mass: f32,
+    }
+
+    let mut world = World::new();
+
+    let mut batch = BatchSpawn::new(3);
+    batch
+        .set(position(), (0..3).map(|i| vec2(i as f32, 0.0)))
+        .unwrap();
+    batch.set(mass(), [2.0, 3.0, 4.0]).unwrap();
+
+    let ids = batch.spawn(&mut world);
+
+    for (i, &id) in ids.iter().enumerate() {
+        assert_eq!(world.get_copy(id, position()), Ok(vec2(i as f32, 0.0)));
+        assert_eq!(world.get_copy(id, previous_position()), Ok(Vec2::ZERO));
+        assert_eq!(world.get_copy(id, velocity()), Ok(Vec2::ZERO));
+        assert_eq!(world.get_copy(id, mass()), Ok(i as f32 + 2.0));
+    }
+
+    let ids = [world.spawn(), world.spawn()];
+    world.despawn(ids[0]).unwrap();
+    world.despawn(ids[1]).unwrap();
+
+    let mut batch = BatchSpawn::new(2);
+    batch.set(velocity(), [Vec2::X, Vec2::Y]).unwrap();
+    batch.spawn_at(&mut world, &ids).unwrap();
+
+    assert_eq!(world.get_copy(ids[1], velocity()), Ok(Vec2::Y));
+    assert_eq!(world.get_copy(ids[1], mass()), Ok(1.0));
+    assert!(!world.has(ids[1], previous_position()));
+}
+
+#[test]
+fn component_history() {
+    use flax::{components::child_of, metadata::History};
+
+    component! {
+        position: Vec2 => [History<3>],
+        velocity: Vec2,
+    }
+
+    let history = |world: &World, id| {
+        world
+            .component_history(id, position())
+            .copied()
+            .collect::<Vec<_>>()
+    };
+
+    let mut world = World::new();
+
+    let id = Entity::builder()
+        .set(position(), vec2(0.0, 0.0))
+        .set(velocity(), vec2(1.0, 0.0))
+        .spawn(&mut world);
+
+    assert_eq!(history(&world, id), [vec2(0.0, 0.0)]);
+    assert_eq!(world.component_history(id, velocity()).len(), 0);
+
+    world.set(id, position(), vec2(1.0, 0.0)).unwrap();
+    world
+        .entity_mut(id)
+        .unwrap()
+        .set(position(), vec2(2.0, 0.0));
+
+    let mut cmd = CommandBuffer::new();
+    cmd.set(id, position(), vec2(3.0, 0.0));
+    cmd.apply(&mut world).unwrap();
+
+    assert_eq!(
+        history(&world, id),
+        [vec2(1.0, 0.0), vec2(2.0, 0.0), vec2(3.0, 0.0)]
+    );
+
+    // Mutations which do not go through `set` are not recorded
+    *world.get_mut(id, position()).unwrap() = vec2(4.0, 0.0);
+    assert_eq!(world.component_history(id, position()).len(), 3);
+
+    // Neither are writes which leave an existing value in place
+    assert!(!world
+        .entity_mut(id)
+        .unwrap()
+        .set_missing(position(), vec2(5.0, 0.0)));
+    assert_eq!(history(&world, id)[2], vec2(3.0, 0.0));
+
+    // All other ways of setting the component are recorded
+    world
+        .entity_mut(id)
+        .unwrap()
+        .set_dedup(position(), vec2(5.0, 0.0));
+    world.entry(id, position()).unwrap().set(vec2(6.0, 0.0));
+    assert_eq!(
+        history(&world, id),
+        [vec2(3.0, 0.0), vec2(5.0, 0.0), vec2(6.0, 0.0)]
+    );
+
+    // Removing the component discards its history
+    world.remove(id, position()).unwrap();
+    assert_eq!(history(&world, id), []);
+
+    world
+        .entry(id, position())
+        .unwrap()
+        .or_insert(vec2(7.0, 0.0));
+    assert_eq!(history(&world, id), [vec2(7.0, 0.0)]);
+
+    world.despawn(id).unwrap();
+    assert_eq!(history(&world, id), []);
+
+    // Recursively despawned entities discard their history
+    let parent = world.spawn();
+    let child = Entity::builder()
+        .set(position(), vec2(8.0, 0.0))
+        .set(child_of(parent), ())
+        .spawn(&mut world);
+
+    assert_eq!(history(&world, child), [vec2(8.0, 0.0)]);
+    world.despawn_recursive(parent, child_of).unwrap();
+    assert_eq!(history(&world, child), []);
+}
+
+#[test]
+fn component_history_drain() {
+    use flax::{filter::All, metadata::History};
+
+    component! {
+        position: Vec2 => [History<3>],
+    }
+
+    let mut world = World::new();
+
+    let ids = (0..4)
+        .map(|i| {
+            Entity::builder()
+                .set(position(), vec2(i as f32, 0.0))
+                .spawn(&mut world)
+        })
+        .collect::<Vec<_>>();
+
+    assert!(world
+        .component_history(ids[1], position())
+        .eq(&[vec2(1.0, 0.0)]));
+
+    // The whole archetype is despawned at once
+    world.drain(position(), All, |_| {});
+    assert!(ids
+        .iter()
+        .all(|&id| world.component_history(id, position()).len() == 0));
+}
+
+#[test]
+fn reserve_archetype() {
+    component! {
+        position: (f32, f32),
+        velocity: (f32, f32),
+    }
+
+    let mut world = World::new();
+
+    let arch_id = world.reserve_archetype([velocity().desc(), position().desc()], 10_000);
+
+    let capacity = |world: &World| {
+        world.archetype_info()[&arch_id]
+            .storage()
+            .iter()
+            .map(|v| v.cap())
+            .collect::<Vec<_>>()
+    };
+
+    let reserved = capacity(&world);
+    assert_eq!(reserved.len(), 2);
+    assert!(reserved.iter().all(|&v| v >= 10_000));
+
+    let ids = world.extend((0..10_000).map(|i| {
+        Entity::builder()
+            .set(position(), (i as f32, 0.0))
+            .set(velocity(), (1.0, 0.0))
+            .into()
+    }));
+
+    assert_eq!(world.archetype_info()[&arch_id].components().len(), 2);
+    assert_eq!(capacity(&world), reserved);
+    assert_eq!(world.get_copy(ids[5], position()), Ok((5.0, 0.0)));
+}
+
+#[test]
+#[cfg(feature = "flume")]
+fn combine_components() {
+    use flax::{
+        events::{Event, EventSubscriber},
+        metadata::{Combine, CombineWith, Sum},
+    };
+
+    struct Append;
+
+    impl CombineWith<Vec<String>> for Append {
+        fn combine(current: &mut Vec<String>, value: Vec<String>) {
+            current.extend(value)
+        }
+    }
+
+    component! {
+        damage: f32 => [Combine<Sum>],
+        log: Vec<String> => [Combine<Append>],
+    }
+
+    let mut world = World::new();
+
+    let (tx, rx) = flume::unbounded();
+    world.subscribe(tx.filter_components([damage().key()]));
+
+    let id = world.spawn();
+
+    assert_eq!(world.set(id, damage(), 5.0), Ok(None));
+    assert_eq!(world.set(id, damage(), 2.0), Ok(None));
+    assert_eq!(world.get_copy(id, damage()), Ok(7.0));
+
+    assert_eq!(
+        rx.drain().collect::<Vec<_>>(),
+        [
+            Event::added(id, damage().key()),
+            Event::modified(id, damage().key()),
+        ]
+    );
+
+    let mut cmd = CommandBuffer::new();
+    cmd.set(id, damage(), 1.0).set(id, damage(), 0.5);
+    cmd.set(id, log(), vec!["a".into()]);
+    cmd.set(id, log(), vec!["b".into(), "c".into()]);
+    cmd.apply(&mut world).unwrap();
+
+    assert_eq!(world.get_copy(id, damage()), Ok(8.5));
+    assert_eq!(
+        world.get_cloned(id, log()),
+        Ok(vec!["a".into(), "b".into(), "c".into()])
+    );
+
+    Entity::builder()
+        .set(damage(), 1.5)
+        .set(log(), vec!["d".into()])
+        .set(name(), "target".into())
+        .append_to(&mut world, id)
+        .unwrap();
+
+    assert_eq!(world.get_copy(id, damage()), Ok(10.0));
+    assert_eq!(world.get(id, log()).unwrap().len(), 4);
+
+    // Components without the metadata are replaced
+    world.set(id, name(), "other".into()).unwrap();
+    assert_eq!(world.get_cloned(id, name()), Ok("other".into()));
+}
+
+#[test]
+fn combine_missing_entity() {
+    use flax::{
+        metadata::{Combine, CombineWith},
+        Error,
+    };
+    use std::sync::Arc;
+
+    struct Keep;
+
+    impl CombineWith<Arc<()>> for Keep {
+        fn combine(_: &mut Arc<()>, _: Arc<()>) {}
+    }
+
+    component! {
+        shared: Arc<()> => [Combine<Keep>],
+    }
+
+    let mut world = World::new();
+    let value = Arc::new(());
+
+    let id = world.spawn();
+    world.set(id, shared(), value.clone()).unwrap();
+    world.set(id, shared(), value.clone()).unwrap();
+    assert_eq!(Arc::strong_count(&value), 2);
+
+    world.despawn(id).unwrap();
+    assert_eq!(Arc::strong_count(&value), 1);
+
+    // The value is dropped when it can not be set
+    assert_eq!(
+        world.set(id, shared(), value.clone()),
+        Err(Error::NoSuchEntity(id))
+    );
+    assert_eq!(Arc::strong_count(&value), 1);
+}
+
+#[test]
+fn component_usage() {
+    component! {
+        position: Vec2,
+        velocity: Vec2,
+        unused: (),
+    }
+
+    let mut world = World::new();
+
+    for i in 0..10 {
+        let mut builder = Entity::builder();
+        builder.set(position(), Vec2::ZERO);
+        if i % 2 == 0 {
+            builder.set(velocity(), Vec2::ONE);
+        }
+
+        builder.spawn(&mut world);
+    }
+
+    world.spawn();
+
+    let usage = |world: &World, desc: flax::component::ComponentDesc| {
+        world
+            .component_usage()
+            .into_iter()
+            .find(|v| v.0 == desc)
+            .map(|v| v.1)
+    };
+
+    assert_eq!(usage(&world, position().desc()), Some(10));
+    assert_eq!(usage(&world, velocity().desc()), Some(5));
+    assert_eq!(usage(&world, unused().desc()), None);
+
+    let id = world.spawn();
+    world.set(id, unused(), ()).unwrap();
+    world.despawn(id).unwrap();
+
+    assert_eq!(usage(&world, unused().desc()), None);
+    assert!(usage(&world, name().desc()).is_some());
+
+    let keys = world
+        .component_usage()
+        .iter()
+        .map(|v| v.0.key())
+        .collect::<Vec<_>>();
+    assert!(keys.windows(2).all(|v| v[0] < v[1]));
+}
+
+#[test]
+fn register_components() {
+    component! {
+        health: f32,
+        mana: f32,
+        stamina: f32,
+    }
+
+    let mut world = World::new();
+
+    world.register_component(stamina());
+    world.register_all([mana().desc(), health().desc()]);
+
+    // Ids are acquired in registration order
+    assert!(stamina().key() < mana().key());
+    assert!(mana().key() < health().key());
+
+    for desc in [health().desc(), mana().desc(), stamina().desc()] {
+        assert!(world.is_alive(desc.key().id()));
+        assert_eq!(
+            world.get(desc.key().id(), name()).as_deref(),
+            Ok(&desc.name().into())
+        );
+    }
+
+    // Registering again does nothing
+    world.register_component(health());
+
+    let id = Entity::builder().set(health(), 5.0).spawn(&mut world);
+    assert_eq!(world.get(id, health()).as_deref(), Ok(&5.0));
+}
+
+#[test]
+fn component_meta() {
+    use flax::metadata::{debuggable, exclusive};
+
+    component! {
+        health: f32 => [Debuggable],
+        secret: String,
+        parent_of(id): () => [Exclusive],
+    }
+
+    let mut world = World::new();
+
+    assert!(!world.component_has_meta(health().key(), debuggable()));
+
+    let parent = world.spawn();
+    Entity::builder()
+        .set(health(), 50.0)
+        .set(secret(), "hunter2".into())
+        .set(parent_of(parent), ())
+        .spawn(&mut world);
+
+    assert!(world.component_has_meta(health().key(), debuggable()));
+    assert!(!world.component_has_meta(secret().key(), debuggable()));
+
+    // Relations share the metadata regardless of the target
+    assert!(world.component_has_meta(parent_of(parent).key(), exclusive()));
+    let other = world.spawn();
+    assert!(world.component_has_meta(parent_of(other).key(), exclusive()));
+
+    assert_eq!(
+        world.component_meta(secret().key(), name()).as_deref(),
+        Ok(&"secret".to_string())
+    );
+    assert!(world.component_meta(secret().key(), debuggable()).is_err());
+}
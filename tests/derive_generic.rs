@@ -63,3 +63,72 @@ fn derive_fetch_generic() {
         })
     );
 }
+
+#[test]
+#[cfg(feature = "derive")]
+fn derive_fetch_generic_where() {
+    use flax::{Entity, FetchExt, Query, World};
+    use glam::{vec3, Vec3};
+
+    flax::component! {
+        position: Vec3 => [flax::Debuggable],
+        velocity: Vec3 => [flax::Debuggable],
+        scale: Vec3 => [flax::Debuggable],
+    }
+
+    mod inner {
+        use flax::{component::ComponentValue, Fetch, *};
+
+        #[derive(Fetch)]
+        #[fetch(item_derives = [Debug, PartialEq], transforms = [Modified])]
+        pub struct MotionQuery<V>
+        where
+            V: ComponentValue + Copy,
+        {
+            pub pos: Component<V>,
+            pub scale: Opt<Component<V>>,
+            #[fetch(ignore)]
+            pub vel: Mutable<V>,
+        }
+    }
+
+    use inner::MotionQuery;
+
+    let mut world = World::new();
+
+    let id = Entity::builder()
+        .set(position(), vec3(1.0, 0.0, 0.0))
+        .set(velocity(), vec3(0.0, 1.0, 0.0))
+        .spawn(&mut world);
+
+    let mut query = Query::new(
+        MotionQuery {
+            pos: position(),
+            scale: scale().opt(),
+            vel: velocity().as_mut(),
+        }
+        .modified(),
+    );
+
+    {
+        let mut borrow = query.borrow(&world);
+        let item = borrow.get(id).unwrap();
+        assert_eq!(item.pos, &vec3(1.0, 0.0, 0.0));
+        assert_eq!(item.scale, None);
+        assert_eq!(item.vel, &vec3(0.0, 1.0, 0.0));
+    }
+
+    assert!(query.borrow(&world).get(id).is_err());
+
+    // Modifying an ignored field does not yield the entity
+    *world.get_mut(id, velocity()).unwrap() = vec3(0.0, 2.0, 0.0);
+    assert!(query.borrow(&world).get(id).is_err());
+
+    world.set(id, scale(), vec3(2.0, 2.0, 2.0)).unwrap();
+
+    let mut borrow = query.borrow(&world);
+    let item = borrow.get(id).unwrap();
+    assert_eq!(item.pos, &vec3(1.0, 0.0, 0.0));
+    assert_eq!(item.scale, Some(&vec3(2.0, 2.0, 2.0)));
+    assert_eq!(item.vel, &vec3(0.0, 2.0, 0.0));
+}
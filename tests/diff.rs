@@ -0,0 +1,77 @@
+use flax::{component, components::name, Diffable, Entity, World};
+
+component! {
+    position: (f32, f32) => [Diffable],
+    health: f32 => [Diffable],
+    velocity: (f32, f32),
+}
+
+#[test]
+fn diff() {
+    let mut world = World::new();
+
+    let player = Entity::builder()
+        .set(position(), (0.0, 0.0))
+        .set(health(), 100.0)
+        .set(velocity(), (1.0, 0.0))
+        .spawn(&mut world);
+
+    let removed = Entity::builder()
+        .set(position(), (5.0, 5.0))
+        .spawn(&mut world);
+
+    let unchanged = Entity::builder()
+        .set(position(), (2.0, 2.0))
+        .spawn(&mut world);
+
+    // Runtime only entities are not affected
+    let particle = Entity::builder()
+        .set(velocity(), (0.0, 1.0))
+        .spawn(&mut world);
+
+    // Ids are preserved, as if loaded from the same source
+    let mut reloaded = World::new();
+    let ids = (0..5).map(|_| reloaded.spawn()).collect::<Vec<_>>();
+    assert_eq!(ids[..4], [player, removed, unchanged, particle]);
+    reloaded.despawn(removed).unwrap();
+    reloaded.despawn(particle).unwrap();
+
+    reloaded.set(player, position(), (1.0, 0.0)).unwrap();
+    reloaded.set(unchanged, position(), (2.0, 2.0)).unwrap();
+    reloaded.set(unchanged, health(), 50.0).unwrap();
+
+    let spawned = ids[4];
+    reloaded.set(spawned, position(), (3.0, 3.0)).unwrap();
+    reloaded.set(spawned, name(), "new".into()).unwrap();
+
+    let diff = world.diff(&reloaded);
+    assert_eq!(diff.despawned(), [removed]);
+    assert_eq!(diff.spawned().map(|v| v.0).collect::<Vec<_>>(), [spawned]);
+    assert_eq!(
+        diff.changed()
+            .map(|(id, v)| (id, v.components().map(|v| v.name()).collect::<Vec<_>>()))
+            .collect::<Vec<_>>(),
+        [(player, vec!["position"]), (unchanged, vec!["health"])]
+    );
+    assert_eq!(
+        diff.removed()
+            .map(|(id, v)| (id, v.iter().map(|v| v.name()).collect::<Vec<_>>()))
+            .collect::<Vec<_>>(),
+        [(player, vec!["health"])]
+    );
+
+    world.apply_diff(diff).unwrap();
+
+    assert!(!world.is_alive(removed));
+    assert_eq!(world.get_copy(player, position()), Ok((1.0, 0.0)));
+    assert!(!world.has(player, health()));
+    assert_eq!(world.get_copy(player, velocity()), Ok((1.0, 0.0)));
+    assert_eq!(world.get_copy(unchanged, health()), Ok(50.0));
+    assert_eq!(world.get_copy(particle, velocity()), Ok((0.0, 1.0)));
+
+    // Components without the metadata are not diffed
+    assert_eq!(world.get_copy(spawned, position()), Ok((3.0, 3.0)));
+    assert!(!world.has(spawned, name()));
+
+    assert!(world.diff(&reloaded).is_empty());
+}
@@ -136,3 +136,281 @@ fn entity_hierarchy() {
 
     assert_eq!(rx.drain().collect_vec(), []);
 }
+
+#[test]
+fn drain() {
+    use flax::filter::All;
+    use itertools::Itertools;
+
+    let mut world = World::new();
+
+    let events = (0..4)
+        .map(|i| Entity::builder().set(a(), i).spawn(&mut world))
+        .collect_vec();
+
+    let kept = Entity::builder()
+        .set(a(), 4)
+        .set(b(), "Kept".into())
+        .spawn(&mut world);
+
+    let partial = (5..8)
+        .map(|i| {
+            Entity::builder()
+                .set(a(), i)
+                .set(b(), "Partial".into())
+                .spawn(&mut world)
+        })
+        .collect_vec();
+
+    let mut values = Vec::new();
+    world.drain(a(), b().without(), |v| values.push(*v));
+    assert_eq!(values, [0, 1, 2, 3]);
+    assert!(events.iter().all(|&id| !world.is_alive(id)));
+
+    // Only part of the archetype is drained
+    values.clear();
+    world.drain(a(), a().gt(4), |v| values.push(*v));
+    values.sort();
+    assert_eq!(values, [5, 6, 7]);
+    assert!(partial.iter().all(|&id| !world.is_alive(id)));
+    assert_eq!(world.get(kept, a()).as_deref(), Ok(&4));
+
+    values.clear();
+    world.drain(a(), All, |v| values.push(*v));
+    assert_eq!(values, [4]);
+    assert!(!world.is_alive(kept));
+}
+
+#[test]
+fn entity_pool() {
+    use flax::components::pooled;
+
+    let mut world = World::new();
+    let mut pool = EntityPool::new(|builder: &mut EntityBuilder| {
+        builder.set(a(), 1);
+    });
+
+    let id1 = pool.acquire(&mut world);
+    let id2 = pool.acquire(&mut world);
+    assert_ne!(id1, id2);
+
+    *world.get_mut(id1, a()).unwrap() = 5;
+    world.set(id1, b(), "Foo".into()).unwrap();
+
+    pool.release(&mut world, id1).unwrap();
+    pool.release(&mut world, id2).unwrap();
+    assert_eq!(pool.len(), 2);
+    assert!(world.has(id1, pooled()));
+    assert_eq!(Query::new(a()).borrow(&world).iter().count(), 0);
+
+    // Pooled entities are not hidden from queries which ask for them
+    assert_eq!(
+        Query::new(entity_ids())
+            .with(pooled())
+            .borrow(&world)
+            .iter()
+            .count(),
+        2
+    );
+    assert_eq!(
+        Query::new(entity_ids())
+            .without(pooled())
+            .borrow(&world)
+            .iter()
+            .count(),
+        0
+    );
+
+    // Despawned entities are skipped
+    world.despawn(id2).unwrap();
+    assert_eq!(pool.acquire(&mut world), id1);
+    assert_eq!(world.get_copy(id1, a()), Ok(1));
+    assert!(!world.has(id1, b()));
+    assert!(!world.has(id1, pooled()));
+
+    let id3 = pool.acquire(&mut world);
+    assert!(pool.is_empty());
+    assert_ne!(id3, id2);
+    assert_eq!(Query::new(a()).borrow(&world).iter().count(), 2);
+
+    assert_eq!(pool.release(&mut world, id2), Err(Error::NoSuchEntity(id2)));
+
+    // Reused entities are reported as added again
+    let mut added = Query::new(entity_ids()).filter(a().added());
+    added.borrow(&world).for_each(|_| {});
+
+    pool.release(&mut world, id1).unwrap();
+    assert_eq!(pool.acquire(&mut world), id1);
+    assert_eq!(added.collect_vec(&world), [id1]);
+}
+
+#[test]
+fn get_errors() {
+    use flax::error::MissingComponent;
+
+    let mut world = World::new();
+
+    let id = Entity::builder().set(a(), 1).spawn(&mut world);
+
+    assert_eq!(world.get_copy(id, a()), Ok(1));
+    assert_eq!(
+        world.get(id, b()).as_deref(),
+        Err(&Error::MissingComponent(MissingComponent {
+            id,
+            desc: b().desc()
+        }))
+    );
+    assert!(matches!(
+        world.get_mut(id, b()),
+        Err(Error::MissingComponent(_))
+    ));
+
+    world.despawn(id).unwrap();
+
+    assert_eq!(world.get_copy(id, a()), Err(Error::NoSuchEntity(id)));
+    assert!(matches!(
+        world.get_mut(id, a()),
+        Err(Error::NoSuchEntity(v)) if v == id
+    ));
+}
+
+#[test]
+fn world_with_capacity() {
+    let mut world = World::with_capacity(1024);
+
+    let ids = (0..1024)
+        .map(|i| Entity::builder().set(a(), i).spawn(&mut world))
+        .collect::<Vec<_>>();
+
+    let empty = world.spawn_many().take(16).collect::<Vec<_>>();
+
+    assert_eq!(world.get_copy(ids[512], a()), Ok(512));
+    assert!(empty
+        .iter()
+        .all(|&id| world.is_alive(id) && !world.has(id, a())));
+}
+
+#[test]
+fn get_by_location() {
+    let mut world = World::new();
+
+    let ids = (0..4)
+        .map(|i| Entity::builder().set(a(), i).spawn(&mut world))
+        .collect::<Vec<_>>();
+
+    let locs = ids
+        .iter()
+        .map(|&id| world.location(id).unwrap())
+        .collect::<Vec<_>>();
+
+    assert!(locs.iter().all(|v| v.arch_id() == locs[0].arch_id()));
+
+    let values = locs
+        .iter()
+        .map(|&loc| *world.get_by_location(loc, a()).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(values, [0, 1, 2, 3]);
+    assert!(world.get_by_location(locs[0], b()).is_none());
+
+    world.despawn(ids[3]).unwrap();
+    assert!(world.get_by_location(locs[3], a()).is_none());
+}
+
+#[test]
+fn retain() {
+    use itertools::Itertools;
+
+    let mut world = World::new();
+
+    let plain = (0..4)
+        .map(|i| Entity::builder().set(a(), i).spawn(&mut world))
+        .collect_vec();
+
+    let named = (4..8)
+        .map(|i| {
+            Entity::builder()
+                .set(a(), i)
+                .set(b(), "Named".into())
+                .spawn(&mut world)
+        })
+        .collect_vec();
+
+    // Only entities matching the filter are considered
+    world.retain(b().with(), |entity| {
+        entity.get(a()).is_ok_and(|v| *v % 2 == 0)
+    });
+
+    assert_eq!(
+        named.iter().map(|&id| world.is_alive(id)).collect_vec(),
+        [true, false, true, false]
+    );
+    assert!(plain.iter().all(|&id| world.is_alive(id)));
+
+    // The whole archetype is despawned
+    world.retain(b().without(), |_| false);
+    assert!(plain.iter().all(|&id| !world.is_alive(id)));
+
+    assert_eq!(
+        Query::new(a())
+            .borrow(&world)
+            .iter()
+            .copied()
+            .sorted()
+            .collect_vec(),
+        [4, 6]
+    );
+}
+
+#[test]
+fn despawn_if() {
+    let mut world = World::new();
+
+    let id = Entity::builder()
+        .set(a(), 5)
+        .set(b(), "Foo".into())
+        .spawn(&mut world);
+    let other = Entity::builder().set(a(), 7).spawn(&mut world);
+
+    let mut visited = 0;
+    assert!(!world.despawn_if(id, |entity| {
+        visited += 1;
+        entity.get(a()).is_ok_and(|v| *v > 5)
+    }));
+    assert_eq!(visited, 1);
+    assert!(world.is_alive(id));
+
+    assert!(world.despawn_if(id, |entity| entity.has(b())));
+    assert!(!world.is_alive(id));
+    assert_eq!(world.get(other, a()).as_deref(), Ok(&7));
+
+    // The predicate is not invoked for dead entities
+    assert!(!world.despawn_if(id, |_| unreachable!()));
+}
+
+#[test]
+fn recycle_policy() {
+    use flax::entity::{EntityKind, RecyclePolicy};
+
+    let mut world = World::new();
+
+    let a = world.spawn();
+    world.despawn(a).unwrap();
+    let b = world.spawn();
+    assert_eq!(b.index(), a.index());
+    assert_ne!(b, a);
+    assert!(!world.is_alive(a));
+
+    world.set_recycle_policy(EntityKind::empty(), RecyclePolicy::Fixed);
+    world.despawn(b).unwrap();
+    let c = world.spawn();
+    assert_eq!(c, b);
+    assert!(world.is_alive(b));
+
+    world.set_recycle_policy(EntityKind::empty(), RecyclePolicy::NeverReuse);
+    world.despawn(c).unwrap();
+    let ids = (0..3).map(|_| world.spawn()).collect::<Vec<_>>();
+    assert!(ids.iter().all(|v| v.index() > c.index()));
+    assert!(!world.is_alive(c));
+    assert!(ids.windows(2).all(|v| v[0].index() < v[1].index()));
+}
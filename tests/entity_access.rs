@@ -34,3 +34,242 @@ fn entity_access() {
     let mut query = entity.query(query);
     assert_eq!(query.get(), Some(("a".into(), &6)));
 }
+
+#[test]
+fn world_get_owned() {
+    use flax::{error::MissingComponent, Error};
+
+    component! {
+        a: i32,
+        b: String,
+    }
+
+    let mut world = World::new();
+
+    let id = Entity::builder()
+        .set(a(), 5)
+        .set(b(), "Foo".into())
+        .spawn(&mut world);
+
+    let value = world.get_copy(id, a()).unwrap();
+    // No borrow is held
+    *world.get_mut(id, a()).unwrap() += value;
+    assert_eq!(world.get_copy(id, a()), Ok(10));
+
+    assert_eq!(world.get_cloned(id, b()), Ok("Foo".into()));
+    assert_eq!(
+        world.get_cloned(id, name()),
+        Err(Error::MissingComponent(MissingComponent {
+            id,
+            desc: name().desc()
+        }))
+    );
+}
+
+#[test]
+fn world_get_many_mut() {
+    use flax::{error::MissingComponent, Error};
+
+    component! {
+        position: (f32, f32),
+        velocity: (f32, f32),
+    }
+
+    let mut world = World::new();
+
+    let id = Entity::builder()
+        .set(name(), "a".into())
+        .set(position(), (0.0, 0.0))
+        .set(velocity(), (1.0, 2.0))
+        .spawn(&mut world);
+
+    {
+        let (mut pos, vel, name) = world
+            .get_many_mut(id, (position().as_mut(), velocity(), name()))
+            .unwrap();
+
+        assert_eq!(&*name, "a");
+        pos.0 += vel.0;
+        pos.1 += vel.1;
+    }
+
+    assert_eq!(world.get_copy(id, position()), Ok((1.0, 2.0)));
+
+    {
+        let (mut pos, mut vel) = world
+            .get_many_mut(id, (position().as_mut(), velocity().as_mut()))
+            .unwrap();
+
+        *vel = (0.0, 0.0);
+        pos.0 += 1.0;
+    }
+
+    assert_eq!(world.get_copy(id, position()), Ok((2.0, 2.0)));
+    assert_eq!(world.get_copy(id, velocity()), Ok((0.0, 0.0)));
+
+    world.remove(id, velocity()).unwrap();
+
+    assert_eq!(
+        world
+            .get_many_mut(id, (position().as_mut(), velocity().as_mut()))
+            .err(),
+        Some(Error::MissingComponent(MissingComponent {
+            id,
+            desc: velocity().desc()
+        }))
+    );
+}
+
+#[test]
+fn world_get_many_mut_aliased() {
+    use flax::{error::MissingComponent, Error, Query};
+
+    component! {
+        a: i32,
+        b: i32,
+        c: i32,
+        d: i32,
+        e: i32,
+        f: i32,
+        g: i32,
+        h: i32,
+    }
+
+    let mut world = World::new();
+
+    let id = Entity::builder()
+        .set(a(), 1)
+        .set(b(), 2)
+        .set(c(), 3)
+        .set(d(), 4)
+        .set(e(), 5)
+        .set(f(), 6)
+        .set(g(), 7)
+        .set(h(), 8)
+        .spawn(&mut world);
+
+    assert_eq!(
+        world.get_many_mut(id, (a().as_mut(), b(), a())).err(),
+        Some(Error::AliasedBorrow(id, a().desc()))
+    );
+
+    assert_eq!(
+        world.get_many_mut(id, (a(), b(), a().as_mut())).err(),
+        Some(Error::AliasedBorrow(id, a().desc()))
+    );
+
+    // Shared borrows of the same component do not alias
+    {
+        let (a1, a2) = world.get_many_mut(id, (a(), a())).unwrap();
+        assert_eq!((*a1, *a2), (1, 1));
+    }
+
+    {
+        let (mut a, b, c, d, e, f, g, h) = world
+            .get_many_mut(
+                id,
+                (a().as_mut(), b(), c(), d(), e(), f(), g(), h().as_mut()),
+            )
+            .unwrap();
+
+        *a = *b + *c + *d + *e + *f + *g + *h;
+    }
+
+    assert_eq!(world.get_copy(id, a()), Ok(35));
+
+    // Nothing is modified if a later component is missing
+    let mut modified = Query::new(flax::entity_ids()).filter(a().modified());
+    modified.collect_vec(&world);
+
+    component! {
+        missing: i32,
+    }
+
+    assert_eq!(
+        world.get_many_mut(id, (a().as_mut(), missing())).err(),
+        Some(Error::MissingComponent(MissingComponent {
+            id,
+            desc: missing().desc()
+        }))
+    );
+
+    assert!(modified.collect_vec(&world).is_empty());
+}
+
+#[test]
+fn world_get_mut_many_components() {
+    use flax::{error::MissingComponent, Error, Query};
+
+    component! {
+        gold: u32,
+        items: Vec<&'static str>,
+    }
+
+    let mut world = World::new();
+
+    let a = Entity::builder()
+        .set(gold(), 100)
+        .set(items(), vec!["shield"])
+        .spawn(&mut world);
+    let b = Entity::builder()
+        .set(gold(), 10)
+        .set(items(), vec!["sword"])
+        .spawn(&mut world);
+    let c = Entity::builder().set(gold(), 5).spawn(&mut world);
+
+    let mut modified = Query::new(flax::entity_ids()).filter(gold().modified());
+    modified.collect_vec(&world);
+
+    {
+        // Both entities share the archetype, and thus the same storage
+        let (a_gold, b_gold, a_items, b_items) = world
+            .get_mut_many_components((
+                (a, gold().as_mut()),
+                (b, gold().as_mut()),
+                (a, items()),
+                (b, items().as_mut()),
+            ))
+            .unwrap();
+
+        *a_gold -= 40;
+        *b_gold += 40;
+        b_items.extend(a_items.iter().copied());
+    }
+
+    assert_eq!(world.get_copy(a, gold()), Ok(60));
+    assert_eq!(world.get_copy(b, gold()), Ok(50));
+    assert_eq!(world.get_cloned(b, items()), Ok(vec!["sword", "shield"]));
+    assert_eq!(modified.collect_vec(&world), [a, b]);
+
+    // Nothing is borrowed or modified on failure
+    assert_eq!(
+        world
+            .get_mut_many_components(((a, gold().as_mut()), (c, items().as_mut())))
+            .err(),
+        Some(Error::MissingComponent(MissingComponent {
+            id: c,
+            desc: items().desc()
+        }))
+    );
+
+    assert_eq!(
+        world
+            .get_mut_many_components(((a, gold().as_mut()), (b, gold()), (a, gold())))
+            .err(),
+        Some(Error::AliasedBorrow(a, gold().desc()))
+    );
+
+    assert!(modified.collect_vec(&world).is_empty());
+
+    // Shared borrows may alias
+    let (x, y) = world
+        .get_mut_many_components(((c, gold()), (c, gold())))
+        .unwrap();
+    assert_eq!((*x, *y), (5, 5));
+
+    world.despawn(c).unwrap();
+    assert_eq!(
+        world.get_mut_many_components(((c, gold()),)).err(),
+        Some(Error::NoSuchEntity(c))
+    );
+}
@@ -1,6 +1,9 @@
 extern crate alloc;
 use alloc::string::String;
-use flax::{component, error::MissingComponent, CommandBuffer, Entity, Error, Exclusive, World};
+use flax::{
+    component, entity_ids, error::MissingComponent, CommandBuffer, Entity, Error, Exclusive, Query,
+    World,
+};
 use std::sync::Arc;
 
 component! {
@@ -156,3 +159,186 @@ fn test_append() {
     assert!(!world.has(id3, relation(id2)));
     assert!(world.has(id3, relation(id1)));
 }
+
+#[test]
+fn bundles() {
+    use flax::buffer::{Bundle, ComponentBuffer};
+
+    struct Pair {
+        a: i32,
+        b: String,
+    }
+
+    impl Bundle for Pair {
+        fn write(self, buffer: &mut ComponentBuffer) {
+            ((a(), self.a), (b(), self.b)).write(buffer)
+        }
+    }
+
+    let mut world = World::new();
+
+    let id = Entity::builder()
+        .set_bundle(Pair {
+            a: 5,
+            b: "Foo".into(),
+        })
+        .spawn(&mut world);
+
+    assert_eq!(world.get(id, a()).as_deref(), Ok(&5));
+    assert_eq!(world.get(id, b()).as_deref(), Ok(&"Foo".into()));
+
+    let id2 = world.spawn();
+    world
+        .insert_bundle(id2, ((a(), 7), (b(), String::from("Bar"))))
+        .unwrap();
+
+    assert_eq!(world.get(id2, a()).as_deref(), Ok(&7));
+    assert_eq!(world.get(id2, b()).as_deref(), Ok(&"Bar".into()));
+
+    world.despawn(id2).unwrap();
+    assert_eq!(
+        world.insert_bundle(id2, (a(), 1)),
+        Err(Error::NoSuchEntity(id2))
+    );
+}
+
+#[test]
+fn world_extend() {
+    use flax::components::child_of;
+
+    let mut world = World::new();
+
+    let mut child = Entity::builder();
+    child.set(a(), 4);
+
+    let ids = world.extend([
+        Entity::builder().set(a(), 1).into(),
+        Entity::builder().set(a(), 2).set(b(), "two".into()).into(),
+        Entity::builder().set(a(), 3).attach(child_of, child).into(),
+        Entity::builder().set(b(), "four".into()).into(),
+    ]);
+
+    assert_eq!(ids.len(), 4);
+    assert_eq!(world.get(ids[0], a()).as_deref(), Ok(&1));
+    assert!(!world.has(ids[0], b()));
+    assert_eq!(world.get(ids[1], a()).as_deref(), Ok(&2));
+    assert_eq!(world.get(ids[1], b()).as_deref(), Ok(&"two".into()));
+    assert_eq!(world.get(ids[2], a()).as_deref(), Ok(&3));
+    assert_eq!(world.get(ids[3], b()).as_deref(), Ok(&"four".into()));
+    assert!(!world.has(ids[3], a()));
+
+    let children = Query::new(entity_ids())
+        .with(child_of(ids[2]))
+        .collect_vec(&world);
+    assert_eq!(children.len(), 1);
+    assert_eq!(world.get(children[0], a()).as_deref(), Ok(&4));
+}
+
+#[test]
+fn spawn_batch_with() {
+    use flax::BatchSpawn;
+
+    let mut world = World::new();
+
+    let mut batch = BatchSpawn::new(4);
+    batch.set(a(), [0, 1, 2, 3]).unwrap();
+
+    let mut visited = Vec::new();
+    let ids = world.spawn_batch_with(&mut batch, |world, idx, ids| {
+        visited.push(idx);
+        let next = ids[(idx + 1) % ids.len()];
+        world.set(ids[idx], relation(next), Arc::new(())).unwrap();
+    });
+
+    assert_eq!(visited, [0, 1, 2, 3]);
+    assert_eq!(ids.len(), 4);
+
+    for (idx, &id) in ids.iter().enumerate() {
+        assert_eq!(world.get(id, a()).as_deref(), Ok(&(idx as i32)));
+        assert!(world.has(id, relation(ids[(idx + 1) % 4])));
+    }
+
+    assert!(world
+        .spawn_batch_with(&mut BatchSpawn::new(0), |_, _, _| unreachable!())
+        .is_empty());
+}
+
+#[test]
+fn entity_builder_buffer() {
+    let mut world = World::new();
+
+    let mut builder = Entity::builder();
+    builder.set(a(), 1).set(b(), "prefab".into());
+
+    let names = builder
+        .buffer()
+        .components()
+        .map(|v| v.name())
+        .collect::<Vec<_>>();
+
+    assert_eq!(names, ["a", "b"]);
+    assert_eq!(builder.buffer().get(a()), Some(&1));
+
+    *builder.buffer_mut().get_mut(a()).unwrap() += 1;
+    builder.buffer_mut().remove(b());
+
+    let id = builder.spawn(&mut world);
+    assert_eq!(world.get(id, a()).as_deref(), Ok(&2));
+    assert!(!world.has(id, b()));
+}
+
+#[test]
+fn entity_builder_from() {
+    use flax::{components::child_of, Cloneable};
+
+    component! {
+        health: f32 => [Cloneable],
+        tags: Vec<String> => [Cloneable],
+        owned: Arc<()>,
+    }
+
+    let mut world = World::new();
+
+    let parent = world.spawn();
+    let value = Arc::new(());
+
+    let template = Entity::builder()
+        .set(health(), 100.0)
+        .set(tags(), vec!["enemy".into()])
+        .set(owned(), value.clone())
+        .set(child_of(parent), ())
+        .spawn(&mut world);
+
+    let mut builder = world.entity_builder_from(template).unwrap();
+    builder
+        .buffer_mut()
+        .get_mut(tags())
+        .unwrap()
+        .push("elite".into());
+
+    let variant = builder.set(health(), 200.0).spawn(&mut world);
+
+    assert_eq!(world.get_copy(variant, health()), Ok(200.0));
+    assert_eq!(
+        world.get_cloned(variant, tags()),
+        Ok(vec!["enemy".to_string(), "elite".to_string()])
+    );
+    assert!(!world.has(variant, owned()));
+    assert_eq!(Arc::strong_count(&value), 2);
+
+    // `child_of` is not cloneable
+    assert!(!world.has(variant, child_of(parent)));
+
+    // The template is untouched
+    assert_eq!(world.get_copy(template, health()), Ok(100.0));
+    assert_eq!(
+        world.get_cloned(template, tags()),
+        Ok(vec!["enemy".to_string()])
+    );
+
+    world.despawn(template).unwrap();
+    assert_eq!(
+        world.entity_builder_from(template).err(),
+        Some(Error::NoSuchEntity(template))
+    );
+}
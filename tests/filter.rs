@@ -358,3 +358,315 @@ fn entity_filter() {
 
     assert_eq!(query.borrow(&world).iter().sorted().collect_vec(), expected);
 }
+
+#[test]
+fn take() {
+    let mut world = World::new();
+
+    let ids_a = (0..3)
+        .map(|i| Entity::builder().set(a(), i as f32).spawn(&mut world))
+        .collect_vec();
+
+    let ids_b = (0..3)
+        .map(|i| {
+            Entity::builder()
+                .set(a(), i as f32)
+                .set(b(), "b".into())
+                .spawn(&mut world)
+        })
+        .collect_vec();
+
+    let mut query = Query::new(entity_ids()).take(4);
+
+    let expected = ids_a.iter().chain(&ids_b[..1]).copied().collect_vec();
+    assert_eq!(query.borrow(&world).iter().collect_vec(), expected);
+
+    // The limit applies to each borrow
+    assert_eq!(query.borrow(&world).iter().collect_vec(), expected);
+
+    let mut items = Vec::new();
+    query.borrow(&world).for_each(|id| items.push(id));
+    assert_eq!(items, expected);
+
+    let mut query = Query::new(a().as_mut()).with(b()).take(2);
+    for _ in 0..2 {
+        query.borrow(&world).for_each(|v| *v += 10.0);
+    }
+
+    assert_eq!(
+        ids_b
+            .iter()
+            .map(|&id| world.get_copy(id, a()).unwrap())
+            .collect_vec(),
+        [20.0, 21.0, 2.0]
+    );
+
+    assert_eq!(Query::new(a()).take(0).borrow(&world).count(), 0);
+}
+
+#[test]
+fn take_per_borrow() {
+    let mut world = World::new();
+
+    let ids = (0..4)
+        .map(|i| Entity::builder().set(a(), i as f32).spawn(&mut world))
+        .collect_vec();
+
+    let mut query = Query::new(entity_ids()).take(2);
+
+    // Random access and iteration share the budget of the borrow
+    let mut borrow = query.borrow(&world);
+    assert_eq!(borrow.get(ids[0]), Ok(ids[0]));
+    assert_eq!(borrow.iter().count(), 1);
+    drop(borrow);
+
+    // Borrowing again within the same change tick restores the budget
+    assert_eq!(query.borrow(&world).iter().collect_vec(), ids[..2]);
+    assert_eq!(query.borrow(&world).iter().collect_vec(), ids[..2]);
+
+    // Counting is independent of the budget used by previous borrows
+    assert_eq!(query.count_filtered(&world), 2);
+    assert_eq!(query.count_filtered(&world), 2);
+    assert_eq!(query.borrow(&world).iter().collect_vec(), ids[..2]);
+
+    // The budget is restored when nested inside other fetches
+    let mut query = Query::new(entity_ids().filtered(filter::Take::new(2)).with_id().opt());
+    assert_eq!(query.borrow(&world).iter().flatten().count(), 2);
+    assert_eq!(query.borrow(&world).iter().flatten().count(), 2);
+
+    // Archetypes beyond the budget are not borrowed
+    let other = Entity::builder()
+        .set(a(), 4.0)
+        .set(b(), "other".into())
+        .spawn(&mut world);
+
+    let mut query = Query::new(a().as_mut()).take(1);
+    let value = world.get(other, a()).unwrap();
+    assert_eq!(query.borrow(&world).iter().count(), 1);
+    assert_eq!(*value, 4.0);
+}
+
+#[test]
+fn ancestor_has() {
+    use flax::{components::child_of, filter::ancestor_has};
+
+    component! {
+        hidden: (),
+        linked(id): (),
+    }
+
+    let mut world = World::new();
+
+    let root = Entity::builder().tag(hidden()).spawn(&mut world);
+    let child = Entity::builder().set(child_of(root), ()).spawn(&mut world);
+    let grandchild = Entity::builder()
+        .tag(hidden())
+        .set(child_of(child), ())
+        .spawn(&mut world);
+    let leaf = Entity::builder()
+        .set(child_of(grandchild), ())
+        .spawn(&mut world);
+    let other = Entity::builder().spawn(&mut world);
+
+    let mut hidden_query = Query::new(entity_ids()).filter(ancestor_has(child_of, hidden()));
+    let mut culled = Query::new(entity_ids())
+        .filter(ancestor_has(child_of, hidden()) | hidden().with())
+        .without_relation(linked);
+
+    assert_eq!(
+        hidden_query.collect_sorted_vec(&world),
+        [child, grandchild, leaf]
+    );
+
+    world.remove(root, hidden()).unwrap();
+
+    assert_eq!(hidden_query.collect_sorted_vec(&world), [leaf]);
+    assert_eq!(culled.collect_sorted_vec(&world), [grandchild, leaf]);
+
+    let mut visible = Query::new(entity_ids()).filter(!ancestor_has(child_of, hidden()));
+    assert_eq!(
+        visible.collect_sorted_vec(&world),
+        [root, child, grandchild, other]
+    );
+
+    // Cycles terminate
+    world.set(other, linked(leaf), ()).unwrap();
+    world.set(leaf, linked(other), ()).unwrap();
+
+    assert!(Query::new(entity_ids())
+        .filter(ancestor_has(linked, hidden()))
+        .collect_vec(&world)
+        .is_empty());
+
+    world.set(other, hidden(), ()).unwrap();
+
+    assert_eq!(
+        Query::new(entity_ids())
+            .filter(ancestor_has(linked, hidden()))
+            .collect_sorted_vec(&world),
+        [leaf, other]
+    );
+}
+
+#[test]
+fn static_filters() {
+    use flax::filter::{All, Nothing};
+
+    let mut world = World::new();
+
+    let ids = (0..4)
+        .map(|i| {
+            let mut builder = EntityBuilder::new();
+            builder.set(a(), i as f32);
+            if i % 2 == 0 {
+                builder.set(d(), "even");
+            }
+            builder.spawn(&mut world)
+        })
+        .collect_vec();
+
+    assert!(Query::new(entity_ids())
+        .filter(Nothing)
+        .collect_vec(&world)
+        .is_empty());
+
+    assert!(Query::new(entity_ids())
+        .with(a())
+        .filter(false)
+        .collect_vec(&world)
+        .is_empty());
+
+    assert_eq!(
+        Query::new(entity_ids())
+            .with(a())
+            .filter(true)
+            .collect_sorted_vec(&world),
+        ids
+    );
+
+    assert_eq!(
+        Query::new(entity_ids())
+            .with(a())
+            .filter(All)
+            .without(d())
+            .collect_sorted_vec(&world),
+        [ids[1], ids[3]]
+    );
+
+    // Only the filter itself is unsatisfiable
+    assert_eq!(
+        Query::new(entity_ids())
+            .with(a())
+            .filter(!Nothing)
+            .collect_sorted_vec(&world),
+        ids
+    );
+
+    assert_eq!(
+        Query::new(entity_ids())
+            .filter(Nothing | d().with())
+            .collect_sorted_vec(&world),
+        [ids[0], ids[2]]
+    );
+}
+
+#[test]
+fn any_modified() {
+    component! {
+        position: f32,
+        rotation: f32,
+        scale: f32,
+    }
+
+    let mut world = World::new();
+
+    let mut batch = BatchSpawn::new(64);
+    batch.set(position(), (0..64).map(|v| v as f32)).unwrap();
+    batch.set(rotation(), (0..64).map(|v| v as f32)).unwrap();
+    let ids = batch.spawn(&mut world);
+
+    let scaled = (0..8)
+        .map(|_| Entity::builder().set(scale(), 1.0).spawn(&mut world))
+        .collect_vec();
+
+    let mut query = Query::new(entity_ids()).filter(filter::any_modified([
+        position().desc(),
+        rotation().desc(),
+        scale().desc(),
+    ]));
+
+    let mut composed = Query::new(entity_ids())
+        .filter(position().modified() | rotation().modified() | scale().modified());
+
+    let all = ids.iter().chain(&scaled).copied().collect_vec();
+    assert_eq!(query.collect_vec(&world), all);
+    assert_eq!(composed.collect_vec(&world), all);
+
+    assert!(query.collect_vec(&world).is_empty());
+    assert!(composed.collect_vec(&world).is_empty());
+
+    // Overlapping and adjacent changes in different components
+    for &slot in &[3, 4, 10, 11, 12] {
+        *world.get_mut(ids[slot], position()).unwrap() += 1.0;
+    }
+
+    for &slot in &[4, 5, 6, 12, 40, 63] {
+        *world.get_mut(ids[slot], rotation()).unwrap() += 1.0;
+    }
+
+    *world.get_mut(scaled[2], scale()).unwrap() = 2.0;
+
+    let expected = [3, 4, 5, 6, 10, 11, 12, 40, 63]
+        .iter()
+        .map(|&v| ids[v])
+        .chain([scaled[2]])
+        .collect_vec();
+
+    assert_eq!(query.collect_vec(&world), expected);
+    assert_eq!(composed.collect_vec(&world), expected);
+}
+
+#[test]
+fn in_archetypes() {
+    use flax::filter::in_archetypes;
+
+    let mut world = World::new();
+
+    let id1 = Entity::builder().set(a(), 1.0).spawn(&mut world);
+    let id2 = Entity::builder()
+        .set(a(), 2.0)
+        .set(b(), "b".into())
+        .spawn(&mut world);
+    let id3 = Entity::builder().set(b(), "b".into()).spawn(&mut world);
+
+    let snapshot = [id1, id2]
+        .iter()
+        .map(|&id| world.location(id).unwrap().arch_id())
+        .collect_vec();
+
+    let mut query = Query::new((entity_ids(), a().copied())).filter(in_archetypes(snapshot));
+    assert_eq!(query.collect_vec(&world), [(id1, 1.0), (id2, 2.0)]);
+
+    // A new archetype is not part of the snapshot
+    Entity::builder()
+        .set(a(), 4.0)
+        .set(d(), "d")
+        .spawn(&mut world);
+    assert_eq!(query.collect_vec(&world), [(id1, 1.0), (id2, 2.0)]);
+
+    // Moves into and out of the snapshot
+    world.set(id3, a(), 3.0).unwrap();
+    world.remove(id1, a()).unwrap();
+    assert_eq!(query.collect_vec(&world), [(id2, 2.0), (id3, 3.0)]);
+
+    // Pruning the now empty archetype of `id2` invalidates its id
+    world.despawn(id2).unwrap();
+    world.despawn(id3).unwrap();
+    world.prune_archetypes();
+
+    Entity::builder()
+        .set(a(), 5.0)
+        .set(b(), "b".into())
+        .spawn(&mut world);
+    assert!(query.collect_vec(&world).is_empty());
+}
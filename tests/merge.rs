@@ -294,3 +294,44 @@ fn merge_custom() {
 
     pretty_assertions::assert_eq!(custom_children, ["child_custom.1"]);
 }
+
+#[test]
+fn merge_remap_handles() {
+    let mut world = World::new();
+    let existing = (0..4)
+        .map(|i| {
+            Entity::builder()
+                .set(name(), format!("existing.{i}"))
+                .spawn(&mut world)
+        })
+        .collect_vec();
+
+    let mut src_world = World::new();
+    let handles = (0..8)
+        .map(|i| {
+            Entity::builder()
+                .set(name(), format!("src.{i}"))
+                .spawn(&mut src_world)
+        })
+        .collect_vec();
+
+    let migrated = world.merge_with(&mut src_world);
+
+    let handles = handles.iter().map(|&id| migrated.get(id)).collect_vec();
+
+    for (i, &id) in handles.iter().enumerate() {
+        assert_eq!(world.get(id, name()).as_deref(), Ok(&format!("src.{i}")));
+    }
+
+    for (i, &id) in existing.iter().enumerate() {
+        assert_eq!(
+            world.get(id, name()).as_deref(),
+            Ok(&format!("existing.{i}"))
+        );
+    }
+
+    let ids = migrated.into_ids();
+    // Ids colliding with existing entities are remapped
+    assert!(existing.iter().all(|id| ids.contains_key(id)));
+    assert!(ids.values().all(|v| !existing.contains(v)));
+}
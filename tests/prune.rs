@@ -57,3 +57,54 @@ fn prune_archetypes() {
     assert_eq!(world.prune_archetypes(), 2);
     assert_eq!(world.prune_archetypes(), 0);
 }
+
+#[test]
+fn compact() {
+    component! {
+        position: (f32, f32),
+        health: f32,
+    }
+
+    let mut world = World::new();
+
+    let ids = (0..64)
+        .map(|i| {
+            Entity::builder()
+                .set(position(), (i as f32, 0.0))
+                .set(health(), 100.0)
+                .spawn(&mut world)
+        })
+        .collect::<Vec<_>>();
+
+    for &id in &ids[4..] {
+        world.despawn(id).unwrap();
+    }
+
+    let info = world.compact();
+    assert!(info.released() > 0);
+    assert!(info.smallest() > 0);
+    assert!(info.archetypes() > 0);
+    assert!(info.entities() >= 4);
+
+    let info = world.compact();
+    assert_eq!(info.pruned(), 0);
+    assert_eq!(info.released(), 0);
+
+    for (i, &id) in ids[..4].iter().enumerate() {
+        assert_eq!(*world.get(id, position()).unwrap(), (i as f32, 0.0));
+    }
+
+    // The archetype can still grow after being shrunk
+    let id = Entity::builder()
+        .set(position(), (5.0, 0.0))
+        .set(health(), 50.0)
+        .spawn(&mut world);
+
+    assert_eq!(*world.get(id, health()).unwrap(), 50.0);
+    assert_eq!(*world.get(ids[3], position()).unwrap(), (3.0, 0.0));
+
+    world.despawn_many(position().with());
+    // Both `position` and `position, health` are now empty
+    let info = world.compact();
+    assert_eq!(info.pruned(), 2);
+}
@@ -1,4 +1,4 @@
-use flax::{component, EntityBuilder, FetchExt, Query, World};
+use flax::{component, entity_ids, EntityBuilder, FetchExt, Query, World};
 use itertools::Itertools;
 
 use flax::components::name;
@@ -135,3 +135,1535 @@ fn query_opt() {
         ]
     );
 }
+
+#[test]
+fn query_combinations() {
+    component! {
+        pos: f32,
+        flag: (),
+    }
+
+    let mut world = World::new();
+
+    for (i, name_str) in ["A", "B", "C", "D"].iter().enumerate() {
+        EntityBuilder::new()
+            .set(name(), name_str.to_string())
+            .set(pos(), i as f32)
+            .spawn(&mut world);
+    }
+
+    EntityBuilder::new()
+        .set(name(), "E".into())
+        .set(pos(), 4.0)
+        .tag(flag())
+        .spawn(&mut world);
+
+    let mut query = Query::new((name(), pos())).without(flag());
+
+    let pairs = query
+        .borrow(&world)
+        .combinations::<2>()
+        .map(|[(a, _), (b, _)]| format!("{a}{b}"))
+        .sorted()
+        .collect_vec();
+
+    assert_eq!(pairs, ["AB", "AC", "AD", "BC", "BD", "CD"]);
+
+    let triples = Query::new(name())
+        .borrow(&world)
+        .combinations::<3>()
+        .count();
+
+    assert_eq!(triples, 10);
+
+    assert_eq!(query.borrow(&world).combinations::<5>().count(), 0);
+
+    // Pull all entities towards each other
+    let mut query = Query::new(pos().as_mut());
+    query.borrow(&world).for_each_combination(|[a, b]| {
+        let mid = (**a + **b) / 2.0;
+        **a += (mid - **a) * 0.5;
+        **b += (mid - **b) * 0.5;
+    });
+
+    let total: f32 = Query::new(pos()).borrow(&world).iter().sum();
+    assert!((total - 10.0).abs() < 1e-4);
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn query_par_fold() {
+    use flax::components::child_of;
+
+    component! {
+        mass: f32,
+    }
+
+    let mut world = World::new();
+
+    for i in 0..1024 {
+        let mut builder = EntityBuilder::new();
+        builder.set(mass(), 0.1 * i as f32);
+
+        if i % 8 == 0 {
+            builder.set_default(child_of(world.spawn()));
+        }
+
+        builder.spawn(&mut world);
+    }
+
+    let mut query = Query::new(mass());
+
+    let sequential = query
+        .borrow(&world)
+        .iter_batched()
+        .map(|batch| batch.fold(0.0, |acc, v| acc + *v))
+        .fold(0.0, |acc, v| acc + v);
+
+    for _ in 0..8 {
+        let total = query
+            .borrow(&world)
+            .par_fold(|| 0.0f32, |acc, v| acc + *v, |a, b| a + b);
+
+        assert_eq!(total.to_bits(), sequential.to_bits());
+    }
+
+    let count = query
+        .borrow(&world)
+        .par_fold(|| 0, |acc, _| acc + 1, |a, b| a + b);
+    assert_eq!(count, 1024);
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn query_par_prepare() {
+    use flax::components::child_of;
+
+    component! {
+        value: i32,
+    }
+
+    let mut world = World::new();
+
+    let ids = (0..64)
+        .map(|i| {
+            let parent = world.spawn();
+            EntityBuilder::new()
+                .set(value(), i)
+                .set_default(child_of(parent))
+                .spawn(&mut world)
+        })
+        .collect_vec();
+
+    let mut query = Query::new(value().as_mut());
+    let mut borrow = query.borrow(&world);
+    borrow.par_prepare();
+
+    for value in borrow.iter() {
+        *value *= 2;
+    }
+
+    assert_eq!(borrow.get(ids[5]).copied(), Ok(10));
+    drop(borrow);
+
+    assert_eq!(
+        Query::new(value())
+            .borrow(&world)
+            .iter()
+            .copied()
+            .sum::<i32>(),
+        (0..64).sum::<i32>() * 2
+    );
+}
+
+#[test]
+fn query_try_get() {
+    component! {
+        health: f32,
+        armor: f32,
+    }
+
+    let mut world = World::new();
+
+    let a = EntityBuilder::new()
+        .set(health(), 50.0)
+        .set(armor(), 5.0)
+        .spawn(&mut world);
+
+    let b = EntityBuilder::new().set(health(), 80.0).spawn(&mut world);
+    let c = EntityBuilder::new()
+        .set(health(), 10.0)
+        .set(armor(), 1.0)
+        .spawn(&mut world);
+
+    world.despawn(c).unwrap();
+
+    let mut query = Query::new((health(), armor())).filter(health().gt(20.0));
+    let mut borrow = query.borrow(&world);
+
+    assert_eq!(borrow.try_get(a), Some((&50.0, &5.0)));
+    // Missing component
+    assert_eq!(borrow.try_get(b), None);
+    // Despawned
+    assert_eq!(borrow.try_get(c), None);
+    drop(borrow);
+
+    world.set(a, health(), 5.0).unwrap();
+    // Filtered
+    assert_eq!(query.borrow(&world).try_get(a), None);
+}
+
+#[test]
+fn query_get_matched() {
+    component! {
+        position: (i32, i32),
+        neighbors: Vec<flax::Entity>,
+    }
+
+    let mut world = World::new();
+
+    let ids = (0..4)
+        .map(|i| {
+            EntityBuilder::new()
+                .set(position(), (i, 0))
+                .spawn(&mut world)
+        })
+        .collect::<Vec<_>>();
+
+    for (i, &id) in ids.iter().enumerate() {
+        let adjacent = ids
+            .iter()
+            .copied()
+            .filter(|&v| v != id && v != ids[(i + 2) % 4])
+            .collect();
+
+        world.set(id, neighbors(), adjacent).unwrap();
+    }
+
+    let removed = ids[3];
+    world.despawn(removed).unwrap();
+
+    let mut query = Query::new(position().copied());
+    let mut borrow = query.borrow(&world);
+
+    let adjacent = world.get(ids[0], neighbors()).unwrap().clone();
+    let positions = adjacent
+        .iter()
+        .map(|&id| borrow.get_matched(id))
+        .collect::<Vec<_>>();
+
+    assert_eq!(positions, [Some((1, 0)), None]);
+
+    let mut query = Query::new(position().copied()).filter(position().gt((1, 0)));
+    let mut borrow = query.borrow(&world);
+    // Per-entity filters are still evaluated
+    assert_eq!(borrow.get_matched(ids[1]), None);
+    assert_eq!(borrow.get_matched(ids[2]), Some((2, 0)));
+}
+
+#[test]
+fn query_get_matched_unmatched() {
+    use flax::components::child_of;
+
+    component! {
+        position: i32,
+    }
+
+    let mut world = World::new();
+
+    let parent = world.spawn();
+    let id1 = EntityBuilder::new()
+        .set(position(), 1)
+        .set(child_of(parent), ())
+        .spawn(&mut world);
+    let id2 = EntityBuilder::new().set(position(), 2).spawn(&mut world);
+
+    let mut query = Query::new(position().copied()).with_relation(child_of);
+    let mut borrow = query.borrow(&world);
+
+    // Archetypes not matched by the query are neither yielded nor cached for iteration
+    assert_eq!(borrow.get_matched(id2), None);
+    assert_eq!(borrow.get_matched(id1), Some(1));
+    assert_eq!(borrow.iter().collect::<Vec<_>>(), [1]);
+}
+
+#[test]
+fn query_any() {
+    use flax::{
+        entity_ids,
+        fetch::{any, AnyItem},
+        Entity,
+    };
+
+    component! {
+        sprite: String,
+        mesh: u32,
+    }
+
+    let mut world = World::new();
+
+    let id1 = Entity::builder()
+        .set(sprite(), "a.png".into())
+        .spawn(&mut world);
+    let id2 = Entity::builder().set(mesh(), 1).spawn(&mut world);
+    let id3 = Entity::builder()
+        .set(mesh(), 2)
+        .set(sprite(), "b.png".into())
+        .spawn(&mut world);
+    // Matches neither
+    Entity::builder()
+        .set(name(), "empty".into())
+        .spawn(&mut world);
+
+    let mut query = Query::new(any((mesh().as_mut(), sprite())));
+    for item in &mut query.borrow(&world) {
+        if let AnyItem::A(mesh) = item {
+            *mesh *= 10;
+        }
+    }
+
+    // The first alternative is used if both are present
+    let items = Query::new((entity_ids(), any((mesh(), sprite()))))
+        .borrow(&world)
+        .iter()
+        .map(|(id, item)| match item {
+            AnyItem::A(mesh) => (id, Ok(*mesh)),
+            AnyItem::B(sprite) => (id, Err(sprite.clone())),
+        })
+        .sorted_by_key(|v| v.0)
+        .collect_vec();
+
+    assert_eq!(
+        items,
+        [(id1, Err("a.png".into())), (id2, Ok(10)), (id3, Ok(20)),]
+    );
+}
+
+#[test]
+fn query_any_prepare_fallback() {
+    use flax::{
+        fetch::{any, AnyItem},
+        filter::Take,
+        Entity,
+    };
+
+    component! {
+        sprite: String,
+        mesh: u32,
+    }
+
+    let mut world = World::new();
+
+    Entity::builder()
+        .set(mesh(), 1)
+        .set(sprite(), "a.png".into())
+        .spawn(&mut world);
+
+    // The first alternative matches the archetype, but fails to prepare
+    let mut query = Query::new(any((mesh().filtered(Take::new(0)), sprite())));
+
+    let items = query
+        .borrow(&world)
+        .iter()
+        .map(|item| match item {
+            AnyItem::A(mesh) => Ok(*mesh),
+            AnyItem::B(sprite) => Err(sprite.clone()),
+        })
+        .collect_vec();
+
+    assert_eq!(items, [Err("a.png".into())]);
+}
+
+#[test]
+fn query_densify() {
+    use flax::{entity_ids, fetch::densify, CommandBuffer, Entity};
+
+    component! {
+        health: f32,
+    }
+
+    let mut world = World::new();
+
+    let id1 = Entity::builder().set(health(), 50.0).spawn(&mut world);
+    let id2 = Entity::builder().set(name(), "b".into()).spawn(&mut world);
+    let id3 = Entity::builder().set(name(), "c".into()).spawn(&mut world);
+
+    let dense = densify(health(), 100.0);
+    let mut query = Query::new((entity_ids(), dense.clone()));
+
+    let items = query
+        .borrow(&world)
+        .iter()
+        .map(|(id, &v)| (id, v))
+        .sorted_by_key(|v| v.0)
+        .collect_vec();
+
+    assert_eq!(items, [(id1, 50.0), (id2, 100.0), (id3, 100.0)]);
+
+    // Assigned before the buffer is applied
+    world.set(id3, health(), 25.0).unwrap();
+
+    let mut cmd = CommandBuffer::new();
+    dense.flush(&mut cmd);
+    cmd.apply(&mut world).unwrap();
+
+    let items = Query::new((entity_ids(), health()))
+        .borrow(&world)
+        .iter()
+        .map(|(id, &v)| (id, v))
+        .sorted_by_key(|v| v.0)
+        .collect_vec();
+
+    assert_eq!(items, [(id1, 50.0), (id2, 100.0), (id3, 25.0)]);
+}
+
+#[test]
+fn query_densify_filter() {
+    use flax::{entity_ids, fetch::densify, CommandBuffer, Entity};
+
+    component! {
+        health: f32,
+    }
+
+    let mut world = World::new();
+
+    let id1 = Entity::builder().set(health(), 50.0).spawn(&mut world);
+    let id2 = Entity::builder().set(name(), "b".into()).spawn(&mut world);
+
+    let dense = densify(health(), 100.0);
+
+    // Entities which are only compared are recorded as well
+    let mut query = Query::new(entity_ids()).filter(dense.clone().lt(75.0));
+    assert_eq!(query.borrow(&world).iter().collect_vec(), [id1]);
+    assert_eq!(query.borrow(&world).iter().collect_vec(), [id1]);
+
+    let mut cmd = CommandBuffer::new();
+    dense.flush(&mut cmd);
+    cmd.apply(&mut world).unwrap();
+
+    assert_eq!(world.get_copy(id2, health()), Ok(100.0));
+
+    // The recorded entities are cleared by the flush
+    world.remove(id2, health()).unwrap();
+    dense.flush(&mut cmd);
+    cmd.apply(&mut world).unwrap();
+
+    assert!(!world.has(id2, health()));
+}
+
+#[test]
+fn query_densify_per_item() {
+    use flax::{entity_ids, fetch::densify, CommandBuffer, Entity, Schedule, System};
+
+    component! {
+        health: f32,
+    }
+
+    let mut world = World::new();
+
+    let ids = (0..4)
+        .map(|i| {
+            Entity::builder()
+                .set(name(), i.to_string())
+                .spawn(&mut world)
+        })
+        .collect_vec();
+
+    let dense = densify(health(), 100.0);
+    let mut query = Query::new((entity_ids(), dense.clone()));
+    let mut other = Query::new(dense.clone());
+
+    // Two live preparations share the recorded entities
+    let mut borrow = query.borrow(&world);
+    let mut other_borrow = other.borrow(&world);
+
+    // Only the fetched items are recorded
+    assert_eq!(borrow.iter().next(), Some((ids[0], &100.0)));
+    assert_eq!(other_borrow.iter().take(2).count(), 2);
+    drop((borrow, other_borrow));
+
+    let mut cmd = CommandBuffer::new();
+    dense.flush(&mut cmd);
+    cmd.apply(&mut world).unwrap();
+
+    assert!(world.has(ids[0], health()));
+    assert!(world.has(ids[1], health()));
+    assert!(!world.has(ids[2], health()));
+    assert!(!world.has(ids[3], health()));
+
+    let mut schedule = Schedule::builder()
+        .with_system(
+            System::builder()
+                .with_query(Query::new(dense.clone()))
+                .for_each(|_| {}),
+        )
+        .with_system(dense.flush_system())
+        .build();
+
+    schedule.execute_seq(&mut world).unwrap();
+
+    assert!(ids
+        .iter()
+        .all(|&id| world.get_copy(id, health()) == Ok(100.0)));
+}
+
+#[test]
+fn query_count_filtered() {
+    use flax::Entity;
+
+    component! {
+        health: f32,
+        pos: (f32, f32),
+    }
+
+    let mut world = World::new();
+
+    for i in 0..10 {
+        Entity::builder()
+            .set(health(), i as f32 * 10.0)
+            .set(pos(), (i as f32, 0.0))
+            .spawn(&mut world);
+    }
+
+    Entity::builder().set(health(), 100.0).spawn(&mut world);
+
+    let mut query = Query::new(pos().as_mut()).filter(health().gt(35.0));
+
+    // The fetched components are not borrowed
+    let mut positions = Query::new(pos().as_mut());
+    let mut positions = positions.borrow(&world);
+    positions.for_each(|v| v.1 = 1.0);
+
+    assert_eq!(query.count_filtered(&world), 6);
+    drop(positions);
+
+    assert_eq!(query.borrow(&world).count(), 6);
+
+    // New archetypes are picked up
+    EntityBuilder::new()
+        .set(name(), "new".into())
+        .set(health(), 50.0)
+        .set(pos(), (0.0, 0.0))
+        .spawn(&mut world);
+    assert_eq!(query.count_filtered(&world), 7);
+    assert_eq!(Query::new(pos()).count_filtered(&world), 11);
+}
+
+#[test]
+fn query_dedup_by() {
+    use flax::{entity_ids, Entity};
+
+    component! {
+        position: (f32, f32),
+        scale: f32,
+        world_matrix: (f32, f32),
+    }
+
+    let mut world = World::new();
+
+    let [a, b, c] = [1.0, 2.0, 3.0].map(|v| {
+        Entity::builder()
+            .set(position(), (v, v))
+            .set(scale(), v)
+            .set_default(world_matrix())
+            .spawn(&mut world)
+    });
+
+    let mut query = Query::new((entity_ids(), world_matrix().as_mut()))
+        .filter(position().modified() | scale().modified())
+        .dedup_by((position().copied(), scale().copied()));
+
+    let mut update = |world: &World| {
+        query
+            .borrow(world)
+            .iter()
+            .map(|(id, _)| id)
+            .sorted()
+            .collect_vec()
+    };
+
+    assert_eq!(update(&world), [a, b, c]);
+    assert_eq!(update(&world), []);
+
+    // Spurious modifications
+    world.set(a, position(), (1.0, 1.0)).unwrap();
+    world.set(b, scale(), 2.0).unwrap();
+    world.set(c, scale(), 5.0).unwrap();
+    assert_eq!(update(&world), [c]);
+
+    // Moving to another archetype keeps the last seen key
+    world.set(a, name(), "a".into()).unwrap();
+    world.set(a, scale(), 1.0).unwrap();
+    world.set(b, position(), (0.0, 2.0)).unwrap();
+    assert_eq!(update(&world), [b]);
+
+    let d = Entity::builder()
+        .set(position(), (4.0, 4.0))
+        .set(scale(), 4.0)
+        .set_default(world_matrix())
+        .spawn(&mut world);
+    assert_eq!(update(&world), [d]);
+}
+
+#[test]
+fn query_dedup_by_unyielded() {
+    use flax::{entity_ids, Entity};
+    use std::sync::Arc;
+
+    component! {
+        position: i32,
+        enabled: bool,
+        shared: Arc<()>,
+    }
+
+    let mut world = World::new();
+
+    let id = Entity::builder()
+        .set(position(), 1)
+        .set(enabled(), false)
+        .spawn(&mut world);
+
+    let mut query = Query::new(entity_ids())
+        .filter(enabled().eq(true))
+        .dedup_by(position().copied());
+
+    assert_eq!(query.collect_vec(&world), []);
+
+    // The key is only recorded for yielded entities
+    world.set(id, enabled(), true).unwrap();
+    assert_eq!(query.collect_vec(&world), [id]);
+    assert_eq!(query.collect_vec(&world), []);
+
+    // Entities rejected by subsequent filters or only counted are not recorded either
+    let mut query = Query::new(entity_ids())
+        .dedup_by(position().copied())
+        .filter(enabled().eq(false));
+
+    assert_eq!(query.collect_vec(&world), []);
+    assert_eq!(query.count_filtered(&world), 0);
+
+    world.set(id, enabled(), false).unwrap();
+    assert_eq!(query.count_filtered(&world), 1);
+    assert_eq!(query.collect_vec(&world), [id]);
+    assert_eq!(query.collect_vec(&world), []);
+
+    // Keys of despawned entities are eventually removed
+    let value = Arc::new(());
+    let mut query = Query::new(entity_ids()).dedup_by(shared().cloned());
+
+    let ids = (0..100)
+        .map(|_| {
+            Entity::builder()
+                .set(shared(), value.clone())
+                .spawn(&mut world)
+        })
+        .collect_vec();
+
+    assert_eq!(query.collect_vec(&world).len(), 100);
+    assert_eq!(Arc::strong_count(&value), 201);
+
+    for id in ids {
+        world.despawn(id).unwrap();
+    }
+
+    assert_eq!(Arc::strong_count(&value), 101);
+
+    let id = Entity::builder()
+        .set(shared(), value.clone())
+        .spawn(&mut world);
+    assert_eq!(query.collect_vec(&world), [id]);
+    assert_eq!(Arc::strong_count(&value), 3);
+}
+
+#[test]
+fn query_bucketed() {
+    use flax::{entity_ids, Bucketed, Entity};
+
+    component! {
+        cell: i32,
+        value: i32,
+        marker: (),
+    }
+
+    let mut world = World::new();
+
+    let mut spawn = |c, v, tagged: bool| {
+        let mut builder = Entity::builder();
+        builder.set(cell(), c).set(value(), v);
+        if tagged {
+            builder.set(marker(), ());
+        }
+        builder.spawn(&mut world)
+    };
+
+    let a = spawn(2, 0, false);
+    let b = spawn(0, 1, true);
+    let c = spawn(1, 2, false);
+    let d = spawn(0, 3, false);
+    let e = spawn(2, 4, true);
+
+    // Not visited as it has no key
+    Entity::builder().set(value(), 5).spawn(&mut world);
+
+    let mut query = Query::new((entity_ids(), value().as_mut()))
+        .with_strategy(Bucketed::new(cell(), |&v: &i32| v));
+
+    let items = query
+        .borrow(&world)
+        .iter()
+        .map(|(id, v)| {
+            *v *= 10;
+            id
+        })
+        .collect_vec();
+
+    // Within a bucket entities are visited in archetype order
+    assert_eq!(items, [d, b, c, a, e]);
+
+    // The buckets are recomputed when the key changes
+    world.set(a, cell(), -1).unwrap();
+
+    let items = query
+        .borrow(&world)
+        .iter()
+        .map(|(id, &mut v)| (id, v))
+        .collect_vec();
+
+    assert_eq!(items, [(a, 0), (d, 30), (b, 10), (c, 20), (e, 40)]);
+
+    let mut query = Query::new(entity_ids())
+        .with_strategy(Bucketed::new(cell(), |&v: &i32| core::cmp::Reverse(v)))
+        .with(marker());
+
+    assert_eq!(query.borrow(&world).iter().collect_vec(), [e, b]);
+
+    // Entities rejected by the key are not visited
+    let mut query =
+        Query::new(entity_ids()).with_strategy(Bucketed::new(cell().ge(0), |&v: &i32| v));
+
+    assert_eq!(query.borrow(&world).iter().collect_vec(), [d, b, c, e]);
+}
+
+#[test]
+fn query_borrow_entities() {
+    use flax::{entity_ids, Entity};
+
+    component! {
+        health: f32,
+        pos: (f32, f32),
+    }
+
+    let mut world = World::new();
+
+    let ids = (0..10)
+        .map(|i| {
+            Entity::builder()
+                .set(health(), i as f32 * 10.0)
+                .set(pos(), (i as f32, 0.0))
+                .spawn(&mut world)
+        })
+        .collect_vec();
+
+    Entity::builder().set(health(), 100.0).spawn(&mut world);
+
+    let mut query = Query::new(pos().as_mut()).filter(health().gt(35.0));
+
+    // The fetched components are not borrowed
+    let mut positions = Query::new(pos().as_mut());
+    let mut positions = positions.borrow(&world);
+    positions.iter().for_each(|v| v.1 = 1.0);
+
+    assert_eq!(
+        query
+            .borrow(&world)
+            .entities()
+            .into_iter()
+            .sorted()
+            .collect_vec(),
+        ids[4..]
+    );
+
+    drop(positions);
+
+    let mut query = Query::new(entity_ids()).filter(health().modified());
+    assert_eq!(query.borrow(&world).entities().len(), 11);
+
+    world.set(ids[2], health(), 0.0).unwrap();
+    assert_eq!(query.borrow(&world).entities(), [ids[2]]);
+}
+
+#[test]
+fn query_inspect() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    component! {
+        health: f32,
+    }
+
+    let mut world = World::new();
+    let ids = (0..4)
+        .map(|i| {
+            EntityBuilder::new()
+                .set(health(), i as f32 * 10.0)
+                .spawn(&mut world)
+        })
+        .collect_vec();
+
+    let seen = Arc::new(AtomicUsize::new(0));
+
+    let mut query = Query::new(health().copied().inspect({
+        let seen = seen.clone();
+        move |_| {
+            seen.fetch_add(1, Ordering::Relaxed);
+        }
+    }));
+
+    assert_eq!(query.collect_vec(&world), [0.0, 10.0, 20.0, 30.0]);
+    assert_eq!(seen.load(Ordering::Relaxed), 4);
+
+    assert_eq!(query.borrow(&world).get(ids[2]), Ok(20.0));
+    assert_eq!(seen.load(Ordering::Relaxed), 5);
+
+    let mut query = Query::new(health().as_mut().inspect({
+        let seen = seen.clone();
+        move |v| {
+            assert!(**v < 40.0);
+            seen.fetch_add(1, Ordering::Relaxed);
+        }
+    }));
+
+    for v in &mut query.borrow(&world) {
+        *v += 5.0;
+    }
+
+    assert_eq!(seen.load(Ordering::Relaxed), 9);
+    assert_eq!(world.get_copy(ids[3], health()), Ok(35.0));
+}
+
+#[test]
+fn query_world_ref() {
+    use flax::{
+        fetch::world_ref,
+        system::{Access, AccessKind, System},
+        Entity, Schedule,
+    };
+
+    component! {
+        health: f32,
+        max_health: f32,
+        owner(id): (),
+    }
+
+    let mut world = World::new();
+
+    let player = Entity::builder()
+        .set(health(), 50.0)
+        .set(max_health(), 100.0)
+        .spawn(&mut world);
+
+    let pets = (0..2)
+        .map(|_| {
+            Entity::builder()
+                .set(health(), 10.0)
+                .set(owner(player), ())
+                .spawn(&mut world)
+        })
+        .collect_vec();
+
+    // Pets heal up to half of the owner's maximum health
+    let mut query = Query::new((health().as_mut(), world_ref())).with_relation(owner);
+
+    for (health, world) in &mut query.borrow(&world) {
+        let target = world.get_copy(player, max_health()).unwrap();
+        *health = target / 2.0;
+    }
+
+    assert_eq!(world.get_copy(pets[0], health()), Ok(50.0));
+    assert_eq!(world.get_copy(pets[1], health()), Ok(50.0));
+
+    let mut query = Query::new((max_health(), world_ref()));
+    let mut borrow = query.borrow(&world);
+    let (&max, w) = borrow.get(player).unwrap();
+    assert_eq!(max, 100.0);
+    assert_eq!(w.get_copy(player, max_health()), Ok(100.0));
+    drop(borrow);
+
+    let schedule = Schedule::builder()
+        .with_system(
+            System::builder()
+                .with_query(Query::new((health().as_mut(), world_ref())))
+                .for_each(|_| {}),
+        )
+        .build();
+
+    let desc = &schedule.describe(&world)[0];
+    assert!(desc.access().contains(&Access {
+        kind: AccessKind::World,
+        mutable: false
+    }));
+    assert!(!desc.is_exclusive());
+}
+
+#[test]
+fn query_chunks_mut() {
+    use flax::{entity_ids, Entity};
+
+    component! {
+        position: f32,
+        velocity: f32,
+        frozen: (),
+    }
+
+    let mut world = World::new();
+
+    let ids = (0..16)
+        .map(|i| {
+            let mut builder = Entity::builder();
+            builder.set(position(), 0.0).set(velocity(), i as f32);
+
+            if i % 4 == 0 {
+                builder.set(frozen(), ());
+            }
+
+            builder.spawn(&mut world)
+        })
+        .collect_vec();
+
+    let mut query = Query::new((entity_ids(), position().as_mut(), velocity())).without(frozen());
+
+    let mut visited = Vec::new();
+    for (ids, pos, vel) in query.borrow(&world).chunks_mut() {
+        assert_eq!(ids.len(), pos.len());
+        assert_eq!(pos.len(), vel.len());
+
+        for (pos, vel) in pos.iter_mut().zip(vel) {
+            *pos += vel;
+        }
+
+        visited.extend_from_slice(ids);
+    }
+
+    visited.sort();
+    assert_eq!(
+        visited,
+        ids.iter()
+            .enumerate()
+            .filter(|(i, _)| i % 4 != 0)
+            .map(|(_, &id)| id)
+            .collect_vec()
+    );
+
+    assert_eq!(world.get_copy(ids[3], position()), Ok(3.0));
+    assert_eq!(world.get_copy(ids[4], position()), Ok(0.0));
+
+    // Slices are split by filters
+    let mut moving = Query::new(position()).filter(position().gt(5.0));
+    let total = moving
+        .borrow(&world)
+        .chunks_mut()
+        .map(|pos: &[f32]| pos.iter().sum::<f32>())
+        .sum::<f32>();
+
+    assert_eq!(total, (6..16).filter(|i| i % 4 != 0).sum::<i32>() as f32);
+
+    // Mutation through slices is tracked
+    let mut changed = Query::new(entity_ids()).filter(position().modified());
+    changed.borrow(&world).for_each(|_| {});
+
+    for pos in Query::new(position().as_mut())
+        .with(frozen())
+        .borrow(&world)
+        .chunks_mut()
+    {
+        pos.fill(-1.0);
+    }
+
+    assert_eq!(
+        changed.collect_sorted_vec(&world),
+        ids.iter().step_by(4).copied().collect_vec()
+    );
+}
+
+#[test]
+fn query_join() {
+    use flax::{entity_ids, Entity};
+
+    component! {
+        health: f32,
+        regen: f32,
+        dead: (),
+    }
+
+    let mut world = World::new();
+
+    let ids = (0..6)
+        .map(|i| {
+            let mut builder = Entity::builder();
+            builder.set(health(), 10.0 * i as f32);
+            if i % 2 == 0 {
+                builder.set(regen(), i as f32);
+            }
+            if i == 4 {
+                builder.set_default(dead());
+            }
+            builder.spawn(&mut world)
+        })
+        .collect_vec();
+
+    let mut query = Query::new((entity_ids(), health().as_mut()));
+    let mut regens = Query::new(regen()).without(dead());
+
+    let mut regens = regens.borrow(&world);
+    let joined = query
+        .borrow(&world)
+        .join(&mut regens)
+        .map(|((id, health), regen)| {
+            *health += regen;
+            id
+        })
+        .sorted()
+        .collect_vec();
+
+    assert_eq!(joined, [ids[0], ids[2]]);
+
+    drop(regens);
+
+    assert_eq!(
+        ids.iter()
+            .map(|&id| world.get_copy(id, health()).unwrap())
+            .collect_vec(),
+        [0.0, 10.0, 22.0, 30.0, 40.0, 50.0]
+    );
+}
+
+#[test]
+#[should_panic]
+fn query_join_aliasing() {
+    component! {
+        health: f32,
+    }
+
+    let mut world = World::new();
+    EntityBuilder::new().set(health(), 1.0).spawn(&mut world);
+
+    let mut a = Query::new(health().as_mut());
+    let mut b = Query::new(health());
+
+    let mut b = b.borrow(&world);
+    for _ in a.borrow(&world).join(&mut b) {}
+}
+
+#[test]
+fn query_opt_or_shared_default() {
+    use flax::Entity;
+
+    component! {
+        health: f32,
+        table: Vec<u32>,
+    }
+
+    let mut world = World::new();
+
+    for i in 0..8 {
+        Entity::builder().set(health(), i as f32).spawn(&mut world);
+    }
+
+    Entity::builder()
+        .set(health(), 10.0)
+        .set(table(), vec![1])
+        .spawn(&mut world);
+
+    let mut query = Query::new(table().opt_or(vec![0; 1024]));
+    let mut borrow = query.borrow(&world);
+    let items = borrow.iter().collect_vec();
+
+    let defaults = items.iter().filter(|v| v.len() == 1024).collect_vec();
+    assert_eq!(defaults.len(), 8);
+
+    // The default is borrowed from the fetch rather than created for each entity
+    assert!(defaults.iter().all(|v| std::ptr::eq(**v, *defaults[0])));
+    assert_eq!(items.iter().filter(|v| ***v == [1]).count(), 1);
+}
+
+#[test]
+#[cfg(feature = "tracing")]
+fn query_traced() {
+    use std::sync::{Arc, Mutex};
+
+    use tracing::{field::Field, span::Attributes, Id, Subscriber};
+    use tracing_subscriber::{layer::Context, prelude::*, Layer};
+
+    component! {
+        health: f32,
+        armor: f32,
+    }
+
+    /// Records the `archetype` field of each created span
+    struct Record(Arc<Mutex<Vec<String>>>);
+
+    impl<S: Subscriber> Layer<S> for Record {
+        fn on_new_span(&self, attrs: &Attributes<'_>, _: &Id, _: Context<'_, S>) {
+            let mut visitor = |field: &Field, value: &dyn core::fmt::Debug| {
+                if field.name() == "archetype" {
+                    self.0.lock().unwrap().push(format!("{value:?}"));
+                }
+            };
+
+            attrs.record(&mut visitor);
+        }
+    }
+
+    let mut world = World::new();
+    EntityBuilder::new().set(health(), 50.0).spawn(&mut world);
+    EntityBuilder::new()
+        .set(health(), 75.0)
+        .set(armor(), 10.0)
+        .spawn(&mut world);
+
+    let spans = Arc::new(Mutex::new(Vec::new()));
+    let subscriber = tracing_subscriber::registry().with(Record(spans.clone()));
+
+    let mut query = Query::new(health().copied().traced("health"));
+    let items = tracing::subscriber::with_default(subscriber, || {
+        query
+            .borrow(&world)
+            .iter()
+            .sorted_by(f32::total_cmp)
+            .collect_vec()
+    });
+
+    assert_eq!(items, [50.0, 75.0]);
+
+    let spans = spans.lock().unwrap();
+    assert_eq!(spans.len(), 2);
+    assert!(spans
+        .iter()
+        .any(|v| v.contains("health") && !v.contains("armor")));
+    assert!(spans
+        .iter()
+        .any(|v| v.contains("health") && v.contains("armor")));
+}
+
+#[test]
+fn query_delta() {
+    use flax::fetch::delta;
+
+    component! {
+        position: f32,
+    }
+
+    let mut world = World::new();
+    let ids = (0..3)
+        .map(|i| {
+            EntityBuilder::new()
+                .set(position(), i as f32)
+                .spawn(&mut world)
+        })
+        .collect_vec();
+
+    let mut query = Query::new((entity_ids(), delta(position())));
+
+    for (_, mut pos) in &mut query.borrow(&world) {
+        assert_eq!(pos.prev(), None);
+        *pos.current_mut() += 10.0;
+    }
+
+    // Moves the last entity into the despawned slot
+    world.despawn(ids[0]).unwrap();
+
+    let items = query
+        .borrow(&world)
+        .iter()
+        .map(|(id, pos)| (id, pos.prev().copied(), *pos.current()))
+        .sorted_by_key(|v| v.0)
+        .collect_vec();
+
+    assert_eq!(
+        items,
+        [(ids[1], Some(11.0), 11.0), (ids[2], Some(12.0), 12.0)]
+    );
+
+    // Moving to another archetype discards the previous value
+    world.set(ids[1], name(), "a".into()).unwrap();
+
+    let items = query
+        .borrow(&world)
+        .iter()
+        .map(|(id, pos)| (id, pos.prev().copied()))
+        .sorted_by_key(|v| v.0)
+        .collect_vec();
+
+    assert_eq!(items, [(ids[1], None), (ids[2], Some(12.0))]);
+}
+
+#[test]
+fn query_iter_rev() {
+    component! {
+        health: f32,
+        armor: f32,
+    }
+
+    let mut world = World::new();
+
+    let ids = (0..6)
+        .map(|i| {
+            let mut builder = EntityBuilder::new();
+            builder.set(health(), i as f32);
+            if i % 2 == 0 {
+                builder.set(armor(), 1.0);
+            }
+            builder.spawn(&mut world)
+        })
+        .collect_vec();
+
+    let mut query = Query::new((entity_ids(), health().as_mut()));
+
+    let forward = query.borrow(&world).iter().map(|v| v.0).collect_vec();
+    let mut reversed = query.borrow(&world).iter_rev().map(|v| v.0).collect_vec();
+    reversed.reverse();
+
+    assert_eq!(forward, reversed);
+    assert_eq!(
+        forward.iter().sorted().collect_vec(),
+        ids.iter().collect_vec()
+    );
+
+    for (_, health) in query.borrow(&world).iter_rev() {
+        *health += 10.0;
+    }
+
+    assert_eq!(
+        Query::new(health().copied())
+            .borrow(&world)
+            .iter()
+            .sorted_by(f32::total_cmp)
+            .collect_vec(),
+        [10.0, 11.0, 12.0, 13.0, 14.0, 15.0]
+    );
+
+    // Filters are evaluated front to back within each archetype
+    let mut query = Query::new(entity_ids()).with(armor()).take(2);
+    let forward = query.borrow(&world).iter().collect_vec();
+    let reversed = query.borrow(&world).iter_rev().collect_vec();
+
+    assert_eq!(forward, [ids[0], ids[2]]);
+    assert_eq!(reversed, [ids[2], ids[0]]);
+
+    let mut query = Query::new(entity_ids()).with(armor()).take(1);
+    assert_eq!(query.borrow(&world).iter_rev().collect_vec(), [ids[0]]);
+}
+
+#[test]
+fn query_with_id() {
+    component! {
+        health: f32,
+        armor: f32,
+    }
+
+    let mut world = World::new();
+
+    let ids = (0..4)
+        .map(|i| {
+            let mut builder = EntityBuilder::new();
+            builder.set(health(), i as f32);
+            if i % 2 == 0 {
+                builder.set(armor(), 1.0);
+            }
+            builder.spawn(&mut world)
+        })
+        .collect_vec();
+
+    let mut query = Query::new(health().as_mut().with_id()).with(armor());
+    for (id, health) in &mut query.borrow(&world) {
+        assert!(id == ids[0] || id == ids[2]);
+        *health += 10.0;
+    }
+
+    let mut query = Query::new((health().copied(), armor().opt_or_default()).with_id());
+    let items = query
+        .borrow(&world)
+        .iter()
+        .map(|(id, (health, &armor))| (id, health, armor))
+        .sorted_by_key(|v| v.0)
+        .collect_vec();
+
+    assert_eq!(
+        items,
+        [
+            (ids[0], 10.0, 1.0),
+            (ids[1], 1.0, 0.0),
+            (ids[2], 12.0, 1.0),
+            (ids[3], 3.0, 0.0),
+        ]
+    );
+
+    let mut query = Query::new(health().modified().copied().with_id());
+    assert_eq!(query.borrow(&world).iter().count(), 4);
+
+    *world.get_mut(ids[3], health()).unwrap() = 5.0;
+    assert_eq!(query.collect_vec(&world), [(ids[3], 5.0)]);
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn world_par_update() {
+    component! {
+        position: f32,
+        velocity: f32,
+        frozen: (),
+    }
+
+    let mut world = World::new();
+
+    let ids = (0..1000)
+        .map(|i| {
+            let mut builder = EntityBuilder::new();
+            builder.set(position(), 0.0).set(velocity(), i as f32);
+            if i % 3 == 0 {
+                builder.tag(frozen());
+            }
+
+            builder.spawn(&mut world)
+        })
+        .collect_vec();
+
+    let mut modified = Query::new(entity_ids()).filter(velocity().modified());
+    assert_eq!(modified.borrow(&world).count(), 1000);
+
+    let velocities = ids
+        .iter()
+        .map(|&id| (id, *world.get(id, velocity()).unwrap()))
+        .collect::<std::collections::BTreeMap<_, _>>();
+
+    world.par_update(velocity(), |id, v| *v += velocities[&id]);
+
+    for (i, &id) in ids.iter().enumerate() {
+        assert_eq!(*world.get(id, velocity()).unwrap(), 2.0 * i as f32);
+    }
+
+    assert_eq!(
+        modified.collect_sorted_vec(&world),
+        ids.iter().copied().sorted().collect_vec()
+    );
+    assert_eq!(modified.borrow(&world).count(), 0);
+}
+
+#[test]
+fn query_entity_location() {
+    use flax::fetch::entity_location;
+
+    component! {
+        health: f32,
+        armor: f32,
+    }
+
+    let mut world = World::new();
+
+    let ids = (0..6)
+        .map(|i| {
+            let mut builder = EntityBuilder::new();
+            builder.set(health(), i as f32);
+            if i % 2 == 0 {
+                builder.set(armor(), 1.0);
+            }
+            builder.spawn(&mut world)
+        })
+        .collect_vec();
+
+    let mut query = Query::new((entity_ids(), entity_location(), health().copied()));
+
+    let locations = query.collect_vec(&world);
+    assert_eq!(locations.len(), 6);
+
+    for &(id, loc, value) in &locations {
+        assert_eq!(world.location(id).unwrap(), loc);
+        assert_eq!(
+            world.get_by_location(loc, health()).as_deref(),
+            Some(&value)
+        );
+    }
+
+    // Removing another entity from the archetype swaps the last entity into the freed slot
+    world.despawn(ids[0]).unwrap();
+
+    let moved = world.location(ids[4]).unwrap();
+    let (_, old, _) = locations.iter().find(|v| v.0 == ids[4]).unwrap();
+    assert_eq!(moved.arch_id(), old.arch_id());
+    assert_ne!(moved.slot(), old.slot());
+
+    let mut query = Query::new((entity_ids(), entity_location())).with(armor());
+    for (id, loc) in query.collect_vec(&world) {
+        assert_eq!(world.location(id).unwrap(), loc);
+    }
+}
+
+#[test]
+fn query_has() {
+    use flax::{components::child_of, fetch::has, Entity};
+
+    component! {
+        health: f32,
+        poisoned: (),
+    }
+
+    let mut world = World::new();
+
+    let root = world.spawn();
+    let a = Entity::builder().set(health(), 50.0).spawn(&mut world);
+    let b = Entity::builder()
+        .set(health(), 50.0)
+        .tag(poisoned())
+        .set(child_of(root), ())
+        .spawn(&mut world);
+    let c = Entity::builder().tag(poisoned()).spawn(&mut world);
+
+    // Does not restrict the matched archetypes
+    let mut query = Query::new((entity_ids(), has(poisoned()), has(child_of(root))));
+
+    assert_eq!(
+        query
+            .collect_vec(&world)
+            .into_iter()
+            .filter(|&(id, _, _)| id != root)
+            .sorted()
+            .collect_vec(),
+        [(a, false, false), (b, true, true), (c, true, false)]
+    );
+
+    // The component is not borrowed
+    let mut query = Query::new((poisoned().as_mut(), has(poisoned())));
+    assert_eq!(
+        query.borrow(&world).iter().map(|v| v.1).collect_vec(),
+        [true, true]
+    );
+
+    let mut query = Query::new(has(health()));
+    let mut borrow = query.borrow(&world);
+    assert_eq!(borrow.get(a), Ok(true));
+    assert_eq!(borrow.get(c), Ok(false));
+}
+
+#[test]
+fn query_iter_mut() {
+    use flax::Entity;
+
+    component! {
+        health: f32,
+        regen: f32,
+    }
+
+    let mut world = World::new();
+    let a = Entity::builder()
+        .set(health(), 50.0)
+        .set(regen(), 1.0)
+        .spawn(&mut world);
+    let b = Entity::builder().set(health(), 20.0).spawn(&mut world);
+
+    let mut query = Query::new((health().as_mut(), regen().opt_or(0.5)));
+    let mut borrow = query.borrow(&world);
+
+    for (health, regen) in borrow.iter_mut() {
+        *health += *regen;
+    }
+
+    // Equivalent to iterating `&mut borrow`
+    for (health, regen) in &mut borrow {
+        *health += *regen;
+    }
+
+    drop(borrow);
+
+    assert_eq!(world.get_copy(a, health()), Ok(52.0));
+    assert_eq!(world.get_copy(b, health()), Ok(21.0));
+}
+
+#[test]
+fn query_sample() {
+    use flax::{BatchSpawn, Entity};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    component! {
+        value: u32,
+        frozen: (),
+    }
+
+    let mut world = World::new();
+
+    let mut batch = BatchSpawn::new(32);
+    batch.set(value(), 0..32).unwrap();
+    batch.spawn(&mut world);
+
+    for v in 32..40 {
+        Entity::builder()
+            .set(value(), v)
+            .tag(frozen())
+            .spawn(&mut world);
+    }
+
+    let mut rng = StdRng::seed_from_u64(42);
+
+    // Fewer matches than requested
+    let mut query = Query::new(value().copied()).with(frozen());
+    assert_eq!(
+        query
+            .borrow(&world)
+            .sample(16, |bound| rng.gen_range(0..bound)),
+        (32..40).collect_vec()
+    );
+
+    // Every match is selected, across several archetypes
+    let mut query = Query::new(value().copied());
+    assert_eq!(
+        query
+            .borrow(&world)
+            .sample(64, |bound| rng.gen_range(0..bound)),
+        (0..40).collect_vec()
+    );
+
+    // Filters are respected, and items are yielded in iteration order
+    let mut query = Query::new(value().copied()).filter(value().lt(20));
+    for _ in 0..16 {
+        let sample = query
+            .borrow(&world)
+            .sample(5, |bound| rng.gen_range(0..bound));
+
+        assert_eq!(sample.len(), 5);
+        assert!(sample.iter().all(|&v| v < 20));
+        assert!(sample.windows(2).all(|v| v[0] < v[1]));
+    }
+
+    // Each entity is equally likely to be selected
+    let mut counts = [0; 40];
+    let mut query = Query::new(value().copied());
+    for _ in 0..4000 {
+        for v in query
+            .borrow(&world)
+            .sample(1, |bound| rng.gen_range(0..bound))
+        {
+            counts[v as usize] += 1;
+        }
+    }
+
+    assert!(counts.iter().all(|&v| (50..150).contains(&v)), "{counts:?}");
+
+    // Only the selected entities are modified
+    let mut modified = Query::new(entity_ids()).filter(value().modified());
+    modified.collect_vec(&world);
+
+    let mut query = Query::new(value().as_mut());
+    for v in query
+        .borrow(&world)
+        .sample(3, |bound| rng.gen_range(0..bound))
+    {
+        *v += 100;
+    }
+
+    let modified = modified.collect_vec(&world);
+    assert_eq!(modified.len(), 3);
+    assert!(modified
+        .iter()
+        .all(|id| world.get_copy(*id, value()).unwrap() >= 100));
+}
@@ -426,3 +426,301 @@ fn relations_mut() {
         ]
     );
 }
+
+#[test]
+fn relations_fetch() {
+    component! {
+        relationship(id): f32,
+    }
+
+    let mut world = World::new();
+
+    let id1 = Entity::builder()
+        .set(name(), "id1".into())
+        .spawn(&mut world);
+
+    let id2 = Entity::builder()
+        .set(relationship(id1), 1.0)
+        .spawn(&mut world);
+
+    let id3 = Entity::builder()
+        .set(relationship(id2), 2.0)
+        .set(relationship(id1), 3.0)
+        .spawn(&mut world);
+
+    // Only entities with at least one relation match
+    assert_eq!(
+        Query::new((entity_ids(), fetch::relations(relationship)))
+            .borrow(&world)
+            .iter()
+            .map(|(id, v)| (id, v.count()))
+            .collect_vec(),
+        [(id2, 1), (id3, 2)]
+    );
+}
+
+#[test]
+fn relation_index() {
+    let mut world = World::new();
+
+    let incoming = |world: &World, target| {
+        world
+            .incoming(child_of, target)
+            .unwrap()
+            .iter()
+            .copied()
+            .sorted()
+            .collect_vec()
+    };
+
+    let root = world.spawn();
+    let a = Entity::builder()
+        .set_default(child_of(root))
+        .spawn(&mut world);
+
+    assert!(world.incoming(child_of, root).is_none());
+
+    // Existing relations are indexed
+    world.enable_relation_index(child_of);
+    assert_eq!(incoming(&world, root), [a]);
+
+    let b = Entity::builder()
+        .set_default(child_of(root))
+        .set(name(), "b".into())
+        .spawn(&mut world);
+    let c = Entity::builder().set_default(child_of(a)).spawn(&mut world);
+
+    assert_eq!(incoming(&world, root), [a, b]);
+    assert_eq!(incoming(&world, a), [c]);
+    assert_eq!(incoming(&world, c), []);
+
+    // Moving between archetypes does not affect the index
+    world.set(a, name(), "a".into()).unwrap();
+    world.remove(b, name()).unwrap();
+    assert_eq!(incoming(&world, root), [a, b]);
+
+    world.remove(b, child_of(root)).unwrap();
+    assert_eq!(incoming(&world, root), [a]);
+
+    // `child_of` is exclusive, which replaces the previous target
+    world.set(c, child_of(b), ()).unwrap();
+    assert_eq!(incoming(&world, b), [c]);
+    assert_eq!(incoming(&world, a), []);
+
+    world.despawn(c).unwrap();
+    assert_eq!(incoming(&world, b), []);
+
+    // Despawning the target detaches the children
+    world.despawn(root).unwrap();
+    assert_eq!(incoming(&world, root), []);
+    assert!(world.is_alive(a));
+}
+
+#[test]
+fn nth_relation_source() {
+    component! {
+        likes(target): &'static str,
+    }
+
+    let mut world = World::new();
+
+    let target = world.spawn();
+    Entity::builder().set(likes(target), "a").spawn(&mut world);
+    let b = Entity::builder().set(likes(target), "b").spawn(&mut world);
+
+    Entity::builder()
+        .set(name(), "other".into())
+        .spawn(&mut world);
+
+    let mut query = Query::new((name().cloned(), likes.first_relation().source(b)));
+
+    assert_eq!(
+        query.borrow(&world).iter().collect_vec(),
+        [("other".to_string(), (target, &"b"))]
+    );
+}
+
+#[test]
+fn relations_mut_tuple() {
+    use flax::system::{AccessKind, System};
+
+    component! {
+        position: f32,
+        connected_to(id): f32,
+    }
+
+    let mut world = World::new();
+
+    let a = Entity::builder().set(position(), 0.0).spawn(&mut world);
+    let b = Entity::builder()
+        .set(position(), 1.0)
+        .set(connected_to(a), 0.0)
+        .spawn(&mut world);
+    let c = Entity::builder()
+        .set(position(), 3.0)
+        .set(connected_to(a), 0.0)
+        .set(connected_to(b), 0.0)
+        .spawn(&mut world);
+
+    let mut modified = Query::new(entity_ids()).filter(connected_to(a).modified());
+    assert_eq!(modified.collect_sorted_vec(&world), [b, c]);
+    assert_eq!(modified.collect_sorted_vec(&world), []);
+
+    let positions: std::collections::BTreeMap<_, _> = Query::new((entity_ids(), position()))
+        .borrow(&world)
+        .iter()
+        .map(|(id, &v)| (id, v))
+        .collect();
+
+    Query::new((position(), relations_like_mut(connected_to)))
+        .borrow(&world)
+        .for_each(|(pos, relations)| {
+            for (target, force) in relations {
+                *force = positions[&target] - *pos;
+            }
+        });
+
+    assert_eq!(world.get_copy(b, connected_to(a)), Ok(-1.0));
+    assert_eq!(world.get_copy(c, connected_to(a)), Ok(-3.0));
+    assert_eq!(world.get_copy(c, connected_to(b)), Ok(-2.0));
+
+    // Modifications are visible to queries which ran at the previous tick
+    assert_eq!(modified.collect_sorted_vec(&world), [b, c]);
+
+    let schedule = Schedule::builder()
+        .with_system(
+            System::builder()
+                .with_query(Query::new((position(), relations_like_mut(connected_to))))
+                .for_each(|_| {}),
+        )
+        .build();
+
+    let access = &schedule.describe(&world)[0];
+    let pairs = [connected_to(a).key(), connected_to(b).key()];
+    for key in pairs {
+        assert!(access.access().iter().any(|v| v.mutable
+            && matches!(v.kind, AccessKind::Archetype { component, .. } if component == key)));
+    }
+
+    assert!(access.access().iter().all(|v| match v.kind {
+        AccessKind::Archetype { component, .. } => v.mutable == pairs.contains(&component),
+        _ => true,
+    }));
+}
+
+#[test]
+fn validate_relations() {
+    component! {
+        likes(id): (),
+    }
+
+    let mut world = World::new();
+
+    let parent = Entity::builder().spawn(&mut world);
+    let child = Entity::builder()
+        .set_default(child_of(parent))
+        .spawn(&mut world);
+
+    let other = Entity::builder()
+        .set_default(likes(child))
+        .spawn(&mut world);
+
+    assert_eq!(world.validate_relations(), Ok(()));
+
+    world.despawn(parent).unwrap();
+    assert_eq!(world.validate_relations(), Ok(()));
+
+    // Relations are not checked when inserted, so a pair to an already despawned target dangles
+    world.set(other, child_of(parent), ()).unwrap();
+    assert_eq!(
+        world.validate_relations(),
+        Err(vec![child_of(parent).key()])
+    );
+
+    world.despawn(other).unwrap();
+    assert_eq!(world.validate_relations(), Ok(()));
+}
+
+#[test]
+fn relation_targets() {
+    component! {
+        likes(id): f32,
+    }
+
+    let mut world = World::new();
+
+    let parent = Entity::builder().spawn(&mut world);
+    let a = Entity::builder().spawn(&mut world);
+    let b = Entity::builder().spawn(&mut world);
+    let c = Entity::builder()
+        .set(likes(a), 0.5)
+        .set(likes(b), 1.0)
+        .set_default(child_of(parent))
+        .spawn(&mut world);
+
+    assert_eq!(world.relation_targets(c, likes).as_slice(), [a, b]);
+    assert_eq!(world.relation_targets(c, child_of).as_slice(), [parent]);
+    assert!(world.relation_targets(a, likes).is_empty());
+
+    world.despawn(a).unwrap();
+    assert_eq!(world.relation_targets(c, likes).as_slice(), [b]);
+    assert!(world.relation_targets(a, likes).is_empty());
+}
+
+#[test]
+fn observe_relations_changed() {
+    use flax::relation::RelationEvent;
+    use std::sync::{Arc, Mutex};
+
+    component! {
+        depends_on(id): (),
+        value: f32,
+    }
+
+    let mut world = World::new();
+
+    let a = Entity::builder().set(value(), 1.0).spawn(&mut world);
+    let b = Entity::builder().set(value(), 2.0).spawn(&mut world);
+
+    // Existing pairs are not reported
+    let c = Entity::builder().set(depends_on(a), ()).spawn(&mut world);
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    world.observe_relations_changed(depends_on, {
+        let events = events.clone();
+        move |event| events.lock().unwrap().push(event)
+    });
+
+    let take = || std::mem::take(&mut *events.lock().unwrap());
+
+    world.set(c, depends_on(b), ()).unwrap();
+    assert_eq!(
+        take(),
+        [RelationEvent::Linked {
+            subject: c,
+            object: b
+        }]
+    );
+
+    // Unrelated changes
+    world.set(c, value(), 3.0).unwrap();
+    world.set(a, child_of(b), ()).unwrap();
+    assert_eq!(take(), []);
+
+    // Despawning the target removes the relation
+    world.despawn(a).unwrap();
+    assert_eq!(
+        take(),
+        [RelationEvent::Unlinked {
+            subject: c,
+            object: a
+        }]
+    );
+
+    world.despawn(c).unwrap();
+    let events = take();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].subject(), c);
+    assert_eq!(events[0].object(), b);
+    assert!(matches!(events[0], RelationEvent::Unlinked { .. }));
+}
@@ -486,3 +486,576 @@ fn into_anyhow(v: flax::Error) -> anyhow::Error {
     #[cfg(feature = "std")]
     return anyhow::Error::new(v);
 }
+
+#[test]
+fn schedule_error_handling() {
+    use flax::system::ErrorAction;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    let mut world = World::new();
+
+    let failing = || {
+        System::builder()
+            .with_name("failing")
+            .build(|| -> anyhow::Result<()> { Err(anyhow::anyhow!("Failed")) })
+    };
+
+    let executed = Arc::new(AtomicUsize::new(0));
+    let counter = {
+        let executed = executed.clone();
+        move || {
+            let executed = executed.clone();
+            System::builder().build(move || {
+                executed.fetch_add(1, Ordering::Relaxed);
+            })
+        }
+    };
+
+    // Aborts by default
+    let mut schedule = Schedule::builder()
+        .with_system(failing())
+        .with_system(counter())
+        .build();
+
+    assert!(schedule.execute_seq(&mut world).is_err());
+    assert_eq!(executed.load(Ordering::Relaxed), 0);
+
+    let errors = Arc::new(AtomicUsize::new(0));
+    let mut schedule = Schedule::builder()
+        .with_system(failing().on_error({
+            let errors = errors.clone();
+            move |_| {
+                errors.fetch_add(1, Ordering::Relaxed);
+                ErrorAction::Continue
+            }
+        }))
+        .with_system(counter())
+        .build();
+
+    schedule.execute_seq(&mut world).unwrap();
+    assert_eq!(errors.load(Ordering::Relaxed), 1);
+    assert_eq!(executed.load(Ordering::Relaxed), 1);
+
+    // Succeeds on the third attempt
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let flaky = System::builder().build({
+        let attempts = attempts.clone();
+        move || -> anyhow::Result<()> {
+            if attempts.fetch_add(1, Ordering::Relaxed) < 2 {
+                anyhow::bail!("Not yet")
+            }
+
+            Ok(())
+        }
+    });
+
+    let mut schedule = Schedule::builder()
+        .with_system(flaky.on_error(|_| ErrorAction::Retry))
+        .with_system(counter())
+        .build();
+
+    schedule.execute_seq(&mut world).unwrap();
+    assert_eq!(attempts.load(Ordering::Relaxed), 3);
+    assert_eq!(executed.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn schedule_retry_limit() {
+    use flax::system::ErrorAction;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    let mut world = World::new();
+
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let failing = System::builder().with_cmd_mut().build({
+        let attempts = attempts.clone();
+        move |cmd: &mut CommandBuffer| -> anyhow::Result<()> {
+            cmd.spawn(Entity::builder().set(name(), "attempt".into()));
+            attempts.fetch_add(1, Ordering::Relaxed);
+            anyhow::bail!("Failed")
+        }
+    });
+
+    let mut schedule = Schedule::builder()
+        .with_system(failing.on_error(|_| ErrorAction::Retry).with_max_retries(3))
+        .build();
+
+    // Gives up once the limit is reached rather than retrying forever
+    assert!(schedule.execute_seq(&mut world).is_err());
+    assert_eq!(attempts.load(Ordering::Relaxed), 4);
+
+    // The commands of each failed attempt are discarded
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let flaky = System::builder().with_cmd_mut().build({
+        let attempts = attempts.clone();
+        move |cmd: &mut CommandBuffer| -> anyhow::Result<()> {
+            cmd.spawn(Entity::builder().set(name(), "attempt".into()));
+            if attempts.fetch_add(1, Ordering::Relaxed) < 2 {
+                anyhow::bail!("Not yet")
+            }
+
+            Ok(())
+        }
+    });
+
+    let mut world = World::new();
+    let mut schedule = Schedule::builder()
+        .with_system(flaky.on_error(|_| ErrorAction::Retry))
+        .build();
+
+    schedule.execute_seq(&mut world).unwrap();
+    assert_eq!(attempts.load(Ordering::Relaxed), 3);
+    assert_eq!(Query::new(name()).borrow(&world).iter().count(), 1);
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn schedule_par_command_order() {
+    let spawn = |label: &'static str| {
+        System::builder()
+            .with_name(label)
+            .with_cmd_mut()
+            .build(move |cmd: &mut CommandBuffer| {
+                cmd.spawn(Entity::builder().set(name(), label.into()));
+            })
+            .boxed()
+    };
+
+    let labels = ["a", "b", "c", "d", "e", "f", "g", "h"];
+
+    for _ in 0..16 {
+        let mut world = World::new();
+        let mut schedule = labels.iter().map(|&v| spawn(v)).collect::<Schedule>();
+
+        schedule.execute_par(&mut world).unwrap();
+
+        // Commands are applied in the order the systems were declared
+        assert_eq!(
+            Query::new(name()).borrow(&world).iter().collect_vec(),
+            labels
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn schedule_par_uneven() {
+    use flax::BatchSpawn;
+    use rayon::prelude::ParallelIterator;
+
+    component! {
+        position: f32,
+        velocity: f32,
+        counter: u32,
+        kind(id): (),
+    }
+
+    let mut world = World::new();
+
+    let kinds = (0..8).map(|_| world.spawn()).collect_vec();
+    for &kind_id in &kinds {
+        let mut batch = BatchSpawn::new(256);
+        batch.set(position(), (0..256).map(|_| 0.0)).unwrap();
+        batch.set(velocity(), (0..256).map(|v| v as f32)).unwrap();
+        batch.set(kind(kind_id), (0..256).map(|_| ())).unwrap();
+        batch.spawn(&mut world);
+    }
+
+    let counters = (0..4)
+        .map(|_| Entity::builder().set(counter(), 0).spawn(&mut world))
+        .collect_vec();
+
+    let integrate = System::builder()
+        .with_name("integrate")
+        .with_query(Query::new((position().as_mut(), velocity())))
+        .par_for_each(|(pos, vel)| *pos += *vel);
+
+    let damp = System::builder()
+        .with_name("damp")
+        .with_query(Query::new(velocity().as_mut()))
+        .build(|mut query: QueryBorrow<flax::fetch::Mutable<f32>>| {
+            query
+                .par_iter_batched()
+                .for_each(|batch| batch.for_each(|vel| *vel *= 0.5))
+        });
+
+    let count = System::builder()
+        .with_name("count")
+        .with_query(Query::new(counter().as_mut()))
+        .for_each(|v| *v += 1);
+
+    let mut schedule = Schedule::new()
+        .with_system(integrate)
+        .with_system(count)
+        .with_system(damp);
+
+    assert_eq!(
+        schedule.batch_info(&world).to_names(),
+        [vec!["integrate", "count"], vec!["damp"]]
+    );
+
+    for _ in 0..2 {
+        schedule.execute_par(&mut world).unwrap();
+    }
+
+    let expected = (0..256).map(|v| v as f32 * 1.5).collect_vec();
+    for &kind_id in &kinds {
+        let positions = Query::new(position().copied())
+            .with(kind(kind_id))
+            .collect_vec(&world);
+        assert_eq!(positions, expected);
+    }
+
+    for id in counters {
+        assert_eq!(world.get_copy(id, counter()), Ok(2));
+    }
+}
+
+#[test]
+fn schedule_describe() {
+    use flax::system::{Access, AccessKind};
+
+    component! {
+        health: f32,
+    }
+
+    let mut world = World::new();
+    Entity::builder().set(health(), 5.0).spawn(&mut world);
+
+    let schedule = Schedule::builder()
+        .with_system(
+            System::builder()
+                .with_name("regen")
+                .with_query(Query::new(health().as_mut()))
+                .for_each(|v| *v += 1.0),
+        )
+        .with_system(
+            System::builder()
+                .with_name("exclusive")
+                .with_world_mut()
+                .build(|_: &mut World| {}),
+        )
+        .build();
+
+    let desc = schedule.describe(&world);
+
+    assert_eq!(
+        desc.iter().map(|v| v.name()).collect_vec(),
+        ["regen", "exclusive"]
+    );
+
+    assert!(desc[0].access().iter().any(|v| v.mutable
+        && matches!(v.kind, AccessKind::Archetype { component, .. } if component == health().key())));
+    assert!(!desc[0].is_exclusive());
+
+    assert_eq!(
+        desc[1].access(),
+        [Access {
+            kind: AccessKind::World,
+            mutable: true
+        }]
+    );
+    assert!(desc[1].is_exclusive());
+}
+
+#[test]
+fn schedule_diagnostics() {
+    use flax::ScheduleDiagnostic;
+
+    component! {
+        health: f32,
+    }
+
+    let mut world = World::new();
+    Entity::builder().set(health(), 5.0).spawn(&mut world);
+
+    let schedule = Schedule::builder()
+        .with_system(
+            System::builder()
+                .with_name("regen")
+                .with_query(Query::new(health().as_mut()))
+                .for_each(|v| *v += 1.0),
+        )
+        .with_system(
+            System::builder()
+                .with_name("cleanup")
+                .with_world_mut()
+                .build(|_: &mut World| {}),
+        )
+        .build();
+
+    let diagnostics = schedule.diagnostics(&world);
+    assert_eq!(
+        diagnostics,
+        [ScheduleDiagnostic::Exclusive {
+            system: "cleanup".into()
+        }]
+    );
+
+    assert!(diagnostics[0].to_string().contains("\"cleanup\""));
+}
+
+#[test]
+fn schedule_would_conflict() {
+    component! {
+        transform: f32,
+        velocity: f32,
+    }
+
+    let mut world = World::new();
+    Entity::builder()
+        .set(transform(), 0.0)
+        .set(velocity(), 1.0)
+        .spawn(&mut world);
+    Entity::builder().set(transform(), 0.0).spawn(&mut world);
+
+    let schedule = Schedule::builder()
+        .with_system(
+            System::builder()
+                .with_name("physics")
+                .with_query(Query::new((transform().as_mut(), velocity())))
+                .for_each(|(t, v)| *t += *v),
+        )
+        .with_system(
+            System::builder()
+                .with_name("renderer")
+                .with_query(Query::new(transform()))
+                .for_each(|_| {}),
+        )
+        .build();
+
+    let access = |system: BoxedSystem| {
+        let mut access = Vec::new();
+        system.access(&world, &mut access);
+        access
+    };
+
+    let reader = System::builder()
+        .with_name("reader")
+        .with_query(Query::new(velocity()))
+        .for_each(|_| {})
+        .boxed();
+
+    assert_eq!(schedule.would_conflict(&access(reader), &world), None);
+
+    let writer = System::builder()
+        .with_name("writer")
+        .with_query(Query::new(transform().as_mut()))
+        .for_each(|_| {})
+        .boxed();
+
+    let report = schedule.would_conflict(&access(writer), &world).unwrap();
+
+    assert_eq!(report.systems().collect_vec(), ["physics", "renderer"]);
+    assert_eq!(
+        report.to_string(),
+        "System \"physics\" writes `transform` which the new system writes\n\
+         System \"renderer\" reads `transform` which the new system writes"
+    );
+}
+
+#[test]
+fn schedule_resources() {
+    struct Gravity(f32);
+    struct Frame(u32);
+
+    let mut world = World::new();
+
+    let increment = || {
+        System::builder()
+            .with_name("increment")
+            .with_res_mut::<Frame>()
+            .build(|frame: &mut Frame| frame.0 += 1)
+            .boxed()
+    };
+
+    let mut schedule = Schedule::builder()
+        .with_system(
+            System::builder()
+                .with_name("read_gravity")
+                .with_res::<Gravity>()
+                .build(|gravity: &Gravity| assert_eq!(gravity.0, 9.82)),
+        )
+        .with_system(
+            System::builder()
+                .with_name("read_gravity_2")
+                .with_res::<Gravity>()
+                .build(|gravity: &Gravity| assert_eq!(gravity.0, 9.82)),
+        )
+        .with_system(increment())
+        .with_system(increment())
+        .build();
+
+    assert!(schedule.insert_resource(Gravity(9.82)).is_none());
+    schedule.insert_resource(Frame(0));
+
+    assert_eq!(
+        schedule.batch_info(&world).to_names(),
+        [
+            vec!["read_gravity", "read_gravity_2", "increment"],
+            vec!["increment"],
+        ]
+    );
+
+    schedule.execute_seq(&mut world).unwrap();
+    #[cfg(feature = "rayon")]
+    schedule.execute_par(&mut world).unwrap();
+
+    let frames = if cfg!(feature = "rayon") { 4 } else { 2 };
+    assert_eq!(schedule.resources().get::<Frame>().unwrap().0, frames);
+    assert_eq!(
+        schedule.resources_mut().remove::<Frame>().map(|v| v.0),
+        Some(frames)
+    );
+}
+
+#[test]
+fn schedule_stop() {
+    use flax::system::SystemResult;
+
+    struct Paused(bool);
+    struct Frame(u32);
+
+    component! {
+        paused_at: u32,
+    }
+
+    let mut world = World::new();
+
+    let mut schedule = Schedule::builder()
+        .with_system(
+            System::builder()
+                .with_name("check_paused")
+                .with_res::<Paused>()
+                .with_res::<Frame>()
+                .with_cmd_mut()
+                .build(|paused: &Paused, frame: &Frame, cmd: &mut CommandBuffer| {
+                    if paused.0 {
+                        cmd.spawn(Entity::builder().set(paused_at(), frame.0));
+                        SystemResult::Stop
+                    } else {
+                        SystemResult::Continue
+                    }
+                }),
+        )
+        .with_system(
+            System::builder()
+                .with_name("step")
+                .with_res_mut::<Frame>()
+                .build(|frame: &mut Frame| -> anyhow::Result<()> {
+                    frame.0 += 1;
+                    Ok(())
+                }),
+        )
+        .build();
+
+    schedule.insert_resource(Paused(false));
+    schedule.insert_resource(Frame(0));
+
+    let frame = |schedule: &Schedule| schedule.resources().get::<Frame>().unwrap().0;
+
+    schedule.execute_seq(&mut world).unwrap();
+    assert_eq!(frame(&schedule), 1);
+
+    schedule.resources().get_mut::<Paused>().unwrap().0 = true;
+    schedule.execute_seq(&mut world).unwrap();
+    assert_eq!(frame(&schedule), 1);
+
+    // Commands of the stopping system are still applied
+    assert_eq!(Query::new(paused_at().copied()).collect_vec(&world), [1]);
+
+    #[cfg(feature = "rayon")]
+    {
+        schedule.execute_par(&mut world).unwrap();
+        assert_eq!(frame(&schedule), 1);
+
+        schedule.resources().get_mut::<Paused>().unwrap().0 = false;
+        schedule.execute_par(&mut world).unwrap();
+        assert_eq!(frame(&schedule), 2);
+    }
+}
+
+#[test]
+fn schedule_add_remove_system() {
+    struct Log(Vec<&'static str>);
+
+    component! {
+        transform: f32,
+        velocity: f32,
+    }
+
+    fn logger(name: &'static str) -> BoxedSystem {
+        System::builder()
+            .with_name(name)
+            .with_res_mut::<Log>()
+            .build(move |log: &mut Log| log.0.push(name))
+            .boxed()
+    }
+
+    let mut world = World::new();
+    Entity::builder()
+        .set(transform(), 0.0)
+        .set(velocity(), 1.0)
+        .spawn(&mut world);
+
+    let mut schedule = Schedule::builder()
+        .with_system(
+            System::builder()
+                .with_name("physics")
+                .with_query(Query::new((transform().as_mut(), velocity())))
+                .for_each(|(t, v)| *t += *v),
+        )
+        .with_system(logger("first"))
+        .build();
+
+    schedule.insert_resource(Log(Vec::new()));
+
+    let take_log =
+        |schedule: &Schedule| std::mem::take(&mut schedule.resources().get_mut::<Log>().unwrap().0);
+
+    #[cfg(feature = "rayon")]
+    schedule.execute_par(&mut world).unwrap();
+    #[cfg(not(feature = "rayon"))]
+    schedule.execute_seq(&mut world).unwrap();
+
+    assert_eq!(take_log(&schedule), ["first"]);
+
+    schedule.add_system(logger("plugin"));
+    schedule.add_system(
+        System::builder()
+            .with_name("renderer")
+            .with_query(Query::new(transform()))
+            .for_each(|_| {}),
+    );
+
+    assert_eq!(
+        schedule.batch_info(&world).to_names(),
+        [vec!["physics", "first"], vec!["plugin", "renderer"]]
+    );
+
+    #[cfg(feature = "rayon")]
+    schedule.execute_par(&mut world).unwrap();
+    #[cfg(not(feature = "rayon"))]
+    schedule.execute_seq(&mut world).unwrap();
+
+    assert_eq!(take_log(&schedule), ["first", "plugin"]);
+    assert_eq!(Query::new(transform().copied()).collect_vec(&world), [2.0]);
+
+    let removed = schedule.remove_system("first").unwrap();
+    assert_eq!(removed.name(), "first");
+    assert!(schedule.remove_system("first").is_none());
+
+    assert_eq!(
+        schedule.batch_info(&world).to_names(),
+        [vec!["physics", "plugin"], vec!["renderer"]]
+    );
+
+    schedule.execute_seq(&mut world).unwrap();
+    assert_eq!(take_log(&schedule), ["plugin"]);
+}
@@ -240,3 +240,95 @@ fn subscribe_filter() {
         ]
     );
 }
+
+#[test]
+fn observe() {
+    use std::sync::{Arc, Mutex};
+
+    let mut world = World::new();
+
+    let added = Arc::new(Mutex::new(Vec::new()));
+    let removed = Arc::new(Mutex::new(Vec::new()));
+
+    world.observe_added(a(), {
+        let added = added.clone();
+        move |id, &v| added.lock().unwrap().push((id, v))
+    });
+
+    world.observe_removed(a(), {
+        let removed = removed.clone();
+        move |id, &v| removed.lock().unwrap().push((id, v))
+    });
+
+    let id = Entity::builder().set(a(), 1.0).spawn(&mut world);
+    let id2 = Entity::builder().set(b(), 5).spawn(&mut world);
+
+    assert_eq!(*added.lock().unwrap(), [(id, 1.0)]);
+
+    // Modifications and unrelated components are not observed
+    world.set(id, a(), 2.0).unwrap();
+    world.set(id, b(), 3).unwrap();
+    world.set(id2, a(), 4.0).unwrap();
+    assert_eq!(*added.lock().unwrap(), [(id, 1.0), (id2, 4.0)]);
+
+    assert_eq!(world.remove(id, a()), Ok(2.0));
+    world.despawn(id2).unwrap();
+
+    assert_eq!(*removed.lock().unwrap(), [(id, 2.0), (id2, 4.0)]);
+    assert_eq!(added.lock().unwrap().len(), 2);
+}
+
+#[test]
+#[cfg(feature = "flume")]
+fn subscribe_touched() {
+    use flax::events::{Event, EventKind, EventSubscriber};
+    use itertools::Itertools;
+    use pretty_assertions::assert_eq;
+
+    let mut world = World::new();
+
+    let id = Entity::builder().set(a(), 1.5).spawn(&mut world);
+
+    let (tx, rx) = flume::unbounded::<Event>();
+    world.subscribe(tx.with_touched());
+
+    let (modified_tx, modified_rx) = flume::unbounded::<Event>();
+    world.subscribe(modified_tx.filter_event_kind(EventKind::Modified));
+
+    // Not sent unless opted in
+    let (all_tx, all_rx) = flume::unbounded::<Event>();
+    world.subscribe(all_tx);
+
+    world.entity_mut(id).unwrap().set_dedup(a(), 1.5);
+    world.update_dedup(id, a(), 1.5).unwrap();
+
+    let mut cmd = CommandBuffer::new();
+    cmd.set_dedup(id, a(), 1.5);
+    cmd.apply(&mut world).unwrap();
+
+    assert_eq!(
+        rx.drain().collect_vec(),
+        vec![Event::new(id, a().key(), EventKind::Touched); 3]
+    );
+    assert!(modified_rx.is_empty());
+    assert!(all_rx.is_empty());
+
+    world.entity_mut(id).unwrap().set_dedup(a(), 2.0);
+
+    assert_eq!(rx.drain().collect_vec(), [Event::modified(id, a().key())]);
+    assert_eq!(
+        modified_rx.drain().collect_vec(),
+        [Event::modified(id, a().key())]
+    );
+
+    // Touching does not affect change detection
+    let mut query = Query::new(a().modified().copied());
+    assert_eq!(query.collect_vec(&world), [2.0]);
+
+    world.update_dedup(id, a(), 2.0).unwrap();
+    assert!(query.collect_vec(&world).is_empty());
+    assert_eq!(
+        rx.drain().collect_vec(),
+        [Event::new(id, a().key(), EventKind::Touched)]
+    );
+}